@@ -65,7 +65,7 @@ pub(super) fn generate_tuples_impls() -> String {
             .collect::<Vec<_>>()
             .join(", ");
         let where_predicates = (0..n)
-            .map(|i| format!("T{}: Facet", i))
+            .map(|i| format!("T{}: Facet<'a>", i))
             .collect::<Vec<_>>()
             .join(",\n    ");
         let shape_list = (0..n)
@@ -75,7 +75,7 @@ pub(super) fn generate_tuples_impls() -> String {
 
         // Start impl block
         w!(
-            "unsafe impl<{}> Facet for {}
+            "unsafe impl<'a, {}> Facet<'a> for {}
 ",
             type_params,
             // Handle formatting of tuple types correctly
@@ -92,7 +92,7 @@ pub(super) fn generate_tuples_impls() -> String {
 
         // type_name function
         w!(
-            "        fn type_name<{}>(f: &mut fmt::Formatter, opts: TypeNameOpts) -> fmt::Result\n",
+            "        fn type_name<'a, {}>(f: &mut fmt::Formatter, opts: TypeNameOpts) -> fmt::Result\n",
             type_params
         );
         w!("        where\n");
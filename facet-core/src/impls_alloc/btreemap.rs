@@ -15,10 +15,10 @@ struct BTreeMapIterator<'mem, K> {
     keys: VecDeque<&'mem K>,
 }
 
-unsafe impl<K, V> Facet for BTreeMap<K, V>
+unsafe impl<'a, K, V> Facet<'a> for BTreeMap<K, V>
 where
-    K: Facet + core::cmp::Eq + core::cmp::Ord + 'static,
-    V: Facet + 'static,
+    K: Facet<'a> + core::cmp::Eq + core::cmp::Ord + 'static,
+    V: Facet<'a> + 'static,
 {
     const SHAPE: &'static crate::Shape = &const {
         Shape::builder()
@@ -125,6 +125,9 @@ where
                     .vtable(
                         &const {
                             MapVTable::builder()
+                                .init_in_place_with_capacity(|uninit, _capacity| unsafe {
+                                    uninit.put(Self::new())
+                                })
                                 .insert(|ptr, key, value| unsafe {
                                     let map = ptr.as_mut::<BTreeMap<K, V>>();
                                     let k = key.read::<K>();
@@ -5,7 +5,7 @@ use crate::{
     SmartPointerVTable, value_vtable,
 };
 
-unsafe impl<T: Facet> Facet for alloc::sync::Arc<T> {
+unsafe impl<'a, T: Facet<'a>> Facet<'a> for alloc::sync::Arc<T> {
     const SHAPE: &'static crate::Shape = &const {
         crate::Shape::builder()
             .id(ConstTypeId::of::<Self>())
@@ -44,7 +44,7 @@ unsafe impl<T: Facet> Facet for alloc::sync::Arc<T> {
     };
 }
 
-unsafe impl<T: Facet> Facet for alloc::sync::Weak<T> {
+unsafe impl<'a, T: Facet<'a>> Facet<'a> for alloc::sync::Weak<T> {
     const SHAPE: &'static crate::Shape = &const {
         crate::Shape::builder()
             .id(ConstTypeId::of::<Self>())
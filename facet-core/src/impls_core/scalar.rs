@@ -4,7 +4,7 @@ use core::alloc::Layout;
 use core::num::NonZero;
 use typeid::ConstTypeId;
 
-unsafe impl Facet for ConstTypeId {
+unsafe impl Facet<'_> for ConstTypeId {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
             .id(ConstTypeId::of::<ConstTypeId>())
@@ -19,7 +19,7 @@ unsafe impl Facet for ConstTypeId {
     };
 }
 
-unsafe impl Facet for () {
+unsafe impl Facet<'_> for () {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
             .id(ConstTypeId::of::<()>())
@@ -34,7 +34,7 @@ unsafe impl Facet for () {
     };
 }
 
-unsafe impl<T: ?Sized> Facet for core::marker::PhantomData<T> {
+unsafe impl<T: ?Sized> Facet<'_> for core::marker::PhantomData<T> {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
             .id(ConstTypeId::of::<Self>())
@@ -50,7 +50,7 @@ unsafe impl<T: ?Sized> Facet for core::marker::PhantomData<T> {
 }
 
 #[cfg(feature = "alloc")]
-unsafe impl Facet for alloc::string::String {
+unsafe impl Facet<'_> for alloc::string::String {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
             .id(ConstTypeId::of::<alloc::string::String>())
@@ -69,7 +69,7 @@ unsafe impl Facet for alloc::string::String {
     };
 }
 
-unsafe impl Facet for char {
+unsafe impl Facet<'_> for char {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
             .id(ConstTypeId::of::<char>())
@@ -84,7 +84,7 @@ unsafe impl Facet for char {
     };
 }
 
-unsafe impl Facet for &str {
+unsafe impl<'a> Facet<'a> for &'a str {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
             .id(ConstTypeId::of::<&str>())
@@ -100,7 +100,7 @@ unsafe impl Facet for &str {
 }
 
 #[cfg(feature = "alloc")]
-unsafe impl Facet for alloc::borrow::Cow<'_, str> {
+unsafe impl<'a> Facet<'a> for alloc::borrow::Cow<'a, str> {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
             .id(ConstTypeId::of::<alloc::borrow::Cow<'_, str>>())
@@ -118,7 +118,7 @@ unsafe impl Facet for alloc::borrow::Cow<'_, str> {
     };
 }
 
-unsafe impl Facet for bool {
+unsafe impl Facet<'_> for bool {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
             .id(ConstTypeId::of::<bool>())
@@ -135,7 +135,7 @@ unsafe impl Facet for bool {
 
 macro_rules! impl_facet_for_integer {
     ($type:ty, $affinity:expr, $nz_affinity:expr) => {
-        unsafe impl Facet for $type {
+        unsafe impl Facet<'_> for $type {
             const SHAPE: &'static Shape = &const {
                 Shape::builder()
                     .id(ConstTypeId::of::<Self>())
@@ -151,7 +151,7 @@ macro_rules! impl_facet_for_integer {
             };
         }
 
-        unsafe impl Facet for NonZero<$type> {
+        unsafe impl Facet<'_> for NonZero<$type> {
             const SHAPE: &'static Shape = &const {
                 Shape::builder()
                     .id(ConstTypeId::of::<Self>())
@@ -418,7 +418,7 @@ static POSITIVE_ZERO_F64: f64 = 0.0f64;
 static NEGATIVE_ZERO_F64: f64 = -0.0f64;
 static EPSILON_F64: f64 = f64::EPSILON;
 
-unsafe impl Facet for f32 {
+unsafe impl Facet<'_> for f32 {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
             .id(ConstTypeId::of::<f32>())
@@ -445,7 +445,7 @@ unsafe impl Facet for f32 {
     };
 }
 
-unsafe impl Facet for f64 {
+unsafe impl Facet<'_> for f64 {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
             .id(ConstTypeId::of::<f64>())
@@ -472,7 +472,7 @@ unsafe impl Facet for f64 {
     };
 }
 
-unsafe impl Facet for core::net::SocketAddr {
+unsafe impl Facet<'_> for core::net::SocketAddr {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
             .id(ConstTypeId::of::<Self>())
@@ -490,7 +490,7 @@ unsafe impl Facet for core::net::SocketAddr {
     };
 }
 
-unsafe impl Facet for core::net::IpAddr {
+unsafe impl Facet<'_> for core::net::IpAddr {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
             .id(ConstTypeId::of::<Self>())
@@ -508,7 +508,7 @@ unsafe impl Facet for core::net::IpAddr {
     };
 }
 
-unsafe impl Facet for core::net::Ipv4Addr {
+unsafe impl Facet<'_> for core::net::Ipv4Addr {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
             .id(ConstTypeId::of::<Self>())
@@ -526,7 +526,7 @@ unsafe impl Facet for core::net::Ipv4Addr {
     };
 }
 
-unsafe impl Facet for core::net::Ipv6Addr {
+unsafe impl Facet<'_> for core::net::Ipv6Addr {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
             .id(ConstTypeId::of::<Self>())
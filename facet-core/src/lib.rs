@@ -46,12 +46,20 @@ pub use types::*;
 /// all the serializers, deserializers, the entire ecosystem is unsafe.
 ///
 /// You're responsible for describing the type layout properly, and annotating all the invariants.
-pub unsafe trait Facet: Sized {
+///
+/// # Lifetime
+///
+/// The `'a` lifetime is the lifetime of the data a value of this type borrows from,
+/// which is what lets deserializers hand out `&'a str` slices of their input.
+/// Types that don't borrow anything implement `Facet<'a>` for every `'a`. Types that
+/// do (like `&'a str`, or a struct with a lifetime parameter) implement it for their
+/// own lifetime only.
+pub unsafe trait Facet<'a>: Sized {
     /// The shape of this type
     const SHAPE: &'static Shape;
 
     /// Returns true if the type of `self` is equal to the type of `other`
-    fn type_eq<Other: Facet>() -> bool {
+    fn type_eq<Other: Facet<'a>>() -> bool {
         Self::SHAPE == Other::SHAPE
     }
 }
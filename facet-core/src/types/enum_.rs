@@ -1,4 +1,4 @@
-use super::Struct;
+use super::{Def, Field, Struct, StructKind};
 
 /// Fields for enum types
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
@@ -56,6 +56,43 @@ impl EnumDefBuilder {
     }
 }
 
+/// How the variant of an enum is represented by self-describing formats,
+/// selected with container attributes on the enum
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
+pub enum EnumTagging {
+    /// `{"Variant": payload}`, or just `"Variant"` for unit variants. This is the default.
+    External,
+    /// `{"type": "Variant", ...fields}`, selected with `#[facet(tag = "type")]`
+    Internal {
+        /// Name of the field holding the variant name
+        tag: &'static str,
+    },
+    /// `{"t": "Variant", "c": payload}`, selected with `#[facet(tag = "t", content = "c")]`
+    Adjacent {
+        /// Name of the field holding the variant name
+        tag: &'static str,
+        /// Name of the field holding the payload
+        content: &'static str,
+    },
+    /// Just the payload, with no variant name, selected with `#[facet(untagged)]`.
+    /// Deserializers pick the first variant that matches.
+    Untagged,
+}
+
+/// What an internally tagged variant writes next to its tag, see
+/// [`Variant::internally_tagged_fields`]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
+pub enum TaggedFields {
+    /// Nothing: the variant is a unit variant
+    Unit,
+    /// The fields of a struct variant
+    Struct(&'static [Field]),
+    /// The fields of the struct wrapped by a newtype variant, as its field 0
+    Newtype(&'static [Field]),
+}
+
 /// Describes a variant of an enum
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 #[repr(C)]
@@ -81,6 +118,24 @@ impl Variant {
     pub const fn builder() -> VariantBuilder {
         VariantBuilder::new()
     }
+
+    /// Returns the fields written next to the tag when the enum is internally
+    /// tagged, or `None` if the variant can't be (e.g. a tuple variant)
+    pub fn internally_tagged_fields(&self) -> Option<TaggedFields> {
+        match self.data.kind {
+            StructKind::Unit => Some(TaggedFields::Unit),
+            StructKind::Struct => Some(TaggedFields::Struct(self.data.fields)),
+            _ => match self.data.fields {
+                [field] => match field.shape().def {
+                    Def::Struct(sd) if sd.kind == StructKind::Struct => {
+                        Some(TaggedFields::Newtype(sd.fields))
+                    }
+                    _ => None,
+                },
+                _ => None,
+            },
+        }
+    }
 }
 
 /// Builder for Variant
@@ -67,17 +67,46 @@ pub struct Shape {
     /// into a map or fetching a value from a list.
     pub def: Def,
 
+    /// Container attributes set via the derive macro, like `#[facet(tag = "type")]`
+    pub attributes: &'static [ShapeAttribute],
+
     /// Doc comment lines, collected by facet-derive. Note that they tend to
     /// start with a space.
     pub doc: &'static [&'static str],
 }
 
+/// An attribute that can be set on a container (struct or enum)
+#[non_exhaustive]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[repr(C)]
+pub enum ShapeAttribute {
+    /// Name of the field holding the variant name, for internally
+    /// or adjacently tagged enums: `#[facet(tag = "type")]`
+    Tag(&'static str),
+    /// Name of the field holding the variant payload, for adjacently
+    /// tagged enums: `#[facet(tag = "t", content = "c")]`
+    Content(&'static str),
+    /// The enum is represented by its payload alone: `#[facet(untagged)]`
+    Untagged,
+    /// The struct, or the struct variants of the enum, are written as arrays of
+    /// their field values in declaration order instead of maps keyed by field
+    /// name, by formats that support it: `#[facet(as_array)]`
+    AsArray,
+    /// Deserializers reject keys that aren't fields of the struct (or of the
+    /// struct variants of the enum), instead of skipping them:
+    /// `#[facet(deny_unknown_fields)]`
+    DenyUnknownFields,
+    /// Custom container attribute containing arbitrary text
+    Arbitrary(&'static str),
+}
+
 /// Builder for [`Shape`]
 pub struct ShapeBuilder {
     id: Option<ConstTypeId>,
     layout: Option<Layout>,
     vtable: Option<&'static ValueVTable>,
     def: Option<Def>,
+    attributes: &'static [ShapeAttribute],
     doc: &'static [&'static str],
 }
 
@@ -88,14 +117,14 @@ impl Shape {
     }
 
     /// Check if this shape is of the given type
-    pub fn is_type<Other: Facet>(&'static self) -> bool {
+    pub fn is_type<'a, Other: Facet<'a>>(&'static self) -> bool {
         let l = self;
         let r = Other::SHAPE;
         l == r
     }
 
     /// Assert that this shape is of the given type, panicking if it's not
-    pub fn assert_type<Other: Facet>(&'static self) {
+    pub fn assert_type<'a, Other: Facet<'a>>(&'static self) {
         assert!(
             self.is_type::<Other>(),
             "Type mismatch: expected {}, found {self}",
@@ -113,6 +142,7 @@ impl ShapeBuilder {
             layout: None,
             vtable: None,
             def: None,
+            attributes: &[],
             doc: &[],
         }
     }
@@ -145,6 +175,13 @@ impl ShapeBuilder {
         self
     }
 
+    /// Sets the `attributes` field of the `ShapeBuilder`.
+    #[inline]
+    pub const fn attributes(mut self, attributes: &'static [ShapeAttribute]) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
     /// Sets the `doc` field of the `ShapeBuilder`.
     #[inline]
     pub const fn doc(mut self, doc: &'static [&'static str]) -> Self {
@@ -164,6 +201,7 @@ impl ShapeBuilder {
             layout: self.layout.unwrap(),
             vtable: self.vtable.unwrap(),
             def: self.def.unwrap(),
+            attributes: self.attributes,
             doc: self.doc,
         }
     }
@@ -185,6 +223,41 @@ impl core::hash::Hash for Shape {
 }
 
 impl Shape {
+    /// Returns how the variants of this enum should be tagged when serialized,
+    /// according to its container attributes. Defaults to [`EnumTagging::External`].
+    pub fn enum_tagging(&self) -> EnumTagging {
+        let mut tag = None;
+        let mut content = None;
+        for attr in self.attributes {
+            match attr {
+                ShapeAttribute::Untagged => return EnumTagging::Untagged,
+                ShapeAttribute::Tag(t) => tag = Some(*t),
+                ShapeAttribute::Content(c) => content = Some(*c),
+                ShapeAttribute::AsArray
+                | ShapeAttribute::DenyUnknownFields
+                | ShapeAttribute::Arbitrary(_) => {}
+            }
+        }
+        match (tag, content) {
+            (Some(tag), Some(content)) => EnumTagging::Adjacent { tag, content },
+            (Some(tag), None) => EnumTagging::Internal { tag },
+            _ => EnumTagging::External,
+        }
+    }
+
+    /// Whether this struct, or the struct variants of this enum, should be
+    /// written as arrays of field values, per `#[facet(as_array)]`
+    pub fn is_as_array(&self) -> bool {
+        self.attributes.contains(&ShapeAttribute::AsArray)
+    }
+
+    /// Whether deserializers should reject keys that aren't fields of this
+    /// struct (or of the struct variants of this enum), per
+    /// `#[facet(deny_unknown_fields)]`
+    pub fn denies_unknown_fields(&self) -> bool {
+        self.attributes.contains(&ShapeAttribute::DenyUnknownFields)
+    }
+
     /// Check if this shape is of the given type
     pub fn is_shape(&'static self, other: &'static Shape) -> bool {
         self == other
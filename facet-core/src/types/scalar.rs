@@ -711,6 +711,10 @@ pub struct TimeAffinity {
     /// store a copy of the epoch for a lack of timezone support, and None for "it's more
     /// complicated than that".
     timezone_granularity: Option<OpaqueConst<'static>>,
+
+    /// Whether values name a single instant, the same everywhere, like UTC or offset
+    /// datetimes do, as opposed to local dates, times and datetimes
+    absolute: bool,
 }
 
 impl TimeAffinity {
@@ -718,6 +722,11 @@ impl TimeAffinity {
     pub const fn builder() -> TimeAffinityBuilder {
         TimeAffinityBuilder::new()
     }
+
+    /// Whether values name a single instant, the same everywhere
+    pub const fn is_absolute(&self) -> bool {
+        self.absolute
+    }
 }
 
 /// Builder for UuidAffinity
@@ -729,6 +738,7 @@ pub struct TimeAffinityBuilder {
     granularity: Option<OpaqueConst<'static>>,
     interval_elements: Option<&'static [OpaqueConst<'static>]>,
     timezone_granularity: Option<OpaqueConst<'static>>,
+    absolute: bool,
 }
 
 impl TimeAffinityBuilder {
@@ -742,6 +752,7 @@ impl TimeAffinityBuilder {
             granularity: None,
             interval_elements: None,
             timezone_granularity: None,
+            absolute: false,
         }
     }
 
@@ -787,6 +798,12 @@ impl TimeAffinityBuilder {
         self
     }
 
+    /// Marks values as absolute instants, the same everywhere, like UTC or offset datetimes
+    pub const fn absolute(mut self) -> Self {
+        self.absolute = true;
+        self
+    }
+
     /// Builds the ScalarAffinity
     pub const fn build(self) -> ScalarAffinity {
         ScalarAffinity::Time(TimeAffinity {
@@ -796,6 +813,7 @@ impl TimeAffinityBuilder {
             granularity: self.granularity,
             interval_elements: self.interval_elements,
             timezone_granularity: self.timezone_granularity,
+            absolute: self.absolute,
         })
     }
 }
@@ -0,0 +1,20 @@
+use facet_core::{ScalarAffinity, TimeAffinity};
+
+fn time(affinity: ScalarAffinity) -> TimeAffinity {
+    match affinity {
+        ScalarAffinity::Time(time) => time,
+        other => panic!("expected a time affinity, got {:?}", other),
+    }
+}
+
+#[test]
+fn time_affinity_is_local_by_default() {
+    let affinity = time(TimeAffinity::builder().build());
+    assert!(!affinity.is_absolute());
+}
+
+#[test]
+fn time_affinity_absolute() {
+    let affinity = time(TimeAffinity::builder().absolute().build());
+    assert!(affinity.is_absolute());
+}
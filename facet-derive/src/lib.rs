@@ -20,6 +20,11 @@ keyword! {
     KFacet = "facet";
     KSensitive = "sensitive";
     KInvariants = "invariants";
+    KTag = "tag";
+    KContent = "content";
+    KUntagged = "untagged";
+    KAsArray = "as_array";
+    KDenyUnknownFields = "deny_unknown_fields";
 }
 
 operator! {
@@ -64,15 +69,32 @@ unsynn! {
 
     struct FacetAttr {
         _facet: KFacet,
-        inner: ParenthesisGroupContaining<FacetInner>,
+        inner: ParenthesisGroupContaining<CommaDelimitedVec<FacetInner>>,
     }
 
     enum FacetInner {
         Sensitive(KSensitive),
         Invariants(InvariantInner),
+        Tag(TagInner),
+        Content(ContentInner),
+        Untagged(KUntagged),
+        AsArray(KAsArray),
+        DenyUnknownFields(KDenyUnknownFields),
         Other(Vec<TokenTree>)
     }
 
+    struct TagInner {
+        _kw_tag: KTag,
+        _eq: Eq,
+        value: LiteralString,
+    }
+
+    struct ContentInner {
+        _kw_content: KContent,
+        _eq: Eq,
+        value: LiteralString,
+    }
+
     struct InvariantInner {
         _kw_invariants: KInvariants,
         _eq: Eq,
@@ -217,6 +239,39 @@ unsynn! {
 ///
 /// This uses unsynn, so it's light, but it _will_ choke on some Rust syntax because...
 /// there's a lot of Rust syntax.
+///
+/// Adjacently tagged enums take `tag` and `content`, in any order, in one
+/// `#[facet(...)]` attribute or in separate ones:
+///
+/// ```
+/// use facet::{EnumTagging, Facet};
+///
+/// #[derive(Facet)]
+/// #[repr(u8)]
+/// #[facet(content = "c")]
+/// #[facet(tag = "t")]
+/// enum Message {
+///     Ping(u32),
+/// }
+///
+/// assert_eq!(
+///     Message::SHAPE.enum_tagging(),
+///     EnumTagging::Adjacent { tag: "t", content: "c" }
+/// );
+/// ```
+///
+/// `content` without `tag` is an error:
+///
+/// ```compile_fail
+/// use facet::Facet;
+///
+/// #[derive(Facet)]
+/// #[repr(u8)]
+/// #[facet(content = "c")]
+/// enum Message {
+///     Ping(u32),
+/// }
+/// ```
 #[proc_macro_derive(Facet, attributes(facet))]
 pub fn facet_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = TokenStream::from(input);
@@ -352,6 +407,74 @@ pub(crate) fn build_maybe_doc(attrs: &[Attribute]) -> String {
     }
 }
 
+impl FacetAttr {
+    /// The comma-separated items of `#[facet(...)]`
+    pub(crate) fn items(&self) -> impl Iterator<Item = &FacetInner> {
+        self.inner.content.0.iter().map(|item| &item.value)
+    }
+}
+
+/// Collects the container attributes (tagging, `as_array`, arbitrary ones) into a
+/// `.attributes(&[...])` call for the shape builder. `tag` and `content` may come
+/// in any order, and from separate `#[facet(...)]` attributes.
+///
+/// Returns the message for a `compile_error!` when `content` is given without `tag`.
+pub(crate) fn build_container_attributes(
+    attrs: &[Attribute],
+) -> core::result::Result<String, &'static str> {
+    let mut attribute_list: Vec<String> = vec![];
+    let mut tag = None;
+    let mut content = None;
+    for attr in attrs {
+        let AttributeInner::Facet(facet_attr) = &attr.body.content else {
+            continue;
+        };
+        for item in facet_attr.items() {
+            match item {
+                FacetInner::Tag(tag_inner) => tag = Some(tag_inner.value.value()),
+                FacetInner::Content(content_inner) => content = Some(content_inner.value.value()),
+                FacetInner::Untagged(_) => {
+                    attribute_list.push("::facet::ShapeAttribute::Untagged".to_string());
+                }
+                FacetInner::AsArray(_) => {
+                    attribute_list.push("::facet::ShapeAttribute::AsArray".to_string());
+                }
+                FacetInner::DenyUnknownFields(_) => {
+                    attribute_list.push("::facet::ShapeAttribute::DenyUnknownFields".to_string());
+                }
+                FacetInner::Other(tt) => {
+                    attribute_list.push(format!(
+                        r#"::facet::ShapeAttribute::Arbitrary({:?})"#,
+                        tt.tokens_to_string()
+                    ));
+                }
+                FacetInner::Sensitive(_) | FacetInner::Invariants(_) => {}
+            }
+        }
+    }
+
+    match (tag, content) {
+        (Some(tag), content) => {
+            attribute_list.push(format!("::facet::ShapeAttribute::Tag({})", tag));
+            if let Some(content) = content {
+                attribute_list.push(format!("::facet::ShapeAttribute::Content({})", content));
+            }
+        }
+        (None, Some(_)) => {
+            return Err(
+                "#[facet(content = \"...\")] needs a #[facet(tag = \"...\")] for the variant name",
+            );
+        }
+        (None, None) => {}
+    }
+
+    if attribute_list.is_empty() {
+        Ok(String::new())
+    } else {
+        Ok(format!(".attributes(&[{}])", attribute_list.join(",")))
+    }
+}
+
 pub(crate) fn gen_struct_field(
     field_name: &str,
     struct_name: &str,
@@ -364,21 +487,34 @@ pub(crate) fn gen_struct_field(
     let mut doc_lines: Vec<&str> = vec![];
     for attr in attrs {
         match &attr.body.content {
-            AttributeInner::Facet(facet_attr) => match &facet_attr.inner.content {
-                FacetInner::Sensitive(_ksensitive) => {
-                    flags = "::facet::FieldFlags::SENSITIVE";
-                    attribute_list.push("::facet::FieldAttribute::Sensitive".to_string());
+            AttributeInner::Facet(facet_attr) => {
+                for item in facet_attr.items() {
+                    match item {
+                        FacetInner::Sensitive(_ksensitive) => {
+                            flags = "::facet::FieldFlags::SENSITIVE";
+                            attribute_list.push("::facet::FieldAttribute::Sensitive".to_string());
+                        }
+                        FacetInner::Invariants(_invariant_inner) => {
+                            panic!("fields cannot have invariants")
+                        }
+                        FacetInner::Tag(_) | FacetInner::Content(_) | FacetInner::Untagged(_) => {
+                            panic!("enum tagging attributes go on the enum, not on fields")
+                        }
+                        FacetInner::AsArray(_) => {
+                            panic!("as_array goes on the struct or enum, not on fields")
+                        }
+                        FacetInner::DenyUnknownFields(_) => {
+                            panic!("deny_unknown_fields goes on the struct or enum, not on fields")
+                        }
+                        FacetInner::Other(tt) => {
+                            attribute_list.push(format!(
+                                r#"::facet::FieldAttribute::Arbitrary({:?})"#,
+                                tt.tokens_to_string()
+                            ));
+                        }
+                    }
                 }
-                FacetInner::Invariants(_invariant_inner) => {
-                    panic!("fields cannot have invariants")
-                }
-                FacetInner::Other(tt) => {
-                    attribute_list.push(format!(
-                        r#"::facet::FieldAttribute::Arbitrary({:?})"#,
-                        tt.tokens_to_string()
-                    ));
-                }
-            },
+            }
             AttributeInner::Doc(doc_inner) => doc_lines.push(doc_inner.value.value()),
             AttributeInner::Repr(_) => {
                 // muffin
@@ -409,6 +545,44 @@ pub(crate) fn gen_struct_field(
     )
 }
 
+/// Generic parameters and where clauses for the `Facet<'__facet>` impl: everything
+/// the type declares, plus the `'__facet` lifetime, which has to be equal to every
+/// lifetime parameter of the type so that borrowed data can't outlive its source.
+pub(crate) fn facet_impl_generics(
+    generics: Option<&GenericParams>,
+    generics_def: &str,
+    where_clauses: &str,
+) -> (String, String) {
+    let impl_generics = if generics_def.is_empty() {
+        "'__facet".to_string()
+    } else {
+        format!("'__facet, {generics_def}")
+    };
+
+    let lifetime_bounds = generics
+        .map(|generics| {
+            generics
+                .params
+                .0
+                .iter()
+                .filter_map(|param| match &param.value {
+                    GenericParam::Lifetime { name, .. } => {
+                        Some(format!("{name}: '__facet, '__facet: {name},"))
+                    }
+                    _ => None,
+                })
+                .collect::<String>()
+        })
+        .unwrap_or_default();
+
+    let where_clauses = match (where_clauses.is_empty(), lifetime_bounds.is_empty()) {
+        (_, true) => where_clauses.to_string(),
+        (true, false) => format!("where {lifetime_bounds}"),
+        (false, false) => format!("{where_clauses} {lifetime_bounds}"),
+    };
+    (impl_generics, where_clauses)
+}
+
 fn generics_split_for_impl(generics: Option<&GenericParams>) -> (String, String) {
     let Some(generics) = generics else {
         return ("".to_string(), "".to_string());
@@ -75,6 +75,8 @@ pub(crate) fn process_enum(parsed: Enum) -> proc_macro::TokenStream {
         .clauses
         .as_ref()
         .map_or(String::new(), ToString::to_string);
+    let (impl_generics, impl_where_clauses) =
+        facet_impl_generics(parsed.generics.as_ref(), &generics_def, &where_clauses);
 
     // collect all `#repr(..)` attrs
     // either multiple attrs, or a single attr with multiple values
@@ -169,6 +171,14 @@ pub(crate) fn process_enum(parsed: Enum) -> proc_macro::TokenStream {
         String::new()
     };
     let maybe_container_doc = build_maybe_doc(&parsed.attributes);
+    let maybe_container_attributes = match build_container_attributes(&parsed.attributes) {
+        Ok(attributes) => attributes,
+        Err(message) => {
+            return format!("compile_error!({:?});", message)
+                .into_token_stream()
+                .into();
+        }
+    };
 
     // Generate the impl
     let output = format!(
@@ -176,7 +186,7 @@ pub(crate) fn process_enum(parsed: Enum) -> proc_macro::TokenStream {
 {static_decl}
 
 #[automatically_derived]
-unsafe impl<{generics_def}> ::facet::Facet for {enum_name}<{generics_use}> {where_clauses} {{
+unsafe impl<{impl_generics}> ::facet::Facet<'__facet> for {enum_name}<{generics_use}> {impl_where_clauses} {{
     const SHAPE: &'static ::facet::Shape = &const {{
         // Define all shadow structs at the beginning of the const block
         // to ensure they're in scope for offset_of! macros
@@ -195,6 +205,7 @@ unsafe impl<{generics_def}> ::facet::Facet for {enum_name}<{generics_use}> {wher
                 .variants(&const {{[ {variants} ]}})
                 .repr(::facet::EnumRepr::{repr_type})
                 .build()))
+            {maybe_container_attributes}
             {maybe_container_doc}
             .build()
     }};
@@ -73,17 +73,29 @@ pub(crate) fn process_struct(parsed: Struct) -> proc_macro::TokenStream {
         String::new()
     };
     let maybe_container_doc = build_maybe_doc(&parsed.attributes);
+    let maybe_container_attributes = match build_container_attributes(&parsed.attributes) {
+        Ok(attributes) => attributes,
+        Err(message) => {
+            return format!("compile_error!({:?});", message)
+                .into_token_stream()
+                .into();
+        }
+    };
     let where_clauses = where_clauses.map_or(String::new(), ToString::to_string);
+    let (impl_generics, impl_where_clauses) =
+        facet_impl_generics(parsed.generics.as_ref(), &generics_def, &where_clauses);
 
     let mut invariant_maybe = "".to_string();
     let invariant_attrs = parsed
         .attributes
         .iter()
         .filter_map(|attr| match &attr.body.content {
-            AttributeInner::Facet(facet_attr) => match &facet_attr.inner.content {
-                FacetInner::Invariants(invariant_inner) => Some(invariant_inner),
-                _ => None,
-            },
+            AttributeInner::Facet(facet_attr) => Some(facet_attr.items()),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|item| match item {
+            FacetInner::Invariants(invariant_inner) => Some(invariant_inner),
             _ => None,
         })
         .collect::<Vec<_>>();
@@ -129,7 +141,7 @@ pub(crate) fn process_struct(parsed: Struct) -> proc_macro::TokenStream {
 {static_decl}
 
 #[automatically_derived]
-unsafe impl<{generics_def}> ::facet::Facet for {struct_name}<{generics_use}> {where_clauses} {{
+unsafe impl<{impl_generics}> ::facet::Facet<'__facet> for {struct_name}<{generics_use}> {impl_where_clauses} {{
     const SHAPE: &'static ::facet::Shape = &const {{
         let fields: &'static [::facet::Field] = &const {{[{fields}]}};
 
@@ -150,6 +162,7 @@ unsafe impl<{generics_def}> ::facet::Facet for {struct_name}<{generics_use}> {wh
                 .kind({kind})
                 .fields(fields)
                 .build()))
+            {maybe_container_attributes}
             {maybe_container_doc}
             .build()
     }};
@@ -1,19 +1,41 @@
-use facet_core::Facet;
-use facet_reflect::Wip;
+use crate::{JsonErrorKind, JsonParseErrorWithContext};
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use facet_core::{Def, EnumTagging, Facet, Field, ScalarAffinity, Shape, StructKind, TaggedFields};
+use facet_reflect::{PathSegment, ReflectError, Wip, format_path};
+use log::trace;
 
-/// A JSON parse error, with context. Never would've guessed huh.
-#[derive(Debug)]
-pub struct JsonParseErrorWithContext<'input> {
-    input: &'input [u8],
+/// How deeply arrays and objects may nest, like serde_json. Deeper input is
+/// rejected, rather than overflowing the stack.
+const MAX_DEPTH: usize = 128;
+
+/// Options controlling how [`from_str_with_options`] and friends parse their input.
+///
+/// The default options only accept strict JSON, exactly like [`from_str`].
+#[derive(Debug, Clone, Default)]
+pub struct JsonReaderOptions {
+    lenient: bool,
 }
 
-impl core::fmt::Display for JsonParseErrorWithContext<'_> {
-    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(
-            f,
-            "JSON parse error: {}",
-            core::str::from_utf8(self.input).unwrap_or("invalid UTF-8")
-        )
+impl JsonReaderOptions {
+    /// Options for strict JSON
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Options accepting the JSON5 / JSONC extensions popular in config files:
+    ///
+    ///   - `// line` and `/* block */` comments
+    ///   - trailing commas in arrays and objects
+    ///   - unquoted identifier keys (`{ name: "x" }`)
+    ///   - single-quoted strings
+    ///   - hexadecimal numbers, a leading `+`, and leading or trailing decimal points
+    ///   - `Infinity`, `-Infinity` and `NaN`
+    ///
+    /// The values are deserialized exactly like strict JSON would be.
+    pub fn lenient() -> Self {
+        Self { lenient: true }
     }
 }
 
@@ -22,26 +44,1178 @@ impl core::fmt::Display for JsonParseErrorWithContext<'_> {
 /// This function takes a JSON string representation and converts it into a Rust
 /// value of the specified type `T`. The type must implement the `Facet` trait
 /// to provide the necessary type information for deserialization.
-pub fn from_str<T: Facet>(json: &str) -> Result<T, JsonParseErrorWithContext<'_>> {
+///
+/// Object keys that aren't fields of the struct are skipped, unless the struct
+/// has `#[facet(deny_unknown_fields)]`.
+pub fn from_str<'input: 'facet, 'facet, T: Facet<'facet>>(
+    json: &'input str,
+) -> Result<T, JsonParseErrorWithContext<'input>> {
     from_slice(json.as_bytes())
 }
 
+/// Deserializes a JSON5 / JSONC string, with comments, trailing commas and so on
+/// (see [`JsonReaderOptions::lenient`])
+pub fn from_str_lenient<'input: 'facet, 'facet, T: Facet<'facet>>(
+    json: &'input str,
+) -> Result<T, JsonParseErrorWithContext<'input>> {
+    from_str_with_options(json, &JsonReaderOptions::lenient())
+}
+
+/// Deserializes a JSON string, with the given options
+pub fn from_str_with_options<'input: 'facet, 'facet, T: Facet<'facet>>(
+    json: &'input str,
+    options: &JsonReaderOptions,
+) -> Result<T, JsonParseErrorWithContext<'input>> {
+    from_slice_with_options(json.as_bytes(), options)
+}
+
 /// Deserialize JSON from a slice
-pub fn from_slice<T: Facet>(json: &[u8]) -> Result<T, JsonParseErrorWithContext<'_>> {
+pub fn from_slice<'input: 'facet, 'facet, T: Facet<'facet>>(
+    json: &'input [u8],
+) -> Result<T, JsonParseErrorWithContext<'input>> {
+    from_slice_with_options(json, &JsonReaderOptions::default())
+}
+
+/// Deserialize JSON from a slice, with the given options
+pub fn from_slice_with_options<'input: 'facet, 'facet, T: Facet<'facet>>(
+    json: &'input [u8],
+    options: &JsonReaderOptions,
+) -> Result<T, JsonParseErrorWithContext<'input>> {
     let wip = Wip::alloc::<T>();
-    let wip = from_slice_wip(wip, json)?;
-    let heap_value = wip.build().unwrap();
-    Ok(heap_value.materialize::<T>().unwrap())
+    let wip = from_slice_wip_with_options(wip, json, options)?;
+    let reflect_err =
+        |e| JsonParseErrorWithContext::new(JsonErrorKind::ReflectError(e), json, json.len());
+    let heap_value = wip.build().map_err(reflect_err)?;
+    heap_value.materialize::<T>().map_err(reflect_err)
 }
 
 /// Deserialize a JSON string into a Wip object.
-pub fn from_slice_wip<'input, 'a>(
-    _wip: Wip<'a>,
-    _input: &'input [u8],
-) -> Result<Wip<'a>, JsonParseErrorWithContext<'input>> {
-    todo!()
-
-    // loop {
-    //     let frame = wip.frames_count();
-    // }
+pub fn from_slice_wip<'input: 'facet, 'facet>(
+    wip: Wip<'facet>,
+    input: &'input [u8],
+) -> Result<Wip<'facet>, JsonParseErrorWithContext<'input>> {
+    from_slice_wip_with_options(wip, input, &JsonReaderOptions::default())
+}
+
+/// Deserialize a JSON string into a Wip object, with the given options
+pub fn from_slice_wip_with_options<'input: 'facet, 'facet>(
+    wip: Wip<'facet>,
+    input: &'input [u8],
+    options: &JsonReaderOptions,
+) -> Result<Wip<'facet>, JsonParseErrorWithContext<'input>> {
+    let mut parser = Parser {
+        input,
+        pos: 0,
+        path: Vec::new(),
+        depth: 0,
+        lenient: options.lenient,
+    };
+    let wip = parser.deserialize_value(wip)?;
+
+    parser.skip_whitespace();
+    if parser.pos < input.len() {
+        return Err(parser.err(JsonErrorKind::TrailingCharacters));
+    }
+
+    Ok(wip)
+}
+
+/// Walks over JSON input, feeding values into a [`Wip`] as it goes.
+struct Parser<'input> {
+    input: &'input [u8],
+    pos: usize,
+    /// Path to the value currently being deserialized, for error messages
+    path: Vec<PathSegment<'input>>,
+    /// How many arrays and objects enclose the current position
+    depth: usize,
+    /// Whether the JSON5 / JSONC extensions are accepted
+    lenient: bool,
+}
+
+impl<'input> Parser<'input> {
+    /// Creates an error of the given kind at the current position
+    fn err(&self, kind: JsonErrorKind) -> JsonParseErrorWithContext<'input> {
+        self.err_at(kind, self.pos)
+    }
+
+    /// Creates an error of the given kind, spanning from `start` to the current position
+    fn err_at(&self, kind: JsonErrorKind, start: usize) -> JsonParseErrorWithContext<'input> {
+        JsonParseErrorWithContext::new(kind, self.input, start)
+            .with_len(self.pos.saturating_sub(start))
+            .with_path(format_path(&self.path))
+    }
+
+    /// Creates an error for the character at the current position (or for
+    /// the end of input, if we ran out)
+    fn unexpected(&self, expected: &'static str) -> JsonParseErrorWithContext<'input> {
+        let err = match self.input[self.pos.min(self.input.len())..]
+            .utf8_chunks()
+            .next()
+        {
+            None => self.err(JsonErrorKind::UnexpectedEof),
+            Some(chunk) => {
+                let c = chunk.valid().chars().next().unwrap_or('\u{FFFD}');
+                self.err(JsonErrorKind::UnexpectedCharacter(c)).with_len(1)
+            }
+        };
+        err.with_expected(expected)
+    }
+
+    /// Wraps a reflection error, positioned at the current byte offset
+    fn reflect_err(&self, e: ReflectError) -> JsonParseErrorWithContext<'input> {
+        self.err(JsonErrorKind::ReflectError(e))
+    }
+
+    /// Skips whitespace, and comments in lenient mode
+    fn skip_whitespace(&mut self) {
+        loop {
+            match self.input.get(self.pos..) {
+                Some([b' ' | b'\t' | b'\n' | b'\r', ..]) => self.pos += 1,
+                Some([b'/', b'/', ..]) if self.lenient => {
+                    while !matches!(self.input.get(self.pos), None | Some(b'\n')) {
+                        self.pos += 1;
+                    }
+                }
+                Some([b'/', b'*', ..]) if self.lenient => {
+                    // an unterminated comment runs to the end of the input
+                    self.pos += 2;
+                    while self.pos < self.input.len() && !self.input[self.pos..].starts_with(b"*/")
+                    {
+                        self.pos += 1;
+                    }
+                    self.pos = (self.pos + 2).min(self.input.len());
+                }
+                _ => return,
+            }
+        }
+    }
+
+    /// Whether `b` opens a string: `"`, or `'` in lenient mode
+    fn is_quote(&self, b: u8) -> bool {
+        b == b'"' || (self.lenient && b == b'\'')
+    }
+
+    /// Whether `b` starts a number. Lenient mode adds `+`, `.`, `Infinity` and `NaN`.
+    fn is_number_start(&self, b: u8) -> bool {
+        matches!(b, b'-' | b'0'..=b'9') || (self.lenient && matches!(b, b'+' | b'.' | b'I' | b'N'))
+    }
+
+    /// Skips whitespace, then returns the next byte without consuming it
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_whitespace();
+        self.input.get(self.pos).copied()
+    }
+
+    /// Skips whitespace, then consumes the given byte
+    fn expect(&mut self, b: u8) -> Result<(), JsonParseErrorWithContext<'input>> {
+        if self.peek() == Some(b) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            let expected = match b {
+                b'{' => "'{'",
+                b'}' => "'}'",
+                b'[' => "'['",
+                b']' => "']'",
+                b':' => "':'",
+                b',' => "','",
+                b'"' => "a string",
+                _ => "a different character",
+            };
+            Err(self.unexpected(expected))
+        }
+    }
+
+    /// Consumes `,` and returns `true` if there are more items in the
+    /// current array or object, or consumes `close` and returns `false`.
+    fn next_item(&mut self, close: u8) -> Result<bool, JsonParseErrorWithContext<'input>> {
+        match self.peek() {
+            Some(b',') => {
+                self.pos += 1;
+                // lenient mode allows a trailing comma
+                if self.lenient && self.peek() == Some(close) {
+                    self.pos += 1;
+                    return Ok(false);
+                }
+                Ok(true)
+            }
+            Some(b) if b == close => {
+                self.pos += 1;
+                Ok(false)
+            }
+            _ if close == b'}' => Err(self.unexpected("',' or '}'")),
+            _ => Err(self.unexpected("',' or ']'")),
+        }
+    }
+
+    /// Consumes the closing bracket of an array or object with a known number
+    /// of items, after an optional trailing comma in lenient mode
+    fn expect_end(&mut self, close: u8) -> Result<(), JsonParseErrorWithContext<'input>> {
+        if self.lenient && self.peek() == Some(b',') {
+            self.pos += 1;
+        }
+        self.expect(close)
+    }
+
+    /// Consumes the given literal (`true`, `false` or `null`)
+    fn parse_literal(
+        &mut self,
+        literal: &'static str,
+    ) -> Result<(), JsonParseErrorWithContext<'input>> {
+        self.skip_whitespace();
+        for &expected in literal.as_bytes() {
+            if self.input.get(self.pos) != Some(&expected) {
+                return Err(self.unexpected(literal));
+            }
+            self.pos += 1;
+        }
+        Ok(())
+    }
+
+    /// Parses a number, and returns its textual representation. In lenient
+    /// mode, hexadecimal numbers are converted to decimal.
+    fn parse_number(&mut self) -> Result<Cow<'input, str>, JsonParseErrorWithContext<'input>> {
+        self.skip_whitespace();
+        let start = self.pos;
+
+        match self.input.get(self.pos) {
+            Some(b'-') => self.pos += 1,
+            Some(b'+') if self.lenient => self.pos += 1,
+            _ => {}
+        }
+
+        if self.lenient {
+            let rest = &self.input[self.pos..];
+            for special in ["Infinity", "NaN"] {
+                if rest.starts_with(special.as_bytes()) {
+                    self.pos += special.len();
+                    return Ok(Cow::Borrowed(
+                        core::str::from_utf8(&self.input[start..self.pos]).unwrap(),
+                    ));
+                }
+            }
+            if let [b'0', b'x' | b'X', ..] = rest {
+                return self.parse_hex_number(start);
+            }
+        }
+
+        // integer part: a single zero, or digits not starting with zero.
+        // Lenient mode allows leaving it out before a fraction.
+        let has_integer_part = match self.input.get(self.pos) {
+            Some(b'0') => {
+                self.pos += 1;
+                true
+            }
+            Some(b'1'..=b'9') => {
+                self.skip_digits();
+                true
+            }
+            Some(b'.') if self.lenient => false,
+            _ => return Err(self.unexpected("a digit")),
+        };
+
+        // fraction, whose digits lenient mode allows leaving out after an integer part
+        if self.input.get(self.pos) == Some(&b'.') {
+            self.pos += 1;
+            match self.input.get(self.pos) {
+                Some(b'0'..=b'9') => self.skip_digits(),
+                _ if self.lenient && has_integer_part => {}
+                _ => return Err(self.unexpected("a digit")),
+            }
+        }
+
+        // exponent
+        if let Some(b'e' | b'E') = self.input.get(self.pos) {
+            self.pos += 1;
+            if let Some(b'+' | b'-') = self.input.get(self.pos) {
+                self.pos += 1;
+            }
+            if !matches!(self.input.get(self.pos), Some(b'0'..=b'9')) {
+                return Err(self.unexpected("a digit"));
+            }
+            self.skip_digits();
+        }
+
+        // only ASCII digits, signs, dots and exponents were consumed
+        Ok(Cow::Borrowed(
+            core::str::from_utf8(&self.input[start..self.pos]).unwrap(),
+        ))
+    }
+
+    /// Parses a hexadecimal number starting with `0x`, and returns it in decimal.
+    /// `start` is where the number (and its sign, if any) starts.
+    fn parse_hex_number(
+        &mut self,
+        start: usize,
+    ) -> Result<Cow<'input, str>, JsonParseErrorWithContext<'input>> {
+        self.pos += 2;
+        let digits_start = self.pos;
+        while self.input.get(self.pos).is_some_and(u8::is_ascii_hexdigit) {
+            self.pos += 1;
+        }
+        if self.pos == digits_start {
+            return Err(self.unexpected("a hexadecimal digit"));
+        }
+
+        let digits = core::str::from_utf8(&self.input[digits_start..self.pos]).unwrap();
+        let value = u128::from_str_radix(digits, 16)
+            .map_err(|_| self.err_at(JsonErrorKind::InvalidNumber, start))?;
+        let sign = if self.input[start] == b'-' { "-" } else { "" };
+        Ok(Cow::Owned(alloc::format!("{}{}", sign, value)))
+    }
+
+    fn skip_digits(&mut self) {
+        while let Some(b'0'..=b'9') = self.input.get(self.pos) {
+            self.pos += 1;
+        }
+    }
+
+    /// Parses a string. Strings without escape sequences are borrowed from the input.
+    fn parse_string(&mut self) -> Result<Cow<'input, str>, JsonParseErrorWithContext<'input>> {
+        let quote = match self.peek() {
+            Some(b) if self.is_quote(b) => b,
+            _ => return Err(self.unexpected("a string")),
+        };
+        self.pos += 1;
+        let start = self.pos;
+
+        // fast path: no escapes
+        loop {
+            match self.input.get(self.pos) {
+                None => return Err(self.err(JsonErrorKind::UnexpectedEof)),
+                Some(&b) if b == quote => {
+                    let s = core::str::from_utf8(&self.input[start..self.pos]).map_err(|e| {
+                        self.err_at(JsonErrorKind::InvalidUtf8, start + e.valid_up_to())
+                    })?;
+                    self.pos += 1;
+                    return Ok(Cow::Borrowed(s));
+                }
+                Some(b'\\') => break,
+                Some(0x00..=0x1f) => return Err(self.unexpected("an escaped control character")),
+                Some(_) => self.pos += 1,
+            }
+        }
+
+        // slow path: unescape into a buffer
+        let mut buf: Vec<u8> = self.input[start..self.pos].to_vec();
+        loop {
+            match self.input.get(self.pos) {
+                None => return Err(self.err(JsonErrorKind::UnexpectedEof)),
+                Some(&b) if b == quote => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(b'\\') => {
+                    if let Some(c) = self.parse_escape()? {
+                        let mut tmp = [0u8; 4];
+                        buf.extend_from_slice(c.encode_utf8(&mut tmp).as_bytes());
+                    }
+                }
+                Some(0x00..=0x1f) => return Err(self.unexpected("an escaped control character")),
+                Some(&b) => {
+                    buf.push(b);
+                    self.pos += 1;
+                }
+            }
+        }
+
+        String::from_utf8(buf)
+            .map(Cow::Owned)
+            .map_err(|_| self.err_at(JsonErrorKind::InvalidUtf8, start))
+    }
+
+    /// Parses an escape sequence, starting at the backslash. Returns `None`
+    /// for an escaped line break, which lenient mode uses to continue strings
+    /// on the next line.
+    fn parse_escape(&mut self) -> Result<Option<char>, JsonParseErrorWithContext<'input>> {
+        let start = self.pos;
+        self.pos += 1;
+        let c = match self.input.get(self.pos) {
+            None => return Err(self.err(JsonErrorKind::UnexpectedEof)),
+            Some(b'"') => '"',
+            Some(b'\'') if self.lenient => '\'',
+            Some(b'v') if self.lenient => '\u{0B}',
+            Some(b'0')
+                if self.lenient
+                    && !self.input.get(self.pos + 1).is_some_and(u8::is_ascii_digit) =>
+            {
+                '\0'
+            }
+            Some(b'x') if self.lenient => {
+                self.pos += 1;
+                let code = self.parse_hex(2)?;
+                return Ok(Some(char::from(code as u8)));
+            }
+            Some(b'\n') if self.lenient => {
+                self.pos += 1;
+                return Ok(None);
+            }
+            Some(b'\r') if self.lenient => {
+                self.pos += 1;
+                if self.input.get(self.pos) == Some(&b'\n') {
+                    self.pos += 1;
+                }
+                return Ok(None);
+            }
+            Some(b'\\') => '\\',
+            Some(b'/') => '/',
+            Some(b'b') => '\u{08}',
+            Some(b'f') => '\u{0C}',
+            Some(b'n') => '\n',
+            Some(b'r') => '\r',
+            Some(b't') => '\t',
+            Some(b'u') => {
+                self.pos += 1;
+                let high = self.parse_hex(4)?;
+                let code = match high {
+                    0xD800..=0xDBFF => {
+                        // a high surrogate must be followed by a low surrogate
+                        if self.input.get(self.pos..self.pos + 2) != Some(b"\\u") {
+                            return Err(self.err_at(JsonErrorKind::InvalidEscape, start));
+                        }
+                        self.pos += 2;
+                        let low = self.parse_hex(4)?;
+                        if !(0xDC00..=0xDFFF).contains(&low) {
+                            return Err(self.err_at(JsonErrorKind::InvalidEscape, start));
+                        }
+                        0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+                    }
+                    _ => high,
+                };
+                return char::from_u32(code)
+                    .map(Some)
+                    .ok_or_else(|| self.err_at(JsonErrorKind::InvalidEscape, start));
+            }
+            Some(_) => return Err(self.err(JsonErrorKind::InvalidEscape)),
+        };
+        self.pos += 1;
+        Ok(Some(c))
+    }
+
+    /// Parses the hex digits of a `\u` (or, in lenient mode, `\x`) escape
+    fn parse_hex(&mut self, len: usize) -> Result<u32, JsonParseErrorWithContext<'input>> {
+        let mut code = 0;
+        for _ in 0..len {
+            let digit = match self.input.get(self.pos) {
+                None => return Err(self.err(JsonErrorKind::UnexpectedEof)),
+                Some(&b) => (b as char)
+                    .to_digit(16)
+                    .ok_or_else(|| self.err(JsonErrorKind::InvalidEscape))?,
+            };
+            code = code * 16 + digit;
+            self.pos += 1;
+        }
+        Ok(code)
+    }
+
+    /// Parses an object key: a string, or in lenient mode also an identifier
+    fn parse_key(&mut self) -> Result<Cow<'input, str>, JsonParseErrorWithContext<'input>> {
+        match self.peek() {
+            Some(b) if self.is_quote(b) => self.parse_string(),
+            Some(_) if self.lenient => self.parse_identifier(),
+            _ => Err(self.unexpected("a string key")),
+        }
+    }
+
+    /// Parses an unquoted key (lenient mode only): letters, digits, `_` and `$`,
+    /// not starting with a digit
+    fn parse_identifier(&mut self) -> Result<Cow<'input, str>, JsonParseErrorWithContext<'input>> {
+        let start = self.pos;
+        let rest = match self.input[start..].utf8_chunks().next() {
+            Some(chunk) => chunk.valid(),
+            None => "",
+        };
+        let len = rest
+            .char_indices()
+            .find(|&(i, c)| {
+                let allowed = c.is_alphanumeric() || c == '_' || c == '$';
+                !allowed || (i == 0 && c.is_numeric())
+            })
+            .map_or(rest.len(), |(i, _)| i);
+        if len == 0 {
+            return Err(self.unexpected("a key"));
+        }
+        self.pos += len;
+        Ok(Cow::Borrowed(&rest[..len]))
+    }
+
+    /// Runs `f` on the value at the current position, one level deeper if
+    /// that value is an array or object. Fails past [`MAX_DEPTH`].
+    fn nested<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<T, JsonParseErrorWithContext<'input>>,
+    ) -> Result<T, JsonParseErrorWithContext<'input>> {
+        if !matches!(self.peek(), Some(b'[' | b'{')) {
+            return f(self);
+        }
+        if self.depth >= MAX_DEPTH {
+            return Err(self.err(JsonErrorKind::DepthLimitExceeded).with_len(1));
+        }
+        self.depth += 1;
+        let result = f(self);
+        self.depth -= 1;
+        result
+    }
+
+    /// Skips over a value without deserializing it (used for unknown fields)
+    fn skip_value(&mut self) -> Result<(), JsonParseErrorWithContext<'input>> {
+        self.nested(Self::skip_nested_value)
+    }
+
+    fn skip_nested_value(&mut self) -> Result<(), JsonParseErrorWithContext<'input>> {
+        match self.peek() {
+            None => Err(self.err(JsonErrorKind::UnexpectedEof)),
+            Some(b) if self.is_quote(b) => self.parse_string().map(|_| ()),
+            Some(b) if self.is_number_start(b) => self.parse_number().map(|_| ()),
+            Some(b't') => self.parse_literal("true"),
+            Some(b'f') => self.parse_literal("false"),
+            Some(b'n') => self.parse_literal("null"),
+            Some(b'[') => {
+                self.pos += 1;
+                if self.peek() == Some(b']') {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                loop {
+                    self.skip_value()?;
+                    if !self.next_item(b']')? {
+                        return Ok(());
+                    }
+                }
+            }
+            Some(b'{') => {
+                self.pos += 1;
+                if self.peek() == Some(b'}') {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                loop {
+                    self.parse_key()?;
+                    self.expect(b':')?;
+                    self.skip_value()?;
+                    if !self.next_item(b'}')? {
+                        return Ok(());
+                    }
+                }
+            }
+            Some(_) => Err(self.unexpected("a value")),
+        }
+    }
+
+    /// Describes the JSON value starting at the current position, for error messages
+    fn value_kind(&mut self) -> &'static str {
+        match self.peek() {
+            Some(b) if self.is_quote(b) => "string",
+            Some(b) if self.is_number_start(b) => "number",
+            Some(b't' | b'f') => "boolean",
+            Some(b'n') => "null",
+            Some(b'[') => "array",
+            Some(b'{') => "object",
+            _ => "invalid value",
+        }
+    }
+
+    /// Creates a type mismatch error, underlining the whole value at the current position
+    fn type_mismatch(&mut self, expected: &'static Shape) -> JsonParseErrorWithContext<'input> {
+        let got = self.value_kind();
+        let start = self.pos;
+        let _ = self.skip_value();
+        let err = self.err_at(JsonErrorKind::TypeMismatch { expected, got }, start);
+        self.pos = start;
+        err
+    }
+}
+
+impl<'input: 'facet, 'facet> Parser<'input> {
+    /// Deserializes the value at the current position into the current frame of `wip`
+    fn deserialize_value(
+        &mut self,
+        wip: Wip<'facet>,
+    ) -> Result<Wip<'facet>, JsonParseErrorWithContext<'input>> {
+        self.nested(|parser| parser.deserialize_nested_value(wip))
+    }
+
+    fn deserialize_nested_value(
+        &mut self,
+        wip: Wip<'facet>,
+    ) -> Result<Wip<'facet>, JsonParseErrorWithContext<'input>> {
+        let shape = wip.shape();
+        trace!("Deserializing {} at byte {}", shape, self.pos);
+
+        if self.peek().is_none() {
+            return Err(self.err(JsonErrorKind::UnexpectedEof));
+        }
+
+        match shape.def {
+            Def::Scalar(_) => self.deserialize_scalar(wip),
+            Def::Struct(sd) => match sd.kind {
+                StructKind::Struct => self.deserialize_fields_object(wip, sd.fields),
+                StructKind::TupleStruct | StructKind::Tuple => {
+                    self.deserialize_fields_array(wip, sd.fields)
+                }
+                StructKind::Unit => self.deserialize_unit(wip),
+                _ => Err(self.err(JsonErrorKind::UnsupportedShape(shape))),
+            },
+            Def::List(_) => self.deserialize_list(wip),
+            Def::Array(ad) => self.deserialize_array(wip, ad.n),
+            Def::Map(_) => self.deserialize_map(wip),
+            Def::Option(_) => {
+                if self.peek() == Some(b'n') {
+                    self.parse_literal("null")?;
+                    wip.put_default().map_err(|e| self.reflect_err(e))
+                } else {
+                    let wip = wip.push_some().map_err(|e| self.reflect_err(e))?;
+                    let wip = self.deserialize_value(wip)?;
+                    wip.pop().map_err(|e| self.reflect_err(e))
+                }
+            }
+            Def::Enum(_) => self.deserialize_enum(wip),
+            _ => Err(self.err(JsonErrorKind::UnsupportedShape(shape))),
+        }
+    }
+
+    fn deserialize_scalar(
+        &mut self,
+        wip: Wip<'facet>,
+    ) -> Result<Wip<'facet>, JsonParseErrorWithContext<'input>> {
+        let shape = wip.shape();
+        self.skip_whitespace();
+        let start = self.pos;
+        match self.peek() {
+            Some(b) if self.is_quote(b) => {
+                // only map keys are parsed from strings into numbers and booleans
+                if matches!(shape.def, Def::Scalar(sd) if matches!(sd.affinity, ScalarAffinity::Number(_) | ScalarAffinity::Boolean(_)))
+                {
+                    return Err(self.type_mismatch(shape));
+                }
+                let s = self.parse_string()?;
+                self.put_str(wip, s, start)
+            }
+            Some(b) if self.is_number_start(b) => {
+                if !matches!(shape.def, Def::Scalar(sd) if matches!(sd.affinity, ScalarAffinity::Number(_)))
+                {
+                    return Err(self.type_mismatch(shape));
+                }
+                let text = self.parse_number()?;
+                wip.parse(&text)
+                    .map_err(|_| self.err_at(JsonErrorKind::InvalidValue(shape), start))
+            }
+            Some(b't' | b'f') if shape.is_type::<bool>() => {
+                let value = self.peek() == Some(b't');
+                self.parse_literal(if value { "true" } else { "false" })?;
+                wip.put(value).map_err(|e| self.reflect_err(e))
+            }
+            Some(b'n') if shape.is_type::<()>() => {
+                self.parse_literal("null")?;
+                wip.put(()).map_err(|e| self.reflect_err(e))
+            }
+            _ => Err(self.type_mismatch(shape)),
+        }
+    }
+
+    /// Puts a string into the current frame, which may be a string type,
+    /// a `char`, or any scalar that can be parsed from a string.
+    ///
+    /// `&str` and `Cow<str>` borrow from the input when the string has no
+    /// escape sequences. Escaped strings become `Cow::Owned`, and can't be
+    /// deserialized as `&str` at all.
+    fn put_str(
+        &self,
+        wip: Wip<'facet>,
+        s: Cow<'input, str>,
+        start: usize,
+    ) -> Result<Wip<'facet>, JsonParseErrorWithContext<'input>> {
+        let shape = wip.shape();
+        let invalid = || self.err_at(JsonErrorKind::InvalidValue(shape), start);
+
+        if shape.is_type::<String>() {
+            wip.put(s.into_owned()).map_err(|e| self.reflect_err(e))
+        } else if shape.is_type::<Cow<'_, str>>() {
+            wip.put::<Cow<'facet, str>>(s)
+                .map_err(|e| self.reflect_err(e))
+        } else if shape.is_type::<&str>() {
+            match s {
+                Cow::Borrowed(s) => wip.put::<&'facet str>(s).map_err(|e| self.reflect_err(e)),
+                Cow::Owned(_) => Err(self.err_at(JsonErrorKind::CannotBorrow(shape), start)),
+            }
+        } else if shape.is_type::<char>() {
+            let mut chars = s.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => wip.put(c).map_err(|e| self.reflect_err(e)),
+                _ => Err(invalid()),
+            }
+        } else if matches!(shape.def, Def::Scalar(_)) {
+            wip.parse(&s).map_err(|_| invalid())
+        } else {
+            Err(self.err_at(
+                JsonErrorKind::TypeMismatch {
+                    expected: shape,
+                    got: "string",
+                },
+                start,
+            ))
+        }
+    }
+
+    /// Deserializes a JSON object into the fields of a struct (or struct variant)
+    fn deserialize_fields_object(
+        &mut self,
+        mut wip: Wip<'facet>,
+        fields: &'static [Field],
+    ) -> Result<Wip<'facet>, JsonParseErrorWithContext<'input>> {
+        if self.peek() != Some(b'{') {
+            return Err(self.type_mismatch(wip.shape()));
+        }
+        self.pos += 1;
+
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+        } else {
+            loop {
+                self.skip_whitespace();
+                let key_start = self.pos;
+                let key = self.parse_key()?;
+                self.expect(b':')?;
+
+                match wip.field_index(&key) {
+                    Some(index) => {
+                        if wip.is_field_set(index).map_err(|e| self.reflect_err(e))? {
+                            return Err(self.err_at(
+                                JsonErrorKind::DuplicateField(fields[index].name),
+                                key_start,
+                            ));
+                        }
+                        self.path.push(PathSegment::Field(fields[index].name));
+                        wip = wip.field(index).map_err(|e| self.reflect_err(e))?;
+                        wip = self.deserialize_value(wip)?;
+                        wip = wip.pop().map_err(|e| self.reflect_err(e))?;
+                        self.path.pop();
+                    }
+                    None if wip.shape().denies_unknown_fields() => {
+                        return Err(
+                            self.err_at(JsonErrorKind::UnknownField(key.into_owned()), key_start)
+                        );
+                    }
+                    None => {
+                        trace!("Skipping unknown field {:?}", key);
+                        self.skip_value()?;
+                    }
+                }
+
+                if !self.next_item(b'}')? {
+                    break;
+                }
+            }
+        }
+
+        // absent optional fields are `None`, everything else is required
+        let end = self.pos - 1;
+        for (index, field) in fields.iter().enumerate() {
+            if wip.is_field_set(index).map_err(|e| self.reflect_err(e))? {
+                continue;
+            }
+            if !matches!(field.shape().def, Def::Option(_)) {
+                return Err(self.err_at(JsonErrorKind::MissingField(field.name), end));
+            }
+            wip = wip
+                .field(index)
+                .and_then(|wip| wip.put_default())
+                .and_then(|wip| wip.pop())
+                .map_err(|e| self.reflect_err(e))?;
+        }
+
+        Ok(wip)
+    }
+
+    /// Deserializes a JSON array into the fields of a tuple, tuple struct, or tuple variant
+    fn deserialize_fields_array(
+        &mut self,
+        mut wip: Wip<'facet>,
+        fields: &'static [Field],
+    ) -> Result<Wip<'facet>, JsonParseErrorWithContext<'input>> {
+        if self.peek() != Some(b'[') {
+            return Err(self.type_mismatch(wip.shape()));
+        }
+        self.pos += 1;
+
+        for index in 0..fields.len() {
+            if index > 0 {
+                self.expect(b',')?;
+            }
+            self.path.push(PathSegment::Index(index));
+            wip = wip.field(index).map_err(|e| self.reflect_err(e))?;
+            wip = self.deserialize_value(wip)?;
+            wip = wip.pop().map_err(|e| self.reflect_err(e))?;
+            self.path.pop();
+        }
+        self.expect_end(b']')?;
+
+        Ok(wip)
+    }
+
+    /// Unit structs are written as `null`, but we also accept `{}` and `[]`
+    fn deserialize_unit(
+        &mut self,
+        wip: Wip<'facet>,
+    ) -> Result<Wip<'facet>, JsonParseErrorWithContext<'input>> {
+        match self.peek() {
+            Some(b'n') => self.parse_literal("null")?,
+            Some(b'{') => {
+                self.pos += 1;
+                self.expect(b'}')?;
+            }
+            Some(b'[') => {
+                self.pos += 1;
+                self.expect(b']')?;
+            }
+            _ => return Err(self.type_mismatch(wip.shape())),
+        }
+        Ok(wip)
+    }
+
+    fn deserialize_list(
+        &mut self,
+        wip: Wip<'facet>,
+    ) -> Result<Wip<'facet>, JsonParseErrorWithContext<'input>> {
+        if self.peek() != Some(b'[') {
+            return Err(self.type_mismatch(wip.shape()));
+        }
+        self.pos += 1;
+
+        let mut wip = wip.begin_pushback().map_err(|e| self.reflect_err(e))?;
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(wip);
+        }
+
+        for index in 0.. {
+            self.path.push(PathSegment::Index(index));
+            wip = wip.push().map_err(|e| self.reflect_err(e))?;
+            wip = self.deserialize_value(wip)?;
+            wip = wip.pop().map_err(|e| self.reflect_err(e))?;
+            self.path.pop();
+
+            if !self.next_item(b']')? {
+                break;
+            }
+        }
+        Ok(wip)
+    }
+
+    /// Fixed-size arrays, which must have exactly `len` elements
+    fn deserialize_array(
+        &mut self,
+        mut wip: Wip<'facet>,
+        len: usize,
+    ) -> Result<Wip<'facet>, JsonParseErrorWithContext<'input>> {
+        if self.peek() != Some(b'[') {
+            return Err(self.type_mismatch(wip.shape()));
+        }
+        let start = self.pos;
+        self.pos += 1;
+
+        let mut count = 0;
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+        } else {
+            loop {
+                if count < len {
+                    self.path.push(PathSegment::Index(count));
+                    wip = wip.field(count).map_err(|e| self.reflect_err(e))?;
+                    wip = self.deserialize_value(wip)?;
+                    wip = wip.pop().map_err(|e| self.reflect_err(e))?;
+                    self.path.pop();
+                } else {
+                    self.skip_value()?;
+                }
+                count += 1;
+
+                if !self.next_item(b']')? {
+                    break;
+                }
+            }
+        }
+
+        if count != len {
+            return Err(self.err_at(
+                JsonErrorKind::WrongLength {
+                    expected: len,
+                    got: count,
+                },
+                start,
+            ));
+        }
+        Ok(wip)
+    }
+
+    fn deserialize_map(
+        &mut self,
+        wip: Wip<'facet>,
+    ) -> Result<Wip<'facet>, JsonParseErrorWithContext<'input>> {
+        if self.peek() != Some(b'{') {
+            return Err(self.type_mismatch(wip.shape()));
+        }
+        self.pos += 1;
+
+        let mut wip = wip.begin_map_insert().map_err(|e| self.reflect_err(e))?;
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(wip);
+        }
+
+        loop {
+            self.skip_whitespace();
+            let start = self.pos;
+            let key = self.parse_key()?;
+            self.expect(b':')?;
+
+            // keys are always strings in JSON, but may be parsed into e.g. integers
+            wip = wip.push_map_key().map_err(|e| self.reflect_err(e))?;
+            wip = self.put_str(wip, key.clone(), start)?;
+            self.path.push(PathSegment::Key(key));
+            wip = wip.push_map_value().map_err(|e| self.reflect_err(e))?;
+            wip = self.deserialize_value(wip)?;
+            wip = wip.pop().map_err(|e| self.reflect_err(e))?;
+            self.path.pop();
+
+            if !self.next_item(b'}')? {
+                return Ok(wip);
+            }
+        }
+    }
+
+    /// Deserializes an enum, in the representation selected by its tagging attributes
+    fn deserialize_enum(
+        &mut self,
+        wip: Wip<'facet>,
+    ) -> Result<Wip<'facet>, JsonParseErrorWithContext<'input>> {
+        match wip.shape().enum_tagging() {
+            EnumTagging::Internal { tag } => self.deserialize_internally_tagged(wip, tag),
+            EnumTagging::Adjacent { tag, content } => {
+                self.deserialize_adjacently_tagged(wip, tag, content)
+            }
+            EnumTagging::Untagged => self.deserialize_untagged(wip),
+            _ => self.deserialize_externally_tagged(wip),
+        }
+    }
+
+    /// `"Unit"` or `{"Variant": payload}`
+    fn deserialize_externally_tagged(
+        &mut self,
+        wip: Wip<'facet>,
+    ) -> Result<Wip<'facet>, JsonParseErrorWithContext<'input>> {
+        let shape = wip.shape();
+        match self.peek() {
+            Some(b) if self.is_quote(b) => {
+                let start = self.pos;
+                let name = self.parse_string()?;
+                let wip = self.select_variant(wip, &name, start)?;
+                let variant = wip.selected_variant().unwrap();
+                if !variant.data.fields.is_empty() {
+                    return Err(self.err_at(
+                        JsonErrorKind::TypeMismatch {
+                            expected: shape,
+                            got: "string",
+                        },
+                        start,
+                    ));
+                }
+                Ok(wip)
+            }
+            Some(b'{') => {
+                self.pos += 1;
+                self.skip_whitespace();
+                let start = self.pos;
+                let name = self.parse_key()?;
+                self.expect(b':')?;
+
+                let wip = self.select_variant(wip, &name, start)?;
+                let wip = self.deserialize_variant_payload(wip)?;
+                self.expect_end(b'}')?;
+                Ok(wip)
+            }
+            _ => Err(self.type_mismatch(shape)),
+        }
+    }
+
+    /// `{"type": "Variant", ...fields}`
+    fn deserialize_internally_tagged(
+        &mut self,
+        wip: Wip<'facet>,
+        tag: &'static str,
+    ) -> Result<Wip<'facet>, JsonParseErrorWithContext<'input>> {
+        let shape = wip.shape();
+        let (name, start) = self.find_tag(shape, tag)?;
+        let mut wip = self.select_variant(wip, &name, start)?;
+        let variant = wip.selected_variant().unwrap();
+
+        // the tag key is not a field of the variant, so it gets skipped like unknown keys
+        self.path.push(PathSegment::Field(variant.name));
+        wip = match variant.internally_tagged_fields() {
+            Some(TaggedFields::Unit) => {
+                self.skip_value()?;
+                wip
+            }
+            Some(TaggedFields::Struct(fields)) => self.deserialize_fields_object(wip, fields)?,
+            Some(TaggedFields::Newtype(fields)) => {
+                let wip = wip.field(0).map_err(|e| self.reflect_err(e))?;
+                let wip = self.deserialize_fields_object(wip, fields)?;
+                wip.pop().map_err(|e| self.reflect_err(e))?
+            }
+            _ => return Err(self.err(JsonErrorKind::UnsupportedShape(shape))),
+        };
+        self.path.pop();
+        Ok(wip)
+    }
+
+    /// `{"t": "Variant", "c": payload}`, with the keys in any order. Unit
+    /// variants may leave out the content.
+    fn deserialize_adjacently_tagged(
+        &mut self,
+        wip: Wip<'facet>,
+        tag: &'static str,
+        content: &'static str,
+    ) -> Result<Wip<'facet>, JsonParseErrorWithContext<'input>> {
+        let (name, start) = self.find_tag(wip.shape(), tag)?;
+        let mut wip = self.select_variant(wip, &name, start)?;
+        let variant = wip.selected_variant().unwrap();
+
+        let mut has_content = false;
+        self.pos += 1;
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+        } else {
+            loop {
+                let key = self.parse_key()?;
+                self.expect(b':')?;
+
+                if key == content && !has_content {
+                    wip = self.deserialize_variant_payload(wip)?;
+                    has_content = true;
+                } else {
+                    self.skip_value()?;
+                }
+
+                if !self.next_item(b'}')? {
+                    break;
+                }
+            }
+        }
+
+        if !has_content && variant.data.kind != StructKind::Unit {
+            return Err(self.err_at(JsonErrorKind::MissingField(content), self.pos - 1));
+        }
+        Ok(wip)
+    }
+
+    /// Just the payload: tries each variant in declaration order, and picks
+    /// the first one the value deserializes into
+    fn deserialize_untagged(
+        &mut self,
+        wip: Wip<'facet>,
+    ) -> Result<Wip<'facet>, JsonParseErrorWithContext<'input>> {
+        let shape = wip.shape();
+        let start = self.pos;
+        let path_len = self.path.len();
+        let variant = Wip::find_untagged_variant(shape, |scratch| {
+            let attempt = self.deserialize_variant_payload(scratch);
+            self.pos = start;
+            self.path.truncate(path_len);
+            attempt
+        });
+
+        if let Some(variant) = variant {
+            trace!("Untagged value matches variant {}", variant.name);
+            let wip = wip
+                .variant_named(variant.name)
+                .map_err(|e| self.reflect_err(e))?;
+            return self.deserialize_variant_payload(wip);
+        }
+
+        let _ = self.skip_value();
+        let err = self.err_at(JsonErrorKind::NoMatchingVariant(shape), start);
+        self.pos = start;
+        Err(err)
+    }
+
+    /// Deserializes the data of the selected variant: newtype variants hold
+    /// their value directly, tuple variants are arrays and struct variants objects
+    fn deserialize_variant_payload(
+        &mut self,
+        mut wip: Wip<'facet>,
+    ) -> Result<Wip<'facet>, JsonParseErrorWithContext<'input>> {
+        let variant = wip.selected_variant().unwrap();
+        self.path.push(PathSegment::Field(variant.name));
+        wip = match variant.data.kind {
+            StructKind::Unit => self.deserialize_unit(wip)?,
+            StructKind::Tuple | StructKind::TupleStruct if variant.data.fields.len() == 1 => {
+                let wip = wip.field(0).map_err(|e| self.reflect_err(e))?;
+                let wip = self.deserialize_value(wip)?;
+                wip.pop().map_err(|e| self.reflect_err(e))?
+            }
+            StructKind::Tuple | StructKind::TupleStruct => {
+                self.deserialize_fields_array(wip, variant.data.fields)?
+            }
+            _ => self.deserialize_fields_object(wip, variant.data.fields)?,
+        };
+        self.path.pop();
+        Ok(wip)
+    }
+
+    /// Looks ahead in the object at the current position for the variant name
+    /// stored under `tag`. Returns the name and where it starts; the position
+    /// is left at the opening brace.
+    fn find_tag(
+        &mut self,
+        shape: &'static Shape,
+        tag: &'static str,
+    ) -> Result<(Cow<'input, str>, usize), JsonParseErrorWithContext<'input>> {
+        if self.peek() != Some(b'{') {
+            return Err(self.type_mismatch(shape));
+        }
+        let object_start = self.pos;
+        self.pos += 1;
+
+        let mut found = None;
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+        } else {
+            loop {
+                let key = self.parse_key()?;
+                self.expect(b':')?;
+
+                if key == tag && found.is_none() {
+                    if !self.peek().is_some_and(|b| self.is_quote(b)) {
+                        return Err(self.type_mismatch(<String as Facet>::SHAPE));
+                    }
+                    let start = self.pos;
+                    found = Some((self.parse_string()?, start));
+                } else {
+                    self.skip_value()?;
+                }
+
+                if !self.next_item(b'}')? {
+                    break;
+                }
+            }
+        }
+
+        let Some(found) = found else {
+            return Err(self.err_at(JsonErrorKind::MissingField(tag), object_start));
+        };
+        self.pos = object_start;
+        Ok(found)
+    }
+
+    fn select_variant(
+        &self,
+        wip: Wip<'facet>,
+        name: &str,
+        start: usize,
+    ) -> Result<Wip<'facet>, JsonParseErrorWithContext<'input>> {
+        if wip.find_variant(name).is_none() {
+            return Err(self.err_at(JsonErrorKind::UnknownVariant(name.to_string()), start));
+        }
+        wip.variant_named(name).map_err(|e| self.reflect_err(e))
+    }
 }
@@ -0,0 +1,263 @@
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::String;
+use core::fmt::{self, Display, Formatter};
+use facet_ansi::{ColorStyle as _, Style, Stylize as _};
+use facet_core::Shape;
+use facet_reflect::{ReflectError, Snippet};
+
+/// A JSON parse error, with context. Never would've guessed huh.
+///
+/// Besides the [kind](JsonErrorKind) of error, this records where in the input
+/// it happened (byte offset, line and column), which token the parser expected
+/// there, and the path of the value being deserialized (e.g. `config.servers[2].port`).
+///
+/// The `Display` implementation renders the offending line of input with a
+/// caret underline. Call [`with_colors`](Self::with_colors) to style it
+/// for a terminal.
+#[derive(Debug)]
+pub struct JsonParseErrorWithContext<'input> {
+    input: Cow<'input, [u8]>,
+    snippet: Snippet,
+    kind: JsonErrorKind,
+    expected: Option<&'static str>,
+    path: String,
+    colors: bool,
+}
+
+impl<'input> JsonParseErrorWithContext<'input> {
+    /// Creates a new error of the given kind, at the given byte offset of the input.
+    pub fn new(kind: JsonErrorKind, input: &'input [u8], pos: usize) -> Self {
+        Self {
+            input: Cow::Borrowed(input),
+            snippet: Snippet::new(input, pos..pos),
+            kind,
+            expected: None,
+            path: String::new(),
+            colors: false,
+        }
+    }
+
+    /// Sets how many bytes, starting at the error position, the caret should underline
+    pub(crate) fn with_len(mut self, len: usize) -> Self {
+        let pos = self.snippet.position();
+        self.snippet = Snippet::new(&self.input, pos..pos + len);
+        self
+    }
+
+    /// Records the token the parser expected at the error position
+    pub(crate) fn with_expected(mut self, expected: &'static str) -> Self {
+        self.expected = Some(expected);
+        self
+    }
+
+    /// Records the path of the value that was being deserialized
+    pub(crate) fn with_path(mut self, path: String) -> Self {
+        self.path = path;
+        self
+    }
+
+    /// Enable or disable ANSI colors when displaying this error
+    pub fn with_colors(mut self, colors: bool) -> Self {
+        self.colors = colors;
+        self
+    }
+
+    /// Returns the kind of error that occurred
+    pub fn kind(&self) -> &JsonErrorKind {
+        &self.kind
+    }
+
+    /// Returns the byte offset in the input at which the error occurred
+    pub fn position(&self) -> usize {
+        self.snippet.position()
+    }
+
+    /// Returns the 1-based line at which the error occurred
+    pub fn line(&self) -> usize {
+        self.snippet.line()
+    }
+
+    /// Returns the 1-based column (in characters) at which the error occurred
+    pub fn column(&self) -> usize {
+        self.snippet.column()
+    }
+
+    /// Returns a description of the token the parser expected, if it was
+    /// expecting something specific (e.g. `':'` or `a value`)
+    pub fn expected(&self) -> Option<&'static str> {
+        self.expected
+    }
+
+    /// Returns the path of the value that was being deserialized, like
+    /// `config.servers[2].port`. Empty for the top-level value.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the input that was being parsed
+    pub fn input(&self) -> &[u8] {
+        &self.input
+    }
+
+    /// Copies the input, so the error can outlive it (e.g. when the input
+    /// is a buffer that gets reused)
+    pub fn into_owned(self) -> JsonParseErrorWithContext<'static> {
+        JsonParseErrorWithContext {
+            input: Cow::Owned(self.input.into_owned()),
+            snippet: self.snippet,
+            kind: self.kind,
+            expected: self.expected,
+            path: self.path,
+            colors: self.colors,
+        }
+    }
+
+    fn style(&self, style: Style) -> Style {
+        if self.colors { style } else { Style::new() }
+    }
+}
+
+impl Display for JsonParseErrorWithContext<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let error = self.style(Style::new().fg_red().fg_bold());
+        let path = self.style(Style::new().fg_yellow());
+
+        write!(f, "{}: {}", "JSON parse error".style(error), self.kind)?;
+        if !self.path.is_empty() {
+            write!(f, " (at {})", self.path.as_str().style(path))?;
+        }
+        writeln!(f)?;
+        let label = self
+            .expected
+            .map(|expected| format!("expected {}", expected));
+        self.snippet.write(f, label.as_deref(), self.colors)
+    }
+}
+
+impl core::error::Error for JsonParseErrorWithContext<'_> {}
+
+/// The different kinds of errors that can occur while deserializing JSON
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum JsonErrorKind {
+    /// The input ended before a complete value could be read
+    UnexpectedEof,
+    /// A character that isn't valid at this position in a JSON document
+    UnexpectedCharacter(char),
+    /// A number that doesn't follow the JSON grammar
+    InvalidNumber,
+    /// An invalid escape sequence in a string
+    InvalidEscape,
+    /// A string that isn't valid UTF-8
+    InvalidUtf8,
+    /// Non-whitespace characters after the top-level value
+    TrailingCharacters,
+    /// The JSON value doesn't have the right type for the shape being deserialized
+    TypeMismatch {
+        /// The shape we were deserializing
+        expected: &'static Shape,
+        /// The kind of JSON value we got instead (e.g. "string", "object")
+        got: &'static str,
+    },
+    /// The value could not be converted into the shape being deserialized
+    /// (e.g. a number out of range, or a malformed IP address)
+    InvalidValue(&'static Shape),
+    /// The string contains escape sequences, so it can't be borrowed from the
+    /// input as a `&str` (deserialize into `Cow<str>` or `String` instead)
+    CannotBorrow(&'static Shape),
+    /// An array doesn't have as many elements as the fixed-size array it's deserialized into
+    WrongLength {
+        /// The number of elements of the fixed-size array
+        expected: usize,
+        /// The number of elements in the JSON array
+        got: usize,
+    },
+    /// A field required by a struct was not present in the object
+    MissingField(&'static str),
+    /// A field appears more than once in the object
+    DuplicateField(&'static str),
+    /// The object has a key that isn't a field of a struct with
+    /// `#[facet(deny_unknown_fields)]`
+    UnknownField(String),
+    /// The enum has no variant with this name
+    UnknownVariant(String),
+    /// None of the variants of an untagged enum could be deserialized from the value
+    NoMatchingVariant(&'static Shape),
+    /// The shape is not supported by the JSON deserializer
+    UnsupportedShape(&'static Shape),
+    /// Arrays and objects are nested more deeply than the parser allows
+    DepthLimitExceeded,
+    /// An error occurred while building the value
+    ReflectError(ReflectError),
+}
+
+impl Display for JsonErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonErrorKind::UnexpectedEof => write!(f, "Unexpected end of input"),
+            JsonErrorKind::UnexpectedCharacter(c) => write!(f, "Unexpected character: {:?}", c),
+            JsonErrorKind::InvalidNumber => write!(f, "Invalid number"),
+            JsonErrorKind::InvalidEscape => write!(f, "Invalid escape sequence"),
+            JsonErrorKind::InvalidUtf8 => write!(f, "Invalid UTF-8"),
+            JsonErrorKind::TrailingCharacters => write!(f, "Trailing characters after value"),
+            JsonErrorKind::TypeMismatch { expected, got } => {
+                write!(f, "Expected {}, got {}", expected, got)
+            }
+            JsonErrorKind::InvalidValue(shape) => write!(f, "Invalid value for {}", shape),
+            JsonErrorKind::CannotBorrow(shape) => write!(
+                f,
+                "Cannot borrow an escaped string as {}, use Cow<str> or String",
+                shape
+            ),
+            JsonErrorKind::WrongLength { expected, got } => {
+                write!(f, "Expected {} elements, got {}", expected, got)
+            }
+            JsonErrorKind::MissingField(name) => write!(f, "Missing field: {}", name),
+            JsonErrorKind::DuplicateField(name) => write!(f, "Duplicate field: {}", name),
+            JsonErrorKind::UnknownField(name) => write!(f, "Unknown field: {}", name),
+            JsonErrorKind::UnknownVariant(name) => write!(f, "Unknown variant: {}", name),
+            JsonErrorKind::NoMatchingVariant(shape) => {
+                write!(f, "Data did not match any variant of {}", shape)
+            }
+            JsonErrorKind::UnsupportedShape(shape) => write!(f, "Unsupported shape: {}", shape),
+            JsonErrorKind::DepthLimitExceeded => {
+                write!(f, "Arrays and objects nested too deeply")
+            }
+            JsonErrorKind::ReflectError(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// An error serializing a value to JSON
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum JsonSerializeError {
+    /// The writer returned an error
+    Write,
+    /// The value (or part of it) can't be represented as JSON
+    Unsupported(String),
+    /// A NaN or infinite float, which canonical JSON can't represent
+    NonFiniteFloat,
+}
+
+impl From<fmt::Error> for JsonSerializeError {
+    fn from(_: fmt::Error) -> Self {
+        JsonSerializeError::Write
+    }
+}
+
+impl Display for JsonSerializeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonSerializeError::Write => write!(f, "Failed to write JSON"),
+            JsonSerializeError::Unsupported(message) => write!(f, "{}", message),
+            JsonSerializeError::NonFiniteFloat => write!(
+                f,
+                "NaN and infinite floats can't be represented in canonical JSON"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for JsonSerializeError {}
@@ -11,10 +11,16 @@ extern crate alloc;
 #[cfg(not(feature = "alloc"))]
 compile_error!("feature `alloc` is required");
 
+mod error;
+pub use error::{JsonErrorKind, JsonParseErrorWithContext, JsonSerializeError};
+
 mod deserialize;
 pub use deserialize::*;
 
-#[cfg(feature = "std")]
 mod serialize;
-#[cfg(feature = "std")]
 pub use serialize::*;
+
+#[cfg(feature = "std")]
+mod ndjson;
+#[cfg(feature = "std")]
+pub use ndjson::*;
@@ -0,0 +1,226 @@
+//! Newline-delimited JSON (also known as JSON Lines): one JSON value per line
+
+use crate::{JsonParseErrorWithContext, JsonWriterOptions, from_slice, to_writer_with_options};
+use core::fmt::{self, Display, Formatter};
+use core::marker::PhantomData;
+use facet_core::Facet;
+use std::io::{self, BufRead, Write};
+
+/// Reads newline-delimited JSON from `reader`, one record per line.
+///
+/// Records are deserialized lazily, as the returned iterator is advanced, so
+/// the input never has to fit in memory. Blank lines are skipped, and both
+/// `\n` and `\r\n` line endings are accepted.
+///
+/// Since each line is read into a buffer that gets reused, `T` can't borrow
+/// from the input: use `String` rather than `&str`.
+pub fn from_json_lines<T, R>(reader: R) -> JsonLinesReader<R, T>
+where
+    T: for<'a> Facet<'a>,
+    R: BufRead,
+{
+    JsonLinesReader {
+        reader,
+        buf: Vec::new(),
+        line: 0,
+        record: 0,
+        done: false,
+        phantom: PhantomData,
+    }
+}
+
+/// Iterator over the records of a newline-delimited JSON stream, returned by
+/// [`from_json_lines`]
+pub struct JsonLinesReader<R, T> {
+    reader: R,
+    buf: Vec<u8>,
+    /// 1-based number of the last line read
+    line: usize,
+    /// 1-based number of the last record read
+    record: usize,
+    /// set after an I/O error, or at the end of the input
+    done: bool,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<R, T> JsonLinesReader<R, T> {
+    /// Returns the 1-based number of the last record read (0 before the first one)
+    pub fn record(&self) -> usize {
+        self.record
+    }
+
+    /// Returns the underlying reader
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R, T> Iterator for JsonLinesReader<R, T>
+where
+    T: for<'a> Facet<'a>,
+    R: BufRead,
+{
+    type Item = Result<T, JsonLinesError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            self.buf.clear();
+            match self.reader.read_until(b'\n', &mut self.buf) {
+                Ok(0) => self.done = true,
+                Ok(_) => {
+                    self.line += 1;
+                    let mut record = self.buf.as_slice();
+                    if let Some(rest) = record.strip_suffix(b"\n") {
+                        record = rest.strip_suffix(b"\r").unwrap_or(rest);
+                    }
+                    if record.iter().all(u8::is_ascii_whitespace) {
+                        continue;
+                    }
+
+                    self.record += 1;
+                    return Some(from_slice::<T>(record).map_err(|e| JsonLinesError {
+                        record: self.record,
+                        line: self.line,
+                        kind: JsonLinesErrorKind::Parse(e.into_owned()),
+                    }));
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(JsonLinesError {
+                        record: self.record + 1,
+                        line: self.line + 1,
+                        kind: JsonLinesErrorKind::Io(e),
+                    }));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Writes values as newline-delimited JSON: each value minified on its own line
+pub struct JsonLinesWriter<W: Write> {
+    writer: W,
+    options: JsonWriterOptions,
+    record: usize,
+}
+
+impl<W: Write> JsonLinesWriter<W> {
+    /// Creates a writer that writes records to `writer`
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            // pretty-printing would split records over several lines
+            options: JsonWriterOptions::default().with_trailing_newline(true),
+            record: 0,
+        }
+    }
+
+    /// Sorts the keys of maps in every record (see [`JsonWriterOptions::with_sort_keys`])
+    pub fn with_sort_keys(mut self, sort_keys: bool) -> Self {
+        self.options = self.options.with_sort_keys(sort_keys);
+        self
+    }
+
+    /// Writes a single record
+    pub fn write<'a, T: Facet<'a>>(&mut self, value: &T) -> Result<(), JsonLinesError> {
+        self.record += 1;
+        to_writer_with_options(value, &mut self.writer, &self.options).map_err(|e| JsonLinesError {
+            record: self.record,
+            line: self.record,
+            kind: JsonLinesErrorKind::Io(e),
+        })
+    }
+
+    /// Returns the number of records written so far
+    pub fn record(&self) -> usize {
+        self.record
+    }
+
+    /// Flushes the underlying writer
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Returns the underlying writer
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Writes every value of `values` to `writer` as newline-delimited JSON.
+///
+/// To write values you only have references to, use [`JsonLinesWriter`].
+pub fn to_writer_json_lines<'a, T, I, W>(values: I, writer: &mut W) -> Result<(), JsonLinesError>
+where
+    T: Facet<'a>,
+    I: IntoIterator<Item = T>,
+    W: Write,
+{
+    let mut lines = JsonLinesWriter::new(writer);
+    for value in values {
+        lines.write(&value)?;
+    }
+    Ok(())
+}
+
+/// An error reading or writing a record of newline-delimited JSON
+#[derive(Debug)]
+pub struct JsonLinesError {
+    record: usize,
+    line: usize,
+    kind: JsonLinesErrorKind,
+}
+
+/// What went wrong with a record of newline-delimited JSON
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum JsonLinesErrorKind {
+    /// Reading or writing failed
+    Io(io::Error),
+    /// The record is not valid JSON, or doesn't match the type being deserialized
+    Parse(JsonParseErrorWithContext<'static>),
+}
+
+impl JsonLinesError {
+    /// Returns the 1-based number of the record the error is about. Blank
+    /// lines don't count as records.
+    pub fn record(&self) -> usize {
+        self.record
+    }
+
+    /// Returns the 1-based line of the input (or output) the record is on
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns what went wrong
+    pub fn kind(&self) -> &JsonLinesErrorKind {
+        &self.kind
+    }
+
+    /// Returns what went wrong, by value
+    pub fn into_kind(self) -> JsonLinesErrorKind {
+        self.kind
+    }
+}
+
+impl Display for JsonLinesError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "record {} (line {}): ", self.record, self.line)?;
+        match &self.kind {
+            JsonLinesErrorKind::Io(e) => write!(f, "{}", e),
+            JsonLinesErrorKind::Parse(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl core::error::Error for JsonLinesError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match &self.kind {
+            JsonLinesErrorKind::Io(e) => Some(e),
+            JsonLinesErrorKind::Parse(e) => Some(e),
+        }
+    }
+}
@@ -1,65 +1,646 @@
+use crate::JsonSerializeError;
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
 use core::num::NonZero;
-use facet_core::{Def, Facet};
-use facet_reflect::Peek;
-use std::io::{self, Write};
+use facet_core::{Def, EnumTagging, Facet, StructKind};
+use facet_reflect::{Peek, PeekEnum};
+#[cfg(feature = "std")]
+use std::io;
+
+/// Options controlling the JSON output of [`to_string_with_options`] and friends.
+///
+/// The default options produce minified JSON, exactly like [`to_string`].
+#[derive(Debug, Clone)]
+pub struct JsonWriterOptions {
+    pretty: bool,
+    indent_size: usize,
+    use_tabs: bool,
+    trailing_newline: bool,
+    sort_keys: bool,
+    canonical: bool,
+}
+
+impl Default for JsonWriterOptions {
+    fn default() -> Self {
+        Self {
+            pretty: false,
+            indent_size: 2,
+            use_tabs: false,
+            trailing_newline: false,
+            sort_keys: false,
+            canonical: false,
+        }
+    }
+}
+
+impl JsonWriterOptions {
+    /// Options for minified output
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Options for pretty output: one item per line, indented by two spaces
+    pub fn pretty() -> Self {
+        Self {
+            pretty: true,
+            ..Self::default()
+        }
+    }
+
+    /// Options for canonical output, as specified by
+    /// [RFC 8785](https://www.rfc-editor.org/rfc/rfc8785) (JSON Canonicalization Scheme):
+    ///
+    ///   - no whitespace
+    ///   - the keys of all objects (struct fields and map entries alike) are
+    ///     sorted by their UTF-16 code units
+    ///   - `f32` and `f64` are formatted like ECMAScript's `Number.prototype.toString`,
+    ///     and NaN or infinite values are an error
+    ///   - strings only escape what JSON requires them to
+    ///
+    /// Integers are written in full, so to be interoperable they should stay
+    /// within ±2^53.
+    pub fn canonical() -> Self {
+        Self {
+            canonical: true,
+            ..Self::default()
+        }
+    }
+
+    /// Set the number of spaces per indentation level (pretty output only)
+    pub fn with_indent_size(mut self, size: usize) -> Self {
+        self.indent_size = size;
+        self
+    }
+
+    /// Indent with one tab per level instead of spaces (pretty output only)
+    pub fn with_tabs(mut self, use_tabs: bool) -> Self {
+        self.use_tabs = use_tabs;
+        self
+    }
+
+    /// End the output with a newline
+    pub fn with_trailing_newline(mut self, trailing_newline: bool) -> Self {
+        self.trailing_newline = trailing_newline;
+        self
+    }
+
+    /// Write the entries of maps sorted by key, rather than in iteration order.
+    /// Struct fields are written in declaration order, unless the output is
+    /// [canonical](Self::canonical).
+    pub fn with_sort_keys(mut self, sort_keys: bool) -> Self {
+        self.sort_keys = sort_keys;
+        self
+    }
+}
 
 /// Serializes a value to JSON
-pub fn to_string<T: Facet>(value: &T) -> String {
-    let peek = Peek::new(value);
-    let mut output = Vec::new();
-    serialize(&peek, &mut output).unwrap();
-    String::from_utf8(output).unwrap()
+///
+/// # Panics
+///
+/// Panics if the value can't be represented as JSON. [`to_string_with_options`]
+/// and [`to_vec`] report that as an error instead.
+pub fn to_string<'a, T: Facet<'a>>(value: &T) -> String {
+    peek_to_string(&Peek::new(value))
+}
+
+/// Serializes a value to pretty-printed JSON, indented by two spaces
+///
+/// # Panics
+///
+/// Panics if the value can't be represented as JSON, like [`to_string`].
+pub fn to_string_pretty<'a, T: Facet<'a>>(value: &T) -> String {
+    peek_to_string_with_options(&Peek::new(value), &JsonWriterOptions::pretty()).unwrap()
+}
+
+/// Serializes a value to canonical JSON (see [`JsonWriterOptions::canonical`])
+pub fn to_string_canonical<'a, T: Facet<'a>>(value: &T) -> Result<String, JsonSerializeError> {
+    to_string_with_options(value, &JsonWriterOptions::canonical())
+}
+
+/// Serializes a value to JSON, with the given options
+pub fn to_string_with_options<'a, T: Facet<'a>>(
+    value: &T,
+    options: &JsonWriterOptions,
+) -> Result<String, JsonSerializeError> {
+    peek_to_string_with_options(&Peek::new(value), options)
 }
 
 /// Serializes a Peek instance to JSON
+///
+/// # Panics
+///
+/// Panics if the value can't be represented as JSON, like [`to_string`].
 pub fn peek_to_string(peek: &Peek<'_>) -> String {
-    let mut output = Vec::new();
-    serialize(peek, &mut output).unwrap();
-    String::from_utf8(output).unwrap()
+    peek_to_string_with_options(peek, &JsonWriterOptions::default()).unwrap()
+}
+
+/// Serializes a Peek instance to JSON, with the given options
+pub fn peek_to_string_with_options(
+    peek: &Peek<'_>,
+    options: &JsonWriterOptions,
+) -> Result<String, JsonSerializeError> {
+    let mut output = String::new();
+    peek_to_fmt_writer_with_options(peek, &mut output, options)?;
+    Ok(output)
+}
+
+/// Serializes a value to JSON bytes. Unlike [`to_string`], this reports
+/// unsupported values as errors rather than panicking.
+pub fn to_vec<'a, T: Facet<'a>>(value: &T) -> Result<Vec<u8>, JsonSerializeError> {
+    to_vec_with_options(value, &JsonWriterOptions::default())
+}
+
+/// Serializes a value to JSON bytes, with the given options
+pub fn to_vec_with_options<'a, T: Facet<'a>>(
+    value: &T,
+    options: &JsonWriterOptions,
+) -> Result<Vec<u8>, JsonSerializeError> {
+    let mut output = String::new();
+    to_fmt_writer_with_options(value, &mut output, options)?;
+    Ok(output.into_bytes())
+}
+
+/// Serializes a value as JSON to a [`core::fmt::Write`], like a `String` or a
+/// fixed-size buffer. This works without `std`.
+pub fn to_fmt_writer<'a, T: Facet<'a>, W: fmt::Write>(
+    value: &T,
+    writer: &mut W,
+) -> Result<(), JsonSerializeError> {
+    peek_to_fmt_writer(&Peek::new(value), writer)
+}
+
+/// Serializes a value as JSON to a [`core::fmt::Write`], with the given options
+pub fn to_fmt_writer_with_options<'a, T: Facet<'a>, W: fmt::Write>(
+    value: &T,
+    writer: &mut W,
+    options: &JsonWriterOptions,
+) -> Result<(), JsonSerializeError> {
+    peek_to_fmt_writer_with_options(&Peek::new(value), writer, options)
+}
+
+/// Serializes a Peek instance as JSON to a [`core::fmt::Write`]
+pub fn peek_to_fmt_writer<W: fmt::Write>(
+    peek: &Peek<'_>,
+    writer: &mut W,
+) -> Result<(), JsonSerializeError> {
+    peek_to_fmt_writer_with_options(peek, writer, &JsonWriterOptions::default())
+}
+
+/// Serializes a Peek instance as JSON to a [`core::fmt::Write`], with the given options
+pub fn peek_to_fmt_writer_with_options<W: fmt::Write>(
+    peek: &Peek<'_>,
+    writer: &mut W,
+    options: &JsonWriterOptions,
+) -> Result<(), JsonSerializeError> {
+    let mut serializer = Serializer {
+        writer,
+        options,
+        depth: 0,
+    };
+    serializer.serialize(peek)?;
+    if options.trailing_newline {
+        serializer.write("\n")?;
+    }
+    Ok(())
 }
 
 /// Serializes a value to a writer in JSON format
-pub fn to_writer<T: Facet, W: Write>(value: &T, writer: &mut W) -> io::Result<()> {
-    let peek = Peek::new(value);
-    serialize(&peek, writer)
+#[cfg(feature = "std")]
+pub fn to_writer<'a, T: Facet<'a>, W: io::Write>(value: &T, writer: &mut W) -> io::Result<()> {
+    peek_to_writer(&Peek::new(value), writer)
+}
+
+/// Serializes a value to a writer in JSON format, with the given options
+#[cfg(feature = "std")]
+pub fn to_writer_with_options<'a, T: Facet<'a>, W: io::Write>(
+    value: &T,
+    writer: &mut W,
+    options: &JsonWriterOptions,
+) -> io::Result<()> {
+    peek_to_writer_with_options(&Peek::new(value), writer, options)
 }
 
 /// Serializes a Peek instance to a writer in JSON format
-pub fn peek_to_writer<W: Write>(peek: &Peek<'_>, writer: &mut W) -> io::Result<()> {
-    serialize(peek, writer)
+#[cfg(feature = "std")]
+pub fn peek_to_writer<W: io::Write>(peek: &Peek<'_>, writer: &mut W) -> io::Result<()> {
+    peek_to_writer_with_options(peek, writer, &JsonWriterOptions::default())
 }
 
-/// The core serialization function
-fn serialize<W: Write>(peek: &Peek<'_>, writer: &mut W) -> io::Result<()> {
-    match peek.shape().def {
-        Def::Scalar(_) => serialize_scalar(peek, writer),
-        Def::Struct(_) => serialize_struct(peek, writer),
-        Def::List(_) => serialize_list(peek, writer),
-        Def::Map(_) => serialize_map(peek, writer),
-        Def::Enum(_) => serialize_enum(peek, writer),
-        Def::Option(_) => serialize_option(peek, writer),
-        _ => Err(io::Error::new(
-            io::ErrorKind::Other,
-            format!("Unsupported type: {}", peek.shape()),
-        )),
+/// Serializes a Peek instance to a writer in JSON format, with the given options
+#[cfg(feature = "std")]
+pub fn peek_to_writer_with_options<W: io::Write>(
+    peek: &Peek<'_>,
+    writer: &mut W,
+    options: &JsonWriterOptions,
+) -> io::Result<()> {
+    let mut adapter = IoWriter {
+        inner: writer,
+        error: None,
+    };
+    match peek_to_fmt_writer_with_options(peek, &mut adapter, options) {
+        Ok(()) => Ok(()),
+        Err(JsonSerializeError::Write) => Err(adapter
+            .error
+            .unwrap_or_else(|| io::Error::other("formatter error"))),
+        Err(e @ JsonSerializeError::NonFiniteFloat) => {
+            Err(io::Error::new(io::ErrorKind::InvalidData, e))
+        }
+        Err(e) => Err(io::Error::other(e)),
     }
 }
 
-/// Serializes a scalar value to JSON
-fn serialize_scalar<W: Write>(peek: &Peek<'_>, writer: &mut W) -> io::Result<()> {
+/// Lets the serializer write to an [`io::Write`], keeping hold of the
+/// actual I/O error (`core::fmt::Error` carries no information)
+#[cfg(feature = "std")]
+struct IoWriter<'w, W: io::Write> {
+    inner: &'w mut W,
+    error: Option<io::Error>,
+}
+
+#[cfg(feature = "std")]
+impl<W: io::Write> fmt::Write for IoWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+}
+
+/// Walks a value with [`Peek`], writing it out as JSON
+struct Serializer<'w, W: fmt::Write> {
+    writer: &'w mut W,
+    options: &'w JsonWriterOptions,
+    /// How many arrays/objects deep we currently are, for indentation
+    depth: usize,
+}
+
+impl<W: fmt::Write> Serializer<'_, W> {
+    /// The core serialization function
+    fn serialize(&mut self, peek: &Peek<'_>) -> Result<(), JsonSerializeError> {
+        match peek.shape().def {
+            Def::Scalar(_) => serialize_scalar(peek, self.writer, self.options.canonical),
+            Def::Struct(_) => self.serialize_struct(peek),
+            Def::List(_) => self.serialize_list(peek),
+            Def::Array(_) => self.serialize_array(peek),
+            Def::Slice(_) => self.serialize_slice(peek),
+            Def::Map(_) => self.serialize_map(peek),
+            Def::Enum(_) => self.serialize_enum(peek),
+            Def::Option(_) => self.serialize_option(peek),
+            Def::SmartPointer(_) => self.serialize_smart_pointer(peek),
+            _ => Err(JsonSerializeError::Unsupported(format!(
+                "Unsupported type: {}",
+                peek.shape()
+            ))),
+        }
+    }
+
+    /// Writes a piece of JSON as is
+    fn write(&mut self, s: &str) -> Result<(), JsonSerializeError> {
+        Ok(self.writer.write_str(s)?)
+    }
+
+    /// Writes the opening bracket of an array or object
+    fn open(&mut self, bracket: char) -> Result<(), JsonSerializeError> {
+        self.depth += 1;
+        Ok(self.writer.write_char(bracket)?)
+    }
+
+    /// Writes the separator before an item of an array or object
+    fn item(&mut self, first: bool) -> Result<(), JsonSerializeError> {
+        if !first {
+            self.write(",")?;
+        }
+        self.newline()
+    }
+
+    /// Writes the closing bracket of an array or object
+    fn close(&mut self, bracket: char, empty: bool) -> Result<(), JsonSerializeError> {
+        self.depth -= 1;
+        if !empty {
+            self.newline()?;
+        }
+        Ok(self.writer.write_char(bracket)?)
+    }
+
+    /// Starts a new line at the current depth (pretty output only)
+    fn newline(&mut self) -> Result<(), JsonSerializeError> {
+        if !self.options.pretty {
+            return Ok(());
+        }
+        self.write("\n")?;
+        if self.options.use_tabs {
+            for _ in 0..self.depth {
+                self.write("\t")?;
+            }
+        } else {
+            for _ in 0..self.depth * self.options.indent_size {
+                self.write(" ")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes a JSON string
+    fn string(&mut self, s: &str) -> Result<(), JsonSerializeError> {
+        Ok(write_json_string(self.writer, s, self.options.canonical)?)
+    }
+
+    /// Writes an object key, followed by a colon
+    fn key(&mut self, key: &str) -> Result<(), JsonSerializeError> {
+        self.string(key)?;
+        if self.options.pretty {
+            self.write(": ")
+        } else {
+            self.write(":")
+        }
+    }
+
+    /// Serializes a struct to JSON. Tuples and tuple structs are written as arrays.
+    fn serialize_struct(&mut self, peek: &Peek<'_>) -> Result<(), JsonSerializeError> {
+        let struct_peek = peek
+            .into_struct()
+            .map_err(|e| JsonSerializeError::Unsupported(format!("Not a struct: {}", e)))?;
+
+        if matches!(
+            struct_peek.def().kind,
+            StructKind::Tuple | StructKind::TupleStruct
+        ) {
+            return self.serialize_items(struct_peek.fields().map(|(_, field_peek)| field_peek));
+        }
+
+        self.serialize_object(
+            struct_peek
+                .fields()
+                .map(|(field, field_peek)| (Cow::Borrowed(field.name), field_peek))
+                .collect(),
+        )
+    }
+
+    /// Serializes key/value pairs as a JSON object. Canonical output has them
+    /// sorted by the UTF-16 code units of the keys; otherwise they're written in order.
+    fn serialize_object(
+        &mut self,
+        mut entries: Vec<(Cow<'_, str>, Peek<'_>)>,
+    ) -> Result<(), JsonSerializeError> {
+        if self.options.canonical {
+            entries.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+        }
+
+        self.open('{')?;
+
+        let mut first = true;
+        for (key, value) in entries {
+            self.item(first)?;
+            first = false;
+
+            self.key(&key)?;
+            self.serialize(&value)?;
+        }
+
+        self.close('}', first)
+    }
+
+    /// Serializes a list to JSON
+    fn serialize_list(&mut self, peek: &Peek<'_>) -> Result<(), JsonSerializeError> {
+        let list_peek = peek
+            .into_list()
+            .map_err(|e| JsonSerializeError::Unsupported(format!("Not a list: {}", e)))?;
+        self.serialize_items(list_peek.iter())
+    }
+
+    /// Serializes a fixed-size array to JSON
+    fn serialize_array(&mut self, peek: &Peek<'_>) -> Result<(), JsonSerializeError> {
+        let array_peek = peek
+            .into_array()
+            .map_err(|e| JsonSerializeError::Unsupported(format!("Not an array: {}", e)))?;
+        self.serialize_items(array_peek.iter())
+    }
+
+    /// Serializes a slice to JSON
+    fn serialize_slice(&mut self, peek: &Peek<'_>) -> Result<(), JsonSerializeError> {
+        let slice_peek = peek
+            .into_slice()
+            .map_err(|e| JsonSerializeError::Unsupported(format!("Not a slice: {}", e)))?;
+        self.serialize_items(slice_peek.iter())
+    }
+
+    /// Serializes a sequence of values as a JSON array
+    fn serialize_items<'mem>(
+        &mut self,
+        items: impl Iterator<Item = Peek<'mem>>,
+    ) -> Result<(), JsonSerializeError> {
+        self.open('[')?;
+
+        let mut first = true;
+        for item_peek in items {
+            self.item(first)?;
+            first = false;
+
+            self.serialize(&item_peek)?;
+        }
+
+        self.close(']', first)
+    }
+
+    /// Serializes a map to JSON
+    fn serialize_map(&mut self, peek: &Peek<'_>) -> Result<(), JsonSerializeError> {
+        let map_peek = peek
+            .into_map()
+            .map_err(|e| JsonSerializeError::Unsupported(format!("Not a map: {}", e)))?;
+
+        // JSON keys are strings, so render them up front (which also lets us sort them)
+        let mut entries = Vec::new();
+        for (key, value) in map_peek.iter() {
+            entries.push((Cow::<str>::Owned(map_key_to_string(&key)?), value));
+        }
+        if self.options.sort_keys && !self.options.canonical {
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+        }
+
+        self.serialize_object(entries)
+    }
+
+    /// Serializes an enum to JSON, using the representation selected by the
+    /// enum's tagging attributes (see [`EnumTagging`])
+    fn serialize_enum(&mut self, peek: &Peek<'_>) -> Result<(), JsonSerializeError> {
+        let enum_peek = peek
+            .into_enum()
+            .map_err(|e| JsonSerializeError::Unsupported(format!("Not an enum: {}", e)))?;
+
+        let variant = enum_peek.active_variant();
+        let is_unit = variant.data.kind == StructKind::Unit;
+
+        match peek.shape().enum_tagging() {
+            EnumTagging::Untagged => {
+                if is_unit {
+                    self.write("null")
+                } else {
+                    self.serialize_variant_payload(enum_peek)
+                }
+            }
+            EnumTagging::External => {
+                if is_unit {
+                    // Unit variant - just output the name as a string
+                    return self.string(variant.name);
+                }
+
+                // Variant with data - output as an object with a single key
+                self.open('{')?;
+                self.item(true)?;
+                self.key(variant.name)?;
+                self.serialize_variant_payload(enum_peek)?;
+                self.close('}', false)
+            }
+            EnumTagging::Adjacent { tag, content } => {
+                // canonical output wants the keys sorted, which may put the content first
+                let content_first = !is_unit
+                    && self.options.canonical
+                    && content.encode_utf16().lt(tag.encode_utf16());
+
+                self.open('{')?;
+                self.item(true)?;
+                if content_first {
+                    self.key(content)?;
+                    self.serialize_variant_payload(enum_peek)?;
+                    self.item(false)?;
+                }
+                self.key(tag)?;
+                self.string(variant.name)?;
+                if !is_unit && !content_first {
+                    self.item(false)?;
+                    self.key(content)?;
+                    self.serialize_variant_payload(enum_peek)?;
+                }
+                self.close('}', false)
+            }
+            EnumTagging::Internal { tag } => {
+                let mut entries = vec![(Cow::Borrowed(tag), Peek::new(&variant.name))];
+
+                let fields = enum_peek.internally_tagged_fields().ok_or_else(|| {
+                    JsonSerializeError::Unsupported(format!(
+                        "Internally tagged variant {}::{} must be a unit, struct or newtype struct variant",
+                        peek.shape(),
+                        variant.name
+                    ))
+                })?;
+                for (field, field_peek) in fields {
+                    entries.push((Cow::Borrowed(field.name), field_peek));
+                }
+
+                self.serialize_object(entries)
+            }
+            _ => Err(JsonSerializeError::Unsupported(format!(
+                "Unsupported enum tagging for {}",
+                peek.shape()
+            ))),
+        }
+    }
+
+    /// Serializes the data of the active variant: newtype variants as their
+    /// value, tuple variants as arrays and struct variants as objects
+    fn serialize_variant_payload(
+        &mut self,
+        enum_peek: PeekEnum<'_>,
+    ) -> Result<(), JsonSerializeError> {
+        let variant = enum_peek.active_variant();
+        match variant.data.kind {
+            StructKind::Unit => self.write("null"),
+            StructKind::Tuple | StructKind::TupleStruct if variant.data.fields.len() == 1 => {
+                let field = enum_peek.field(0).ok_or_else(|| {
+                    JsonSerializeError::Unsupported("Failed to access enum field".to_string())
+                })?;
+                self.serialize(&field)
+            }
+            StructKind::Tuple | StructKind::TupleStruct => {
+                self.serialize_items(enum_peek.fields().map(|(_, field_peek)| field_peek))
+            }
+            _ => self.serialize_object(
+                enum_peek
+                    .fields()
+                    .map(|(field, field_peek)| (Cow::Borrowed(field.name), field_peek))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Serializes a smart pointer (like `Arc<T>`) to JSON, as the value it points to
+    fn serialize_smart_pointer(&mut self, peek: &Peek<'_>) -> Result<(), JsonSerializeError> {
+        let smart_pointer_peek = peek
+            .into_smart_pointer()
+            .map_err(|e| JsonSerializeError::Unsupported(format!("Not a smart pointer: {}", e)))?;
+
+        let inner = smart_pointer_peek.borrow_inner().ok_or_else(|| {
+            JsonSerializeError::Unsupported(format!(
+                "Cannot borrow from smart pointer: {}",
+                peek.shape()
+            ))
+        })?;
+        self.serialize(&inner)
+    }
+
+    /// Serializes an `Option<T>` to JSON
+    fn serialize_option(&mut self, peek: &Peek<'_>) -> Result<(), JsonSerializeError> {
+        let option_peek = peek
+            .into_option()
+            .map_err(|e| JsonSerializeError::Unsupported(format!("Not an option: {}", e)))?;
+
+        if option_peek.is_none() {
+            self.write("null")
+        } else {
+            let value = option_peek.value().ok_or_else(|| {
+                JsonSerializeError::Unsupported("Failed to get option value".to_string())
+            })?;
+            self.serialize(&value)
+        }
+    }
+}
+
+/// Converts a map key to the string used as the JSON object key
+fn map_key_to_string(key: &Peek<'_>) -> Result<String, JsonSerializeError> {
+    match key.shape().def {
+        Def::Scalar(_) => {
+            if key.shape().is_type::<String>() {
+                Ok(key.get::<String>().unwrap().clone())
+            } else {
+                // For other scalar types, use their Display implementation
+                Ok(key.to_string())
+            }
+        }
+        _ => Err(JsonSerializeError::Unsupported(format!(
+            "Map keys must be scalar types, got: {}",
+            key.shape()
+        ))),
+    }
+}
+
+/// Serializes a scalar value to JSON. `canonical` selects minimal string
+/// escapes and ECMAScript number formatting.
+fn serialize_scalar<W: fmt::Write>(
+    peek: &Peek<'_>,
+    writer: &mut W,
+    canonical: bool,
+) -> Result<(), JsonSerializeError> {
     // Handle basic scalar types
-    if peek.shape().is_type::<bool>() {
+    let written = if peek.shape().is_type::<bool>() {
         let value = peek.get::<bool>().unwrap();
         write!(writer, "{}", if *value { "true" } else { "false" })
     } else if peek.shape().is_type::<String>() {
         let value = peek.get::<String>().unwrap();
-        write_json_string(writer, value)
+        write_json_string(writer, value, canonical)
     } else if peek.shape().is_type::<&str>() {
         let value = peek.get::<&str>().unwrap();
-        write_json_string(writer, value)
+        write_json_string(writer, value, canonical)
     } else if peek.shape().is_type::<alloc::borrow::Cow<'_, str>>() {
         let value = peek.get::<alloc::borrow::Cow<'_, str>>().unwrap();
-        write_json_string(writer, value)
+        write_json_string(writer, value, canonical)
+    } else if peek.shape().is_type::<char>() {
+        let value = peek.get::<char>().unwrap();
+        write_json_string(writer, value.encode_utf8(&mut [0; 4]), canonical)
     }
     // Integer types
     else if peek.shape().is_type::<u8>() {
@@ -74,6 +655,9 @@ fn serialize_scalar<W: Write>(peek: &Peek<'_>, writer: &mut W) -> io::Result<()>
     } else if peek.shape().is_type::<u64>() {
         let value = peek.get::<u64>().unwrap();
         write!(writer, "{}", value)
+    } else if peek.shape().is_type::<u128>() {
+        let value = peek.get::<u128>().unwrap();
+        write!(writer, "{}", value)
     } else if peek.shape().is_type::<usize>() {
         let value = peek.get::<usize>().unwrap();
         write!(writer, "{}", value)
@@ -89,6 +673,9 @@ fn serialize_scalar<W: Write>(peek: &Peek<'_>, writer: &mut W) -> io::Result<()>
     } else if peek.shape().is_type::<i64>() {
         let value = peek.get::<i64>().unwrap();
         write!(writer, "{}", value)
+    } else if peek.shape().is_type::<i128>() {
+        let value = peek.get::<i128>().unwrap();
+        write!(writer, "{}", value)
     } else if peek.shape().is_type::<isize>() {
         let value = peek.get::<isize>().unwrap();
         write!(writer, "{}", value)
@@ -106,6 +693,9 @@ fn serialize_scalar<W: Write>(peek: &Peek<'_>, writer: &mut W) -> io::Result<()>
     } else if peek.shape().is_type::<NonZero<u64>>() {
         let value = peek.get::<NonZero<u64>>().unwrap();
         write!(writer, "{}", value)
+    } else if peek.shape().is_type::<NonZero<u128>>() {
+        let value = peek.get::<NonZero<u128>>().unwrap();
+        write!(writer, "{}", value)
     } else if peek.shape().is_type::<NonZero<usize>>() {
         let value = peek.get::<NonZero<usize>>().unwrap();
         write!(writer, "{}", value)
@@ -121,6 +711,9 @@ fn serialize_scalar<W: Write>(peek: &Peek<'_>, writer: &mut W) -> io::Result<()>
     } else if peek.shape().is_type::<NonZero<i64>>() {
         let value = peek.get::<NonZero<i64>>().unwrap();
         write!(writer, "{}", value)
+    } else if peek.shape().is_type::<NonZero<i128>>() {
+        let value = peek.get::<NonZero<i128>>().unwrap();
+        write!(writer, "{}", value)
     } else if peek.shape().is_type::<NonZero<isize>>() {
         let value = peek.get::<NonZero<isize>>().unwrap();
         write!(writer, "{}", value)
@@ -128,220 +721,83 @@ fn serialize_scalar<W: Write>(peek: &Peek<'_>, writer: &mut W) -> io::Result<()>
     // Float types
     else if peek.shape().is_type::<f32>() {
         let value = peek.get::<f32>().unwrap();
-        write!(writer, "{}", value)
+        if canonical {
+            if !value.is_finite() {
+                return Err(JsonSerializeError::NonFiniteFloat);
+            }
+            write_ecmascript_number(writer, *value == 0.0, &format!("{:e}", value))
+        } else {
+            write!(writer, "{}", value)
+        }
     } else if peek.shape().is_type::<f64>() {
         let value = peek.get::<f64>().unwrap();
-        write!(writer, "{}", value)
-    } else {
-        Err(io::Error::new(
-            io::ErrorKind::Other,
-            format!("Unsupported scalar type: {}", peek.shape()),
-        ))
-    }
-}
-
-/// Serializes a struct to JSON
-fn serialize_struct<W: Write>(peek: &Peek<'_>, writer: &mut W) -> io::Result<()> {
-    let struct_peek = peek
-        .into_struct()
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("Not a struct: {}", e)))?;
-
-    write!(writer, "{{")?;
-
-    let mut first = true;
-    for (field, field_peek) in struct_peek.fields() {
-        if !first {
-            write!(writer, ",")?;
-        }
-        first = false;
-
-        // Write field name
-        write_json_string(writer, field.name)?;
-        write!(writer, ":")?;
-
-        // Write field value
-        serialize(&field_peek, writer)?;
-    }
-
-    write!(writer, "}}")?;
-
-    Ok(())
-}
-
-/// Serializes a list to JSON
-fn serialize_list<W: Write>(peek: &Peek<'_>, writer: &mut W) -> io::Result<()> {
-    let list_peek = peek
-        .into_list()
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("Not a list: {}", e)))?;
-
-    write!(writer, "[")?;
-
-    let mut first = true;
-    for item_peek in list_peek.iter() {
-        if !first {
-            write!(writer, ",")?;
-        }
-        first = false;
-
-        serialize(&item_peek, writer)?;
-    }
-
-    write!(writer, "]")?;
-
-    Ok(())
-}
-
-/// Serializes a map to JSON
-fn serialize_map<W: Write>(peek: &Peek<'_>, writer: &mut W) -> io::Result<()> {
-    let map_peek = peek
-        .into_map()
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("Not a map: {}", e)))?;
-
-    write!(writer, "{{")?;
-
-    let mut first = true;
-    for (key, value) in map_peek.iter() {
-        if !first {
-            write!(writer, ",")?;
-        }
-        first = false;
-
-        // For map, keys must be converted to strings
-        match key.shape().def {
-            Def::Scalar(_) => {
-                // Try to convert key to string
-                if key.shape().is_type::<String>() {
-                    let key_str = key.get::<String>().unwrap();
-                    write_json_string(writer, key_str)?;
-                } else {
-                    // For other scalar types, use their Display implementation
-                    write!(writer, "\"{}\"", key)?;
-                }
-            }
-            _ => {
-                return Err(io::Error::new(
-                    io::ErrorKind::Other,
-                    format!("Map keys must be scalar types, got: {}", key.shape()),
-                ));
+        if canonical {
+            if !value.is_finite() {
+                return Err(JsonSerializeError::NonFiniteFloat);
             }
-        }
-
-        write!(writer, ":")?;
-
-        // Write map value
-        serialize(&value, writer)?;
-    }
-
-    write!(writer, "}}")?;
-
-    Ok(())
-}
-
-/// Serializes an enum to JSON
-fn serialize_enum<W: Write>(peek: &Peek<'_>, writer: &mut W) -> io::Result<()> {
-    let enum_peek = peek
-        .into_enum()
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("Not an enum: {}", e)))?;
-
-    let variant = enum_peek.active_variant();
-    let variant_name = variant.name;
-
-    // Check if this is a unit variant or a variant with data
-    if variant.data.fields.is_empty() {
-        // Unit variant - just output the name as a string
-        write_json_string(writer, variant_name)
-    } else {
-        // Variant with data - output as an object with a single key
-        write!(writer, "{{")?;
-        write_json_string(writer, variant_name)?;
-        write!(writer, ":")?;
-
-        // If it's a single-field tuple variant, output just the value
-        if variant.data.fields.len() == 1 {
-            let field = enum_peek.field(0).ok_or_else(|| {
-                io::Error::new(io::ErrorKind::Other, "Failed to access enum field")
-            })?;
-            serialize(&field, writer)?;
+            write_ecmascript_number(writer, *value == 0.0, &format!("{:e}", value))
         } else {
-            // Multi-field variant - output as an array or object depending on variant type
-            let is_struct = variant
-                .data
-                .fields
-                .iter()
-                .any(|f| !f.name.starts_with("__"));
-
-            if is_struct {
-                // Struct variant - output as an object
-                write!(writer, "{{")?;
-
-                let mut first = true;
-                for i in 0..variant.data.fields.len() {
-                    let field = enum_peek.field(i).ok_or_else(|| {
-                        io::Error::new(
-                            io::ErrorKind::Other,
-                            format!("Failed to access enum field {}", i),
-                        )
-                    })?;
-                    let field_name = variant.data.fields[i].name;
-
-                    if !first {
-                        write!(writer, ",")?;
-                    }
-                    first = false;
-
-                    write_json_string(writer, field_name)?;
-                    write!(writer, ":")?;
-                    serialize(&field, writer)?;
-                }
-
-                write!(writer, "}}")?
-            } else {
-                // Tuple variant - output as an array
-                write!(writer, "[")?;
-
-                let mut first = true;
-                for i in 0..variant.data.fields.len() {
-                    if !first {
-                        write!(writer, ",")?;
-                    }
-                    first = false;
-
-                    let field = enum_peek.field(i).ok_or_else(|| {
-                        io::Error::new(
-                            io::ErrorKind::Other,
-                            format!("Failed to access enum field {}", i),
-                        )
-                    })?;
-                    serialize(&field, writer)?;
-                }
-
-                write!(writer, "]")?;
-            }
+            write!(writer, "{}", value)
         }
-
-        write!(writer, "}}")?;
-        Ok(())
-    }
+    } else {
+        return Err(JsonSerializeError::Unsupported(format!(
+            "Unsupported scalar type: {}",
+            peek.shape()
+        )));
+    };
+    Ok(written?)
 }
 
-/// Serializes an `Option<T>` to JSON
-fn serialize_option<W: Write>(peek: &Peek<'_>, writer: &mut W) -> io::Result<()> {
-    let option_peek = peek
-        .into_option()
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("Not an option: {}", e)))?;
+/// Writes a float the way ECMAScript's `Number.prototype.toString` does, as
+/// RFC 8785 requires. `scientific` is the (finite) float formatted with `{:e}`,
+/// which gives the shortest digits that round-trip.
+fn write_ecmascript_number<W: fmt::Write>(
+    writer: &mut W,
+    is_zero: bool,
+    scientific: &str,
+) -> fmt::Result {
+    if is_zero {
+        // that includes -0
+        return writer.write_str("0");
+    }
 
-    if option_peek.is_none() {
-        write!(writer, "null")
+    let (mantissa, exponent) = scientific.split_once('e').unwrap();
+    let (sign, mantissa) = match mantissa.strip_prefix('-') {
+        Some(mantissa) => ("-", mantissa),
+        None => ("", mantissa),
+    };
+    let digits = mantissa.replace('.', "");
+    let k = digits.len() as i32;
+    // the value is 0.<digits> * 10^n
+    let n = exponent.parse::<i32>().unwrap() + 1;
+
+    write!(writer, "{}", sign)?;
+    if k <= n && n <= 21 {
+        write!(writer, "{}{}", digits, "0".repeat((n - k) as usize))
+    } else if 0 < n && n <= 21 {
+        let (int, frac) = digits.split_at(n as usize);
+        write!(writer, "{}.{}", int, frac)
+    } else if -6 < n && n <= 0 {
+        write!(writer, "0.{}{}", "0".repeat(-n as usize), digits)
     } else {
-        let value = option_peek
-            .value()
-            .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "Failed to get option value"))?;
-        serialize(&value, writer)
+        let (first, rest) = digits.split_at(1);
+        let dot = if rest.is_empty() { "" } else { "." };
+        let exp_sign = if n > 0 { "+" } else { "-" };
+        write!(
+            writer,
+            "{}{}{}e{}{}",
+            first,
+            dot,
+            rest,
+            exp_sign,
+            (n - 1).abs()
+        )
     }
 }
 
-/// Properly escapes and writes a JSON string
-fn write_json_string<W: Write>(writer: &mut W, s: &str) -> io::Result<()> {
+/// Properly escapes and writes a JSON string. With `minimal`, only the
+/// characters JSON requires to be escaped are.
+fn write_json_string<W: fmt::Write>(writer: &mut W, s: &str, minimal: bool) -> fmt::Result {
     write!(writer, "\"")?;
 
     for c in s.chars() {
@@ -353,7 +809,9 @@ fn write_json_string<W: Write>(writer: &mut W, s: &str) -> io::Result<()> {
             '\t' => write!(writer, "\\t")?,
             '\u{08}' => write!(writer, "\\b")?,
             '\u{0C}' => write!(writer, "\\f")?,
-            c if c.is_control() => write!(writer, "\\u{:04x}", c as u32)?,
+            c if (c as u32) < 0x20 || (!minimal && c.is_control()) => {
+                write!(writer, "\\u{:04x}", c as u32)?
+            }
             c => write!(writer, "{}", c)?,
         }
     }
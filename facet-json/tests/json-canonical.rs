@@ -0,0 +1,146 @@
+#![cfg(feature = "std")]
+
+use std::collections::HashMap;
+
+use facet::Facet;
+use facet_json::{JsonWriterOptions, to_string_canonical, to_string_with_options};
+
+#[test]
+fn canonical_sorts_struct_fields_and_map_entries() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Doc {
+        zebra: u32,
+        apple: Vec<u32>,
+        middle: HashMap<String, bool>,
+    }
+
+    let doc = Doc {
+        zebra: 1,
+        apple: vec![3, 2],
+        middle: (0..20).map(|i| (format!("k{i:02}"), i % 2 == 0)).collect(),
+    };
+    let json = to_string_canonical(&doc).unwrap();
+    let entries = (0..20)
+        .map(|i| format!("\"k{i:02}\":{}", i % 2 == 0))
+        .collect::<Vec<_>>()
+        .join(",");
+    assert_eq!(
+        json,
+        format!("{{\"apple\":[3,2],\"middle\":{{{entries}}},\"zebra\":1}}")
+    );
+
+    // pretty-printing or sort_keys options are irrelevant to canonical output
+    assert_eq!(
+        to_string_with_options(&doc, &JsonWriterOptions::canonical().with_sort_keys(true)).unwrap(),
+        json
+    );
+}
+
+#[test]
+fn canonical_orders_keys_by_utf16_code_units() {
+    facet_testhelpers::setup();
+
+    // U+1F600 is encoded as the surrogates D83D DE00, which sort before U+E000,
+    // even though it's the bigger code point
+    let map: HashMap<String, u32> = [
+        ("\u{e000}".to_string(), 1),
+        ("\u{1f600}".to_string(), 2),
+        ("a".to_string(), 3),
+        ("\u{80}".to_string(), 4),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(
+        to_string_canonical(&map).unwrap(),
+        "{\"a\":3,\"\u{80}\":4,\"\u{1f600}\":2,\"\u{e000}\":1}"
+    );
+}
+
+#[test]
+fn canonical_numbers() {
+    facet_testhelpers::setup();
+
+    let cases: &[(f64, &str)] = &[
+        (0.0, "0"),
+        (-0.0, "0"),
+        (1.0, "1"),
+        (-100.0, "-100"),
+        (0.1, "0.1"),
+        (123.456, "123.456"),
+        (1e20, "100000000000000000000"),
+        (1e21, "1e+21"),
+        (1.5e300, "1.5e+300"),
+        (1e-6, "0.000001"),
+        (1e-7, "1e-7"),
+        (-2.5e-10, "-2.5e-10"),
+        (5e-324, "5e-324"),
+        (f64::MAX, "1.7976931348623157e+308"),
+        (9007199254740992.0, "9007199254740992"),
+    ];
+    for &(value, expected) in cases {
+        assert_eq!(to_string_canonical(&value).unwrap(), expected, "{value:?}");
+    }
+
+    // f32s use their own shortest digits, not those of the widened f64
+    assert_eq!(to_string_canonical(&0.1f32).unwrap(), "0.1");
+    assert_eq!(
+        to_string_canonical(&3.4028235e38f32).unwrap(),
+        "3.4028235e+38"
+    );
+
+    for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+        assert!(to_string_canonical(&value).is_err(), "{value:?}");
+        assert!(
+            to_string_with_options(&value, &JsonWriterOptions::canonical()).is_err(),
+            "{value:?}"
+        );
+    }
+}
+
+#[test]
+fn canonical_strings_escape_minimally() {
+    facet_testhelpers::setup();
+
+    let s = "quote\" backslash\\ \u{8}\t\n\u{c}\r \u{1}\u{1f} del\u{7f} c1\u{85} é€😀";
+    assert_eq!(
+        to_string_canonical(&s.to_string()).unwrap(),
+        "\"quote\\\" backslash\\\\ \\b\\t\\n\\f\\r \\u0001\\u001f del\u{7f} c1\u{85} é€😀\""
+    );
+}
+
+#[test]
+fn canonical_tagged_enums() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    #[repr(u8)]
+    #[facet(tag = "type")]
+    #[allow(dead_code)]
+    enum Internal {
+        Move { y: i32, x: i32, z: i32 },
+    }
+
+    #[derive(Facet)]
+    #[repr(u8)]
+    #[facet(tag = "t", content = "c")]
+    #[allow(dead_code)]
+    enum Adjacent {
+        Stop,
+        Say(String),
+    }
+
+    assert_eq!(
+        to_string_canonical(&Internal::Move { y: 2, x: 1, z: 3 }).unwrap(),
+        r#"{"type":"Move","x":1,"y":2,"z":3}"#
+    );
+    assert_eq!(
+        to_string_canonical(&Adjacent::Say("hi".into())).unwrap(),
+        r#"{"c":"hi","t":"Say"}"#
+    );
+    assert_eq!(
+        to_string_canonical(&Adjacent::Stop).unwrap(),
+        r#"{"t":"Stop"}"#
+    );
+}
@@ -0,0 +1,253 @@
+#![cfg(feature = "std")]
+
+use facet::{EnumTagging, Facet, ShapeAttribute};
+use facet_json::{JsonErrorKind, from_str, to_string};
+
+#[derive(Facet, Debug, PartialEq)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[test]
+fn tagging_attributes_end_up_in_the_shape() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    #[repr(u8)]
+    #[facet(tag = "t", content = "c")]
+    #[allow(dead_code)]
+    enum Adjacent {
+        A,
+    }
+
+    assert_eq!(
+        Adjacent::SHAPE.attributes,
+        &[ShapeAttribute::Tag("t"), ShapeAttribute::Content("c")]
+    );
+    assert_eq!(
+        Adjacent::SHAPE.enum_tagging(),
+        EnumTagging::Adjacent {
+            tag: "t",
+            content: "c"
+        }
+    );
+    assert_eq!(Point::SHAPE.enum_tagging(), EnumTagging::External);
+
+    // in any order, and across attributes
+    #[derive(Facet)]
+    #[repr(u8)]
+    #[facet(content = "c", tag = "t")]
+    #[allow(dead_code)]
+    enum Swapped {
+        A,
+    }
+
+    #[derive(Facet)]
+    #[repr(u8)]
+    #[facet(content = "c")]
+    #[facet(tag = "t")]
+    #[allow(dead_code)]
+    enum Split {
+        A,
+    }
+
+    for shape in [Swapped::SHAPE, Split::SHAPE] {
+        assert_eq!(
+            shape.enum_tagging(),
+            EnumTagging::Adjacent {
+                tag: "t",
+                content: "c"
+            }
+        );
+    }
+}
+
+#[test]
+fn externally_tagged() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    enum Message {
+        Ping,
+        Text(String),
+        Move(i32, i32),
+        At(Point),
+        Resize { w: u32 },
+    }
+
+    let cases = [
+        (Message::Ping, r#""Ping""#),
+        (Message::Text("hi".into()), r#"{"Text":"hi"}"#),
+        (Message::Move(1, 2), r#"{"Move":[1,2]}"#),
+        (Message::At(Point { x: 1, y: 2 }), r#"{"At":{"x":1,"y":2}}"#),
+        // single-field struct variants are objects too, not newtypes
+        (Message::Resize { w: 3 }, r#"{"Resize":{"w":3}}"#),
+    ];
+    for (value, json) in cases {
+        assert_eq!(to_string(&value), json);
+        assert_eq!(from_str::<Message>(json).unwrap(), value);
+    }
+}
+
+#[test]
+fn internally_tagged() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    #[facet(tag = "type")]
+    enum Event {
+        Connected,
+        Message { from: String, body: String },
+        Moved(Point),
+    }
+
+    let cases = [
+        (Event::Connected, r#"{"type":"Connected"}"#),
+        (
+            Event::Message {
+                from: "amos".into(),
+                body: "hi".into(),
+            },
+            r#"{"type":"Message","from":"amos","body":"hi"}"#,
+        ),
+        (
+            Event::Moved(Point { x: 1, y: -1 }),
+            r#"{"type":"Moved","x":1,"y":-1}"#,
+        ),
+    ];
+    for (value, json) in cases {
+        assert_eq!(to_string(&value), json);
+        assert_eq!(from_str::<Event>(json).unwrap(), value);
+    }
+
+    // the tag doesn't have to come first
+    let event: Event = from_str(r#"{"body":"yo","from":"x","type":"Message"}"#).unwrap();
+    assert_eq!(
+        event,
+        Event::Message {
+            from: "x".into(),
+            body: "yo".into()
+        }
+    );
+
+    let err = from_str::<Event>(r#"{"from":"x"}"#).unwrap_err();
+    assert!(matches!(err.kind(), JsonErrorKind::MissingField("type")));
+
+    let err = from_str::<Event>(r#"{"type":"Nope"}"#).unwrap_err();
+    assert!(matches!(err.kind(), JsonErrorKind::UnknownVariant(name) if name == "Nope"));
+}
+
+#[test]
+fn internally_tagged_tuple_variant_is_an_error() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    #[facet(tag = "type")]
+    enum Bad {
+        Pair(u32, u32),
+    }
+
+    let mut out = Vec::new();
+    assert!(facet_json::to_writer(&Bad::Pair(1, 2), &mut out).is_err());
+    assert!(from_str::<Bad>(r#"{"type":"Pair"}"#).is_err());
+}
+
+#[test]
+fn adjacently_tagged() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    #[facet(tag = "t", content = "c")]
+    enum Cmd {
+        Stop,
+        Say(String),
+        Go(i32, i32),
+        Jump { height: u32 },
+    }
+
+    let cases = [
+        (Cmd::Stop, r#"{"t":"Stop"}"#),
+        (Cmd::Say("hello".into()), r#"{"t":"Say","c":"hello"}"#),
+        (Cmd::Go(3, 4), r#"{"t":"Go","c":[3,4]}"#),
+        (Cmd::Jump { height: 9 }, r#"{"t":"Jump","c":{"height":9}}"#),
+    ];
+    for (value, json) in cases {
+        assert_eq!(to_string(&value), json);
+        assert_eq!(from_str::<Cmd>(json).unwrap(), value);
+    }
+
+    // content before tag works as well
+    let cmd: Cmd = from_str(r#"{"c":[1,2],"t":"Go"}"#).unwrap();
+    assert_eq!(cmd, Cmd::Go(1, 2));
+
+    let err = from_str::<Cmd>(r#"{"t":"Say"}"#).unwrap_err();
+    assert!(matches!(err.kind(), JsonErrorKind::MissingField("c")));
+}
+
+#[test]
+fn untagged() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    #[facet(untagged)]
+    enum Value {
+        Nothing,
+        Number(u64),
+        Text(String),
+        Pair(u32, u32),
+        Point(Point),
+        Named { name: String },
+    }
+
+    let cases = [
+        (Value::Nothing, "null"),
+        (Value::Number(42), "42"),
+        (Value::Text("hi".into()), r#""hi""#),
+        (Value::Pair(1, 2), "[1,2]"),
+        (Value::Point(Point { x: 5, y: 6 }), r#"{"x":5,"y":6}"#),
+        (Value::Named { name: "bob".into() }, r#"{"name":"bob"}"#),
+    ];
+    for (value, json) in cases {
+        assert_eq!(to_string(&value), json);
+        assert_eq!(from_str::<Value>(json).unwrap(), value);
+    }
+
+    let err = from_str::<Value>("true").unwrap_err();
+    assert!(matches!(err.kind(), JsonErrorKind::NoMatchingVariant(_)));
+}
+
+#[test]
+fn tagged_enums_nested_in_structs() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    #[facet(tag = "kind")]
+    enum Shape {
+        Circle { r: f64 },
+        Square { side: f64 },
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Drawing {
+        shapes: Vec<Shape>,
+        label: Option<String>,
+    }
+
+    let drawing = Drawing {
+        shapes: vec![Shape::Circle { r: 1.5 }, Shape::Square { side: 2.0 }],
+        label: None,
+    };
+    let json = to_string(&drawing);
+    assert_eq!(
+        json,
+        r#"{"shapes":[{"kind":"Circle","r":1.5},{"kind":"Square","side":2}],"label":null}"#
+    );
+    assert_eq!(from_str::<Drawing>(&json).unwrap(), drawing);
+}
@@ -0,0 +1,189 @@
+use facet::Facet;
+use facet_json::{JsonErrorKind, from_str};
+
+#[derive(Facet, Debug)]
+struct Server {
+    host: String,
+    port: u16,
+}
+
+#[derive(Facet, Debug)]
+struct Config {
+    servers: Vec<Server>,
+}
+
+#[derive(Facet, Debug)]
+struct Root {
+    config: Config,
+}
+
+#[test]
+fn json_error_location_and_path() {
+    facet_testhelpers::setup();
+
+    let json = r#"{
+  "config": {
+    "servers": [
+      {"host": "a", "port": 80},
+      {"host": "b", "port": 81},
+      {"host": "c", "port": 80000}
+    ]
+  }
+}"#;
+
+    let err = from_str::<Root>(json).unwrap_err();
+    assert!(
+        matches!(err.kind(), JsonErrorKind::InvalidValue(_)),
+        "{err}"
+    );
+    assert_eq!(err.path(), "config.servers[2].port");
+    assert_eq!(err.line(), 6);
+    assert_eq!(err.column(), 29);
+    assert_eq!(err.position(), json.find("80000").unwrap());
+
+    assert_eq!(
+        err.to_string(),
+        r#"JSON parse error: Invalid value for u16 (at config.servers[2].port)
+ --> line 6, column 29 (byte 127)
+  |
+6 |       {"host": "c", "port": 80000}
+  |                             ^^^^^"#
+    );
+}
+
+#[test]
+fn json_error_expected_token() {
+    facet_testhelpers::setup();
+
+    let json = "{\n  \"config\" {}\n}";
+    let err = from_str::<Root>(json).unwrap_err();
+    assert!(
+        matches!(err.kind(), JsonErrorKind::UnexpectedCharacter('{')),
+        "{err}"
+    );
+    assert_eq!(err.expected(), Some("':'"));
+    assert_eq!((err.line(), err.column()), (2, 12));
+    assert!(
+        err.to_string().ends_with(
+            r#"
+2 |   "config" {}
+  |            ^ expected ':'"#
+        ),
+        "{err}"
+    );
+}
+
+#[test]
+fn json_error_type_mismatch_underlines_value() {
+    facet_testhelpers::setup();
+
+    let json = r#"{"config": {"servers": {"host": "a"}}}"#;
+    let err = from_str::<Root>(json).unwrap_err();
+    assert!(
+        matches!(
+            err.kind(),
+            JsonErrorKind::TypeMismatch { got: "object", .. }
+        ),
+        "{err}"
+    );
+    assert_eq!(err.path(), "config.servers");
+    assert!(
+        err.to_string().ends_with(
+            r#"
+1 | {"config": {"servers": {"host": "a"}}}
+  |                        ^^^^^^^^^^^^^"#
+        ),
+        "{err}"
+    );
+}
+
+#[test]
+fn json_error_missing_field_path() {
+    facet_testhelpers::setup();
+
+    let json = r#"{"config": {"servers": [{"host": "a"}]}}"#;
+    let err = from_str::<Root>(json).unwrap_err();
+    assert!(
+        matches!(err.kind(), JsonErrorKind::MissingField("port")),
+        "{err}"
+    );
+    assert_eq!(err.path(), "config.servers[0]");
+    assert_eq!(err.position(), json.find('}').unwrap());
+}
+
+#[test]
+fn json_error_long_line_is_windowed() {
+    facet_testhelpers::setup();
+
+    let json = format!("[{}\"x\"]", "1, ".repeat(100));
+    let err = from_str::<Vec<u32>>(&json).unwrap_err();
+    assert_eq!(err.path(), "[100]");
+    let rendered = err.to_string();
+    let snippet = rendered.lines().nth(3).unwrap();
+    assert!(snippet.starts_with("1 | …"), "{rendered}");
+    assert!(snippet.chars().count() < 100, "{rendered}");
+}
+
+#[test]
+fn json_error_colors() {
+    facet_testhelpers::setup();
+
+    let err = from_str::<u32>("true").unwrap_err();
+    assert!(!err.to_string().contains('\x1b'));
+    let err = err.with_colors(true);
+    assert!(err.to_string().contains('\x1b'));
+}
+
+#[test]
+fn json_error_depth_limit() {
+    facet_testhelpers::setup();
+
+    // an unknown field holding deeply nested arrays is rejected, not skipped
+    // until the stack overflows
+    let json = format!(
+        r#"{{"host": "a", "port": 80, "extra": {}{}}}"#,
+        "[".repeat(200_000),
+        "]".repeat(200_000)
+    );
+    let err = from_str::<Server>(&json).unwrap_err();
+    assert!(
+        matches!(err.kind(), JsonErrorKind::DepthLimitExceeded),
+        "{err}"
+    );
+    let err = facet_json::from_str_lenient::<Server>(&json).unwrap_err();
+    assert!(
+        matches!(err.kind(), JsonErrorKind::DepthLimitExceeded),
+        "{err}"
+    );
+
+    // nesting up to the limit is fine
+    let json = format!(
+        r#"{{"host": "a", "port": 80, "extra": {}{}}}"#,
+        "[".repeat(127),
+        "]".repeat(127)
+    );
+    assert_eq!(from_str::<Server>(&json).unwrap().port, 80);
+
+    let lines = format!("{}\n{{\"host\": \"b\", \"port\": 81}}\n", {
+        let mut deep = String::from(r#"{"host": "a", "port": 80, "x": "#);
+        deep.push_str(&"{\"y\": ".repeat(100_000));
+        deep
+    });
+    let mut records = facet_json::from_json_lines::<Server, _>(lines.as_bytes());
+    let err = records.next().unwrap().unwrap_err();
+    assert!(err.to_string().contains("nested too deeply"), "{err}");
+    assert_eq!(records.next().unwrap().unwrap().port, 81);
+}
+
+#[test]
+fn json_error_duplicate_field() {
+    facet_testhelpers::setup();
+
+    let json = r#"{"host": "a", "port": 80, "port": 81}"#;
+    let err = from_str::<Server>(json).unwrap_err();
+    assert!(
+        matches!(err.kind(), JsonErrorKind::DuplicateField("port")),
+        "{err}"
+    );
+    assert_eq!(err.position(), json.rfind("\"port\"").unwrap());
+}
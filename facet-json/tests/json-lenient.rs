@@ -0,0 +1,152 @@
+#![cfg(feature = "std")]
+
+use std::collections::HashMap;
+
+use facet::Facet;
+use facet_json::{JsonReaderOptions, from_str, from_str_lenient, from_str_with_options};
+
+#[derive(Facet, Debug, PartialEq)]
+struct Server {
+    host: String,
+    port: u16,
+    mask: u32,
+    offset: i64,
+    weights: Vec<f64>,
+    limits: HashMap<String, f32>,
+}
+
+const CONFIG: &str = r#"
+// where to listen
+{
+    host: 'localhost', /* single quotes are fine */
+    "port": +8080,
+    mask: 0xFF00,
+    offset: -0x10,
+    weights: [.5, 2., 1e3,],
+    limits: {
+        $upper: Infinity,
+        lower: -Infinity,
+        'not a number': NaN,
+    },
+}
+// the end
+"#;
+
+#[test]
+fn lenient_config() {
+    facet_testhelpers::setup();
+
+    let server: Server = from_str_lenient(CONFIG).unwrap();
+    assert_eq!(server.host, "localhost");
+    assert_eq!(server.port, 8080);
+    assert_eq!(server.mask, 0xFF00);
+    assert_eq!(server.offset, -16);
+    assert_eq!(server.weights, [0.5, 2.0, 1000.0]);
+    assert_eq!(server.limits["$upper"], f32::INFINITY);
+    assert_eq!(server.limits["lower"], f32::NEG_INFINITY);
+    assert!(server.limits["not a number"].is_nan());
+
+    let options = JsonReaderOptions::lenient();
+    let again: Server = from_str_with_options(CONFIG, &options).unwrap();
+    assert_eq!(again.mask, server.mask);
+}
+
+#[test]
+fn strict_is_the_default() {
+    facet_testhelpers::setup();
+
+    assert!(from_str::<Server>(CONFIG).is_err());
+
+    let cases = [
+        "[1, 2,]",
+        "[1 /* two */, 2]",
+        "// numbers\n[1]",
+        "[0x10]",
+        "[+1]",
+        "[.5]",
+        "[Infinity]",
+        "['a']",
+    ];
+    for json in cases {
+        assert!(from_str::<Vec<f64>>(json).is_err(), "{json}");
+        assert!(
+            from_str_with_options::<Vec<f64>>(json, &JsonReaderOptions::new()).is_err(),
+            "{json}"
+        );
+    }
+    assert!(from_str::<HashMap<String, u32>>("{a: 1}").is_err());
+}
+
+#[test]
+fn lenient_strings() {
+    facet_testhelpers::setup();
+
+    let s: String = from_str_lenient(r#"'it\'s "quoted"'"#).unwrap();
+    assert_eq!(s, r#"it's "quoted""#);
+
+    let s: String = from_str_lenient("'one \\\ntwo \\x41\\v\\0'").unwrap();
+    assert_eq!(s, "one two A\u{b}\0");
+
+    // unescaped strings are still borrowed
+    let s: &str = from_str_lenient("'borrowed'").unwrap();
+    assert_eq!(s, "borrowed");
+}
+
+#[test]
+fn lenient_tuples_and_enums() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    #[facet(tag = "kind")]
+    enum Shape {
+        Circle { r: f64 },
+        Line(Point),
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    enum Cmd {
+        Move(i32, i32),
+    }
+
+    let shapes: Vec<Shape> = from_str_lenient(
+        "[
+            { kind: 'Circle', r: 0x2 },
+            { x: 1, y: 2, kind: 'Line', }, // tag last
+        ]",
+    )
+    .unwrap();
+    assert_eq!(
+        shapes,
+        [Shape::Circle { r: 2.0 }, Shape::Line(Point { x: 1, y: 2 })]
+    );
+
+    let cmd: Cmd = from_str_lenient("{ Move: [1, -2,], }").unwrap();
+    assert_eq!(cmd, Cmd::Move(1, -2));
+
+    let pair: (u8, String) = from_str_lenient("[0x7f, 'x',]").unwrap();
+    assert_eq!(pair, (127, "x".to_string()));
+}
+
+#[test]
+fn lenient_errors() {
+    facet_testhelpers::setup();
+
+    // hex numbers must fit, and still have to be valid for the target type
+    assert!(from_str_lenient::<u8>("0x100").is_err());
+    assert!(from_str_lenient::<u32>("0x").is_err());
+    assert!(from_str_lenient::<u32>("Infinity").is_err());
+    // only one trailing comma
+    assert!(from_str_lenient::<Vec<u32>>("[1,,]").is_err());
+    assert!(from_str_lenient::<Vec<u32>>("[,]").is_err());
+    // keys can't start with a digit
+    assert!(from_str_lenient::<HashMap<String, u32>>("{1a: 1}").is_err());
+    assert!(from_str_lenient::<Vec<u32>>("[1 /* unterminated").is_err());
+}
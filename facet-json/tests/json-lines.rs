@@ -0,0 +1,114 @@
+#![cfg(feature = "std")]
+
+use std::io::{self, BufRead, BufReader, Read};
+
+use facet::Facet;
+use facet_json::{
+    JsonErrorKind, JsonLinesErrorKind, JsonLinesWriter, from_json_lines, to_writer_json_lines,
+};
+
+#[test]
+fn json_lines_read() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Event {
+        id: u32,
+        kind: String,
+    }
+
+    let input = "{\"id\":1,\"kind\":\"start\"}\n\n{\"id\":2,\"kind\":\"tick\"}\r\n  \n{\"id\":3,\"kind\":\"stop\"}";
+    let events = from_json_lines::<Event, _>(input.as_bytes())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let kinds: Vec<_> = events.iter().map(|e| (e.id, e.kind.as_str())).collect();
+    assert_eq!(kinds, [(1, "start"), (2, "tick"), (3, "stop")]);
+}
+
+#[test]
+fn json_lines_errors_report_the_record() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Event {
+        id: u32,
+    }
+
+    let input = "{\"id\":1}\n\n{\"id\":\"two\"}\n{\"id\":3}\n";
+    let mut reader = from_json_lines::<Event, _>(input.as_bytes());
+
+    assert_eq!(reader.next().unwrap().unwrap(), Event { id: 1 });
+
+    let err = reader.next().unwrap().unwrap_err();
+    assert_eq!(err.record(), 2);
+    assert_eq!(err.line(), 3);
+    let JsonLinesErrorKind::Parse(parse_err) = err.kind() else {
+        panic!("expected a parse error, got {err}");
+    };
+    assert!(matches!(
+        parse_err.kind(),
+        JsonErrorKind::TypeMismatch { got: "string", .. }
+    ));
+    assert_eq!(parse_err.path(), "id");
+    assert!(err.to_string().starts_with("record 2 (line 3): "), "{err}");
+
+    // a bad record doesn't stop the stream
+    assert_eq!(reader.next().unwrap().unwrap(), Event { id: 3 });
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn json_lines_io_errors_end_the_stream() {
+    facet_testhelpers::setup();
+
+    struct Flaky(&'static [u8]);
+    impl Read for Flaky {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.0.is_empty() {
+                return Err(io::Error::other("disk on fire"));
+            }
+            let n = self.0.read(buf)?;
+            Ok(n)
+        }
+    }
+
+    let reader: Box<dyn BufRead> = Box::new(BufReader::new(Flaky(b"1\n")));
+    let mut reader = from_json_lines::<u32, _>(reader);
+    assert_eq!(reader.next().unwrap().unwrap(), 1);
+
+    let err = reader.next().unwrap().unwrap_err();
+    assert_eq!(err.record(), 2);
+    assert!(matches!(err.kind(), JsonLinesErrorKind::Io(_)));
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn json_lines_write() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Event {
+        id: u32,
+    }
+
+    let mut out = Vec::new();
+    to_writer_json_lines((1..=3).map(|id| Event { id }), &mut out).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "{\"id\":1}\n{\"id\":2}\n{\"id\":3}\n"
+    );
+
+    let events = [Event { id: 7 }, Event { id: 8 }];
+    let mut writer = JsonLinesWriter::new(Vec::new());
+    for e in &events {
+        writer.write(e).unwrap();
+    }
+    assert_eq!(writer.record(), 2);
+
+    // and it all reads back
+    let out = writer.into_inner();
+    let read = from_json_lines::<Event, _>(out.as_slice())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(read, events);
+}
@@ -1,204 +1,480 @@
-// use std::num::NonZero;
-
-// use facet::Facet;
-// use facet_json::from_str;
-
-// #[test]
-// fn json_read_simple_struct() {
-//     facet_testhelpers::setup();
-
-//     #[derive(Facet)]
-//     struct TestStruct {
-//         name: String,
-//         age: u64,
-//     }
-//     let json = r#"{"name": "Alice", "age": 30}"#;
-
-//     let s: TestStruct = match from_str(json) {
-//         Ok(s) => s,
-//         Err(e) => panic!("Error deserializing JSON: {}", e),
-//     };
-//     assert_eq!(s.name, "Alice");
-//     assert_eq!(s.age, 30);
-// }
-
-// #[test]
-// fn json_read_empty_struct() {
-//     facet_testhelpers::setup();
-
-//     #[derive(Facet)]
-//     struct TestStruct {}
-//     let json = r#"{}"#;
-
-//     let _: TestStruct = match from_str(json) {
-//         Ok(s) => s,
-//         Err(e) => panic!("Error deserializing JSON: {}", e),
-//     };
-// }
-
-// #[test]
-// fn json_read_nonzero() {
-//     facet_testhelpers::setup();
-
-//     #[derive(Facet)]
-//     struct Foo {
-//         foo: NonZero<u8>,
-//     }
-//     let json = r#"{"foo": 1}"#;
-//     let s: Foo = match from_str(json) {
-//         Ok(s) => s,
-//         Err(e) => panic!("Error deserializing JSON: {}", e),
-//     };
-//     assert_eq!(s.foo, { const { NonZero::new(1).unwrap() } });
-// }
-
-// #[test]
-// fn json_read_vec() {
-//     facet_testhelpers::setup();
-
-//     let json = r#"[1, 2, 3, 4, 5]"#;
-
-//     let v: Vec<i32> = match from_str(json) {
-//         Ok(v) => v,
-//         Err(e) => panic!("Error deserializing JSON: {}", e),
-//     };
-//     assert_eq!(v, vec![1, 2, 3, 4, 5]);
-// }
-
-// #[test]
-// fn json_read_empty_vec() {
-//     facet_testhelpers::setup();
-
-//     let json = r#"[]"#;
-
-//     let v: Vec<i32> = match from_str(json) {
-//         Ok(v) => v,
-//         Err(e) => panic!("Error deserializing JSON: {}", e),
-//     };
-//     assert_eq!(v, vec![]);
-// }
-
-// #[test]
-// fn json_read_hashmap() {
-//     facet_testhelpers::setup();
-
-//     let json = r#"{"key1": "value1", "key2": "value2", "key3": "value3"}"#;
-
-//     let m: std::collections::HashMap<String, String> = match from_str(json) {
-//         Ok(m) => m,
-//         Err(e) => panic!("Error deserializing JSON: {}", e),
-//     };
-//     assert_eq!(m.get("key1").unwrap(), "value1");
-//     assert_eq!(m.get("key2").unwrap(), "value2");
-//     assert_eq!(m.get("key3").unwrap(), "value3");
-// }
-
-// #[test]
-// fn json_read_more_types() {
-//     facet_testhelpers::setup();
-
-//     #[derive(Facet)]
-//     struct TestStructWithMoreTypes {
-//         u8_val: u8,
-//         u16_val: u16,
-//         i8_val: i8,
-//         i16_val: i16,
-//         u32_val: u32,
-//         i32_val: i32,
-//         u64_val: u64,
-//         i64_val: i64,
-//         f32_val: f32,
-//         f64_val: f64,
-//     }
-
-//     let json = r#"{
-//         "u8_val": 255,
-//         "u16_val": 65535,
-//         "i8_val": -128,
-//         "i16_val": -32768,
-//         "u32_val": 4294967295,
-//         "i32_val": -2147483648,
-//         "u64_val": 18446744073709551615,
-//         "i64_val": -9223372036854775808,
-//         "f32_val": 3.141592653589793,
-//         "f64_val": 3.141592653589793
-//     }"#;
-
-//     let test_struct: TestStructWithMoreTypes = match from_str(json) {
-//         Ok(s) => s,
-//         Err(e) => panic!("Error deserializing JSON: {}", e),
-//     };
-
-//     assert_eq!(test_struct.u8_val, 255);
-//     assert_eq!(test_struct.u16_val, 65535);
-//     assert_eq!(test_struct.i8_val, -128);
-//     assert_eq!(test_struct.i16_val, -32768);
-//     assert_eq!(test_struct.u32_val, 4294967295);
-//     assert_eq!(test_struct.i32_val, -2147483648);
-//     assert_eq!(test_struct.u64_val, 18446744073709551615);
-//     assert_eq!(test_struct.i64_val, -9223372036854775808);
-//     assert!((test_struct.f32_val - std::f32::consts::PI).abs() < f32::EPSILON);
-//     assert!((test_struct.f64_val - std::f64::consts::PI).abs() < f64::EPSILON);
-// }
-
-// #[test]
-// fn test_from_json_with_nested_structs() {
-//     facet_testhelpers::setup();
-
-//     #[derive(Facet)]
-//     struct InnerStruct {
-//         value: i32,
-//     }
-
-//     #[derive(Facet)]
-//     struct OuterStruct {
-//         name: String,
-//         inner: InnerStruct,
-//     }
-
-//     let json = r#"{
-//         "name": "Outer",
-//         "inner": {
-//             "value": 42
-//         }
-//     }"#;
-
-//     let test_struct: OuterStruct = match from_str(json) {
-//         Ok(s) => s,
-//         Err(e) => panic!("Error deserializing JSON: {}", e),
-//     };
-
-//     assert_eq!(test_struct.name, "Outer");
-//     assert_eq!(test_struct.inner.value, 42);
-// }
-
-// #[test]
-// fn test_from_json_with_option() {
-//     #[derive(Facet)]
-//     struct Options {
-//         name: Option<String>,
-//         age: Option<u32>,
-//         inner: Option<Inner>,
-//     }
-
-//     #[derive(Facet)]
-//     struct Inner {
-//         foo: i32,
-//     }
-
-//     let json = r#"{
-//         "name": "Alice",
-//         "age": null,
-//         "inner": {
-//             "foo": 42
-//         }
-//     }"#;
-
-//     let test_struct: Options = match from_str(json) {
-//         Ok(s) => s,
-//         Err(e) => panic!("Error deserializing JSON: {}", e),
-//     };
-//     assert_eq!(test_struct.name.as_deref(), Some("Alice"));
-//     assert_eq!(test_struct.age, None);
-//     assert_eq!(test_struct.inner.as_ref().map(|i| i.foo), Some(42));
-// }
+use std::borrow::Cow;
+use std::num::NonZero;
+
+use facet::Facet;
+use facet_json::{JsonErrorKind, from_str};
+
+#[test]
+fn json_read_simple_struct() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct TestStruct {
+        name: String,
+        age: u64,
+    }
+    let json = r#"{"name": "Alice", "age": 30}"#;
+
+    let s: TestStruct = match from_str(json) {
+        Ok(s) => s,
+        Err(e) => panic!("Error deserializing JSON: {}", e),
+    };
+    assert_eq!(s.name, "Alice");
+    assert_eq!(s.age, 30);
+}
+
+#[test]
+fn json_read_empty_struct() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct TestStruct {}
+    let json = r#"{}"#;
+
+    let _: TestStruct = match from_str(json) {
+        Ok(s) => s,
+        Err(e) => panic!("Error deserializing JSON: {}", e),
+    };
+}
+
+#[test]
+fn json_read_nonzero() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Foo {
+        foo: NonZero<u8>,
+    }
+    let json = r#"{"foo": 1}"#;
+    let s: Foo = match from_str(json) {
+        Ok(s) => s,
+        Err(e) => panic!("Error deserializing JSON: {}", e),
+    };
+    assert_eq!(s.foo, { const { NonZero::new(1).unwrap() } });
+}
+
+#[test]
+fn json_read_vec() {
+    facet_testhelpers::setup();
+
+    let json = r#"[1, 2, 3, 4, 5]"#;
+
+    let v: Vec<i32> = match from_str(json) {
+        Ok(v) => v,
+        Err(e) => panic!("Error deserializing JSON: {}", e),
+    };
+    assert_eq!(v, vec![1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn json_read_empty_vec() {
+    facet_testhelpers::setup();
+
+    let json = r#"[]"#;
+
+    let v: Vec<i32> = match from_str(json) {
+        Ok(v) => v,
+        Err(e) => panic!("Error deserializing JSON: {}", e),
+    };
+    assert_eq!(v, vec![]);
+}
+
+#[test]
+fn json_read_hashmap() {
+    facet_testhelpers::setup();
+
+    let json = r#"{"key1": "value1", "key2": "value2", "key3": "value3"}"#;
+
+    let m: std::collections::HashMap<String, String> = match from_str(json) {
+        Ok(m) => m,
+        Err(e) => panic!("Error deserializing JSON: {}", e),
+    };
+    assert_eq!(m.get("key1").unwrap(), "value1");
+    assert_eq!(m.get("key2").unwrap(), "value2");
+    assert_eq!(m.get("key3").unwrap(), "value3");
+}
+
+#[test]
+fn json_read_more_types() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct TestStructWithMoreTypes {
+        u8_val: u8,
+        u16_val: u16,
+        i8_val: i8,
+        i16_val: i16,
+        u32_val: u32,
+        i32_val: i32,
+        u64_val: u64,
+        i64_val: i64,
+        f32_val: f32,
+        f64_val: f64,
+    }
+
+    let json = r#"{
+        "u8_val": 255,
+        "u16_val": 65535,
+        "i8_val": -128,
+        "i16_val": -32768,
+        "u32_val": 4294967295,
+        "i32_val": -2147483648,
+        "u64_val": 18446744073709551615,
+        "i64_val": -9223372036854775808,
+        "f32_val": 3.141592653589793,
+        "f64_val": 3.141592653589793
+    }"#;
+
+    let test_struct: TestStructWithMoreTypes = match from_str(json) {
+        Ok(s) => s,
+        Err(e) => panic!("Error deserializing JSON: {}", e),
+    };
+
+    assert_eq!(test_struct.u8_val, 255);
+    assert_eq!(test_struct.u16_val, 65535);
+    assert_eq!(test_struct.i8_val, -128);
+    assert_eq!(test_struct.i16_val, -32768);
+    assert_eq!(test_struct.u32_val, 4294967295);
+    assert_eq!(test_struct.i32_val, -2147483648);
+    assert_eq!(test_struct.u64_val, 18446744073709551615);
+    assert_eq!(test_struct.i64_val, -9223372036854775808);
+    assert!((test_struct.f32_val - std::f32::consts::PI).abs() < f32::EPSILON);
+    assert!((test_struct.f64_val - std::f64::consts::PI).abs() < f64::EPSILON);
+}
+
+#[test]
+fn test_from_json_with_nested_structs() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct InnerStruct {
+        value: i32,
+    }
+
+    #[derive(Facet)]
+    struct OuterStruct {
+        name: String,
+        inner: InnerStruct,
+    }
+
+    let json = r#"{
+        "name": "Outer",
+        "inner": {
+            "value": 42
+        }
+    }"#;
+
+    let test_struct: OuterStruct = match from_str(json) {
+        Ok(s) => s,
+        Err(e) => panic!("Error deserializing JSON: {}", e),
+    };
+
+    assert_eq!(test_struct.name, "Outer");
+    assert_eq!(test_struct.inner.value, 42);
+}
+
+#[test]
+fn test_from_json_with_option() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Options {
+        name: Option<String>,
+        age: Option<u32>,
+        inner: Option<Inner>,
+    }
+
+    #[derive(Facet)]
+    struct Inner {
+        foo: i32,
+    }
+
+    let json = r#"{
+        "name": "Alice",
+        "age": null,
+        "inner": {
+            "foo": 42
+        }
+    }"#;
+
+    let test_struct: Options = match from_str(json) {
+        Ok(s) => s,
+        Err(e) => panic!("Error deserializing JSON: {}", e),
+    };
+    assert_eq!(test_struct.name.as_deref(), Some("Alice"));
+    assert_eq!(test_struct.age, None);
+    assert_eq!(test_struct.inner.as_ref().map(|i| i.foo), Some(42));
+}
+
+#[test]
+fn json_read_missing_option_field() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Options {
+        name: String,
+        nickname: Option<String>,
+    }
+
+    let test_struct: Options = from_str(r#"{"name": "Alice"}"#).unwrap();
+    assert_eq!(test_struct.name, "Alice");
+    assert_eq!(test_struct.nickname, None);
+}
+
+#[test]
+fn json_read_missing_required_field() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug)]
+    struct TestStruct {
+        name: String,
+        age: u64,
+    }
+
+    let err = from_str::<TestStruct>(r#"{"name": "Alice"}"#).unwrap_err();
+    assert!(
+        matches!(err.kind(), JsonErrorKind::MissingField("age")),
+        "{err}"
+    );
+}
+
+#[test]
+fn json_read_unknown_fields_are_skipped() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct TestStruct {
+        name: String,
+    }
+
+    let json = r#"{"extra": {"a": [1, 2.5e3, true, null, "x"]}, "name": "Alice"}"#;
+    let test_struct: TestStruct = from_str(json).unwrap();
+    assert_eq!(test_struct.name, "Alice");
+}
+
+#[test]
+fn json_read_deny_unknown_fields() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet)]
+    #[facet(deny_unknown_fields)]
+    struct TestStruct {
+        name: String,
+    }
+
+    let err = from_str::<TestStruct>(r#"{"name": "Alice", "extra": 1}"#).unwrap_err();
+    assert!(matches!(err.kind(), JsonErrorKind::UnknownField(name) if name == "extra"));
+}
+
+#[test]
+fn json_read_escapes() {
+    facet_testhelpers::setup();
+
+    let s: String = from_str(r#""tab\there \"quoted\" é 😀""#).unwrap();
+    assert_eq!(s, "tab\there \"quoted\" \u{e9} \u{1F600}");
+}
+
+#[test]
+fn json_read_char_and_bool() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct TestStruct {
+        c: char,
+        yes: bool,
+        no: bool,
+    }
+
+    let s: TestStruct = from_str(r#"{"c": "ß", "yes": true, "no": false}"#).unwrap();
+    assert_eq!(s.c, 'ß');
+    assert!(s.yes);
+    assert!(!s.no);
+}
+
+#[test]
+fn json_read_tuple_struct_and_tuple() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Point(i32, i32);
+
+    let p: Point = from_str("[3, -4]").unwrap();
+    assert_eq!(p, Point(3, -4));
+
+    let t: (u8, String) = from_str(r#"[1, "one"]"#).unwrap();
+    assert_eq!(t, (1, "one".to_string()));
+}
+
+#[test]
+fn json_read_enum() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Shape {
+        Empty,
+        Circle(f64),
+        Segment(i32, i32),
+        Rect { width: u32, height: u32 },
+    }
+
+    let s: Shape = from_str(r#""Empty""#).unwrap();
+    assert_eq!(s, Shape::Empty);
+
+    let s: Shape = from_str(r#"{"Circle": 1.5}"#).unwrap();
+    assert_eq!(s, Shape::Circle(1.5));
+
+    let s: Shape = from_str(r#"{"Segment": [1, 2]}"#).unwrap();
+    assert_eq!(s, Shape::Segment(1, 2));
+
+    let s: Shape = from_str(r#"{"Rect": {"width": 3, "height": 4}}"#).unwrap();
+    assert_eq!(
+        s,
+        Shape::Rect {
+            width: 3,
+            height: 4
+        }
+    );
+
+    let err = from_str::<Shape>(r#""Triangle""#).unwrap_err();
+    assert!(matches!(err.kind(), JsonErrorKind::UnknownVariant(name) if name == "Triangle"));
+}
+
+#[test]
+fn json_read_btreemap_with_integer_keys() {
+    facet_testhelpers::setup();
+
+    let m: std::collections::BTreeMap<u32, Vec<String>> =
+        from_str(r#"{"1": ["a"], "2": []}"#).unwrap();
+    assert_eq!(m.get(&1).unwrap(), &vec!["a".to_string()]);
+    assert!(m.get(&2).unwrap().is_empty());
+}
+
+#[test]
+fn json_read_fixed_size_arrays() {
+    facet_testhelpers::setup();
+
+    assert_eq!(from_str::<[u32; 2]>("[1,2]").unwrap(), [1, 2]);
+    assert_eq!(from_str::<[u32; 0]>("[ ]").unwrap(), [0u32; 0]);
+    assert_eq!(
+        from_str::<Vec<[i8; 2]>>("[[1, -1], [0, 2]]").unwrap(),
+        vec![[1, -1], [0, 2]]
+    );
+    let matrix = [[1.5f32, 2.0], [0.0, -1.0]];
+    assert_eq!(
+        from_str::<[[f32; 2]; 2]>(&facet_json::to_string(&matrix)).unwrap(),
+        matrix
+    );
+
+    for (input, got) in [("[1]", 1), ("[1, 2, 3]", 3)] {
+        let err = from_str::<[u32; 2]>(input).unwrap_err();
+        assert!(
+            matches!(err.kind(), JsonErrorKind::WrongLength { expected: 2, got: g } if *g == got),
+            "{err}"
+        );
+    }
+
+    let err = from_str::<[u32; 2]>(r#"[1, "2"]"#).unwrap_err();
+    assert_eq!(err.path(), "[1]");
+}
+
+#[test]
+fn json_read_errors() {
+    facet_testhelpers::setup();
+
+    let err = from_str::<Vec<u32>>("[1, 2").unwrap_err();
+    assert!(matches!(err.kind(), JsonErrorKind::UnexpectedEof), "{err}");
+
+    let err = from_str::<u32>("12 13").unwrap_err();
+    assert!(
+        matches!(err.kind(), JsonErrorKind::TrailingCharacters),
+        "{err}"
+    );
+    assert_eq!(err.position(), 3);
+
+    let err = from_str::<u8>("256").unwrap_err();
+    assert!(
+        matches!(err.kind(), JsonErrorKind::InvalidValue(_)),
+        "{err}"
+    );
+
+    // strings aren't numbers or booleans, even when they'd parse as one
+    #[derive(Debug, Facet)]
+    struct P {
+        #[allow(dead_code)]
+        a: u32,
+    }
+    for input in [r#""twelve""#, r#""5""#] {
+        let err = from_str::<u32>(input).unwrap_err();
+        assert!(
+            matches!(
+                err.kind(),
+                JsonErrorKind::TypeMismatch { got: "string", .. }
+            ),
+            "{err}"
+        );
+    }
+    let err = from_str::<P>(r#"{"a":"5"}"#).unwrap_err();
+    assert_eq!(err.path(), "a");
+    let err = from_str::<bool>(r#""true""#).unwrap_err();
+    assert!(
+        matches!(
+            err.kind(),
+            JsonErrorKind::TypeMismatch { got: "string", .. }
+        ),
+        "{err}"
+    );
+
+    let err = from_str::<String>("12").unwrap_err();
+    assert!(
+        matches!(
+            err.kind(),
+            JsonErrorKind::TypeMismatch { got: "number", .. }
+        ),
+        "{err}"
+    );
+
+    let err = from_str::<Vec<u32>>("[01]").unwrap_err();
+    assert!(
+        matches!(err.kind(), JsonErrorKind::UnexpectedCharacter('1')),
+        "{err}"
+    );
+}
+
+#[test]
+fn json_read_borrowed_strings() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct LogLine<'a> {
+        level: &'a str,
+        message: Cow<'a, str>,
+        tags: Vec<&'a str>,
+    }
+
+    let json = r#"{"level":"warn","message":"disk almost full","tags":["disk","io"]}"#;
+    let line: LogLine = from_str(json).unwrap();
+    assert_eq!(line.level, "warn");
+    assert_eq!(line.tags, ["disk", "io"]);
+
+    // unescaped strings point straight into the input
+    let input = json.as_bytes().as_ptr_range();
+    assert!(input.contains(&line.level.as_ptr()));
+    assert!(input.contains(&line.tags[1].as_ptr()));
+    assert!(matches!(line.message, Cow::Borrowed(m) if input.contains(&m.as_ptr())));
+
+    // escaped strings need to be unescaped, so `Cow` falls back to owning them
+    let json = r#"{"level":"info","message":"tab\there","tags":[]}"#;
+    let line: LogLine = from_str(json).unwrap();
+    assert!(matches!(line.message, Cow::Owned(ref m) if m == "tab\there"));
+
+    // ...and `&str` can't hold them at all
+    let json = r#"{"level":"in\u0066o","message":"","tags":[]}"#;
+    let err = from_str::<LogLine>(json).unwrap_err();
+    assert!(
+        matches!(err.kind(), JsonErrorKind::CannotBorrow(_)),
+        "{err}"
+    );
+    assert_eq!(err.path(), "level");
+}
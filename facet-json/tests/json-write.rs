@@ -114,3 +114,273 @@ fn test_static_strings() {
     let json = facet_json::to_string(&test_struct);
     assert_eq!(json, r#"{"foo":"foo"}"#);
 }
+
+#[test]
+fn test_to_string_pretty() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet)]
+    struct Service {
+        name: String,
+        tags: Vec<String>,
+        empty: Vec<u32>,
+        limits: std::collections::BTreeMap<String, u32>,
+        parent: Option<u32>,
+    }
+
+    let service = Service {
+        name: "web".to_string(),
+        tags: vec!["a".to_string(), "b".to_string()],
+        empty: vec![],
+        limits: [("cpu".to_string(), 2), ("mem".to_string(), 512)].into(),
+        parent: None,
+    };
+
+    let json = facet_json::to_string_pretty(&service);
+    assert_eq!(
+        json,
+        r#"{
+  "name": "web",
+  "tags": [
+    "a",
+    "b"
+  ],
+  "empty": [],
+  "limits": {
+    "cpu": 2,
+    "mem": 512
+  },
+  "parent": null
+}"#
+    );
+}
+
+#[test]
+fn test_writer_options_tabs_and_trailing_newline() {
+    facet_testhelpers::setup();
+
+    let options = facet_json::JsonWriterOptions::pretty()
+        .with_tabs(true)
+        .with_trailing_newline(true);
+    let mut buffer = Vec::new();
+    facet_json::to_writer_with_options(&vec![vec![1u8], vec![]], &mut buffer, &options).unwrap();
+    assert_eq!(
+        String::from_utf8(buffer).unwrap(),
+        "[\n\t[\n\t\t1\n\t],\n\t[]\n]\n"
+    );
+
+    let options = facet_json::JsonWriterOptions::pretty().with_indent_size(4);
+    let json = facet_json::to_string_with_options(&vec![1u8, 2], &options).unwrap();
+    assert_eq!(json, "[\n    1,\n    2\n]");
+
+    let options = facet_json::JsonWriterOptions::new().with_trailing_newline(true);
+    let json = facet_json::to_string_with_options(&vec![1u8, 2], &options).unwrap();
+    assert_eq!(json, "[1,2]\n");
+}
+
+#[test]
+fn test_writer_options_sort_keys() {
+    facet_testhelpers::setup();
+
+    let mut map = std::collections::HashMap::new();
+    for key in ["zebra", "apple", "mango", "kiwi"] {
+        map.insert(key.to_string(), key.len());
+    }
+
+    let options = facet_json::JsonWriterOptions::new().with_sort_keys(true);
+    let json = facet_json::to_string_with_options(&map, &options).unwrap();
+    assert_eq!(json, r#"{"apple":5,"kiwi":4,"mango":5,"zebra":5}"#);
+}
+
+#[test]
+fn test_pretty_enum() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Shape {
+        Rect { w: u32, h: u32 },
+        Empty,
+    }
+
+    let json = facet_json::to_string_pretty(&vec![Shape::Rect { w: 1, h: 2 }, Shape::Empty]);
+    assert_eq!(
+        json,
+        r#"[
+  {
+    "Rect": {
+      "w": 1,
+      "h": 2
+    }
+  },
+  "Empty"
+]"#
+    );
+}
+
+#[test]
+fn test_arrays_slices_and_tuples() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet)]
+    struct Point(f32, f32);
+
+    #[derive(Debug, Facet)]
+    struct Mesh {
+        origin: [f32; 3],
+        normals: Vec<[i8; 2]>,
+        corner: Point,
+        pair: (u8, String),
+        tags: &'static [&'static str],
+    }
+
+    let mesh = Mesh {
+        origin: [1.0, 2.5, -3.0],
+        normals: vec![[0, 1], [-1, 0]],
+        corner: Point(0.5, 1.5),
+        pair: (7, "seven".to_string()),
+        tags: &["a", "b"],
+    };
+
+    let json = facet_json::to_string(&mesh);
+    assert_eq!(
+        json,
+        r#"{"origin":[1,2.5,-3],"normals":[[0,1],[-1,0]],"corner":[0.5,1.5],"pair":[7,"seven"],"tags":["a","b"]}"#
+    );
+}
+
+#[test]
+fn test_arc_and_wide_scalars() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet)]
+    struct Wide {
+        shared: std::sync::Arc<String>,
+        big: u128,
+        small: i128,
+        initial: char,
+        quote: char,
+        id: NonZero<u128>,
+    }
+
+    let wide = Wide {
+        shared: std::sync::Arc::new("hi".to_string()),
+        big: u128::MAX,
+        small: i128::MIN,
+        initial: 'é',
+        quote: '"',
+        id: NonZero::new(1 << 100).unwrap(),
+    };
+
+    let json = facet_json::to_string(&wide);
+    assert_eq!(
+        json,
+        format!(
+            r#"{{"shared":"hi","big":{},"small":{},"initial":"é","quote":"\"","id":{}}}"#,
+            u128::MAX,
+            i128::MIN,
+            1u128 << 100
+        )
+    );
+}
+
+#[test]
+fn test_fmt_writer_and_vec() {
+    facet_testhelpers::setup();
+
+    /// A fixed-size buffer, like the ones used on targets without an allocator
+    struct Buf<const N: usize> {
+        bytes: [u8; N],
+        len: usize,
+    }
+
+    impl<const N: usize> core::fmt::Write for Buf<N> {
+        fn write_str(&mut self, s: &str) -> core::fmt::Result {
+            let end = self.len + s.len();
+            if end > N {
+                return Err(core::fmt::Error);
+            }
+            self.bytes[self.len..end].copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    #[derive(Facet)]
+    struct Telemetry {
+        id: u16,
+        volts: f32,
+        ok: bool,
+    }
+
+    let reading = Telemetry {
+        id: 7,
+        volts: 3.3,
+        ok: true,
+    };
+    let expected = r#"{"id":7,"volts":3.3,"ok":true}"#;
+
+    let mut buf = Buf::<64> {
+        bytes: [0; 64],
+        len: 0,
+    };
+    facet_json::to_fmt_writer(&reading, &mut buf).unwrap();
+    assert_eq!(
+        core::str::from_utf8(&buf.bytes[..buf.len]).unwrap(),
+        expected
+    );
+
+    let mut small = Buf::<8> {
+        bytes: [0; 8],
+        len: 0,
+    };
+    let err = facet_json::to_fmt_writer(&reading, &mut small).unwrap_err();
+    assert!(matches!(err, facet_json::JsonSerializeError::Write));
+
+    assert_eq!(facet_json::to_vec(&reading).unwrap(), expected.as_bytes());
+    assert_eq!(
+        facet_json::to_vec_with_options(
+            &reading,
+            &facet_json::JsonWriterOptions::default().with_trailing_newline(true)
+        )
+        .unwrap(),
+        format!("{expected}\n").as_bytes()
+    );
+}
+
+#[test]
+fn test_writer_errors() {
+    facet_testhelpers::setup();
+
+    struct Broken;
+
+    impl std::io::Write for Broken {
+        fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::BrokenPipe,
+                "nobody's listening",
+            ))
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    // the writer's own error comes back out
+    let err = facet_json::to_writer(&vec![1, 2, 3], &mut Broken).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::BrokenPipe);
+
+    let err = facet_json::to_writer_with_options(
+        &f64::NAN,
+        &mut Vec::new(),
+        &facet_json::JsonWriterOptions::canonical(),
+    )
+    .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(matches!(
+        facet_json::to_string_canonical(&f64::NAN),
+        Err(facet_json::JsonSerializeError::NonFiniteFloat)
+    ));
+}
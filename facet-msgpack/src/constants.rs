@@ -117,3 +117,7 @@ pub const MSGPACK_FIXARRAY_MAX: u8 = 0x9f;
 pub const MSGPACK_FIXMAP_MIN: u8 = 0x80;
 /// Maximum value for fixmap format (0x8f, allowing maps up to 15 key-value pairs)
 pub const MSGPACK_FIXMAP_MAX: u8 = 0x8f;
+
+/// Extension type of the timestamp extension, the only one predefined by the specification
+/// Ref: <https://github.com/msgpack/msgpack/blob/master/spec.md#timestamp-extension-type>
+pub const MSGPACK_EXT_TIMESTAMP: i8 = -1;
@@ -14,12 +14,31 @@ pub enum Error {
     UnknownField(String),
     /// Required field is missing from the input
     MissingField(String),
+    /// A field appears more than once in the input
+    DuplicateField(String),
+    /// Encountered an enum variant name that isn't recognized
+    UnknownVariant(String),
+    /// None of the variants of an untagged enum could be decoded from the input
+    NoMatchingVariant(String),
+    /// An array doesn't have as many elements as the tuple or fixed-size array it's decoded into
+    LengthMismatch {
+        /// The number of elements the target type has
+        expected: usize,
+        /// The number of elements in the MessagePack array
+        got: usize,
+    },
     /// Integer value is too large for the target type
     IntegerOverflow,
+    /// Arrays and maps nest deeper than the max depth of the reader options
+    DepthLimitExceeded,
+    /// Encountered an extension value of a type no handler is registered for
+    UnknownExtType(i8),
     /// Shape is not supported for deserialization
     UnsupportedShape(String),
     /// Type is not supported for deserialization
     UnsupportedType(String),
+    /// Reading the input failed
+    Io(std::io::Error),
 }
 
 impl fmt::Display for Error {
@@ -30,15 +49,38 @@ impl fmt::Display for Error {
             Error::InvalidData => write!(f, "Invalid MessagePack data"),
             Error::UnknownField(field) => write!(f, "Unknown field: {}", field),
             Error::MissingField(field) => write!(f, "Missing required field: {}", field),
+            Error::DuplicateField(field) => write!(f, "Duplicate field: {}", field),
+            Error::UnknownVariant(variant) => write!(f, "Unknown variant: {}", variant),
+            Error::NoMatchingVariant(shape) => {
+                write!(f, "Data did not match any variant of {}", shape)
+            }
+            Error::LengthMismatch { expected, got } => {
+                write!(f, "Expected an array of {} elements, got {}", expected, got)
+            }
             Error::IntegerOverflow => write!(f, "Integer value too large for target type"),
+            Error::DepthLimitExceeded => write!(f, "Arrays and maps nest too deeply"),
+            Error::UnknownExtType(ext_type) => {
+                write!(f, "No handler for MessagePack extension type {}", ext_type)
+            }
             Error::UnsupportedShape(shape) => {
                 write!(f, "Unsupported shape for deserialization: {}", shape)
             }
             Error::UnsupportedType(typ) => {
                 write!(f, "Unsupported type for deserialization: {}", typ)
             }
+            Error::Io(err) => write!(f, "I/O error: {}", err),
         }
     }
 }
 
 impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        match err.kind() {
+            // the input ended in the middle of a value
+            std::io::ErrorKind::UnexpectedEof => Error::InsufficientData,
+            _ => Error::Io(err),
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use core::fmt;
+use std::sync::Arc;
+
+use facet_reflect::{Peek, Wip};
+
+use crate::errors::Error as DecodeError;
+
+/// Reads and writes values of one MessagePack extension type.
+///
+/// Extension values are a type byte and opaque data. Register a handler with
+/// [`MsgpackReaderOptions::with_ext_handler`](crate::MsgpackReaderOptions::with_ext_handler)
+/// to decode them, and with
+/// [`MsgpackWriterOptions::with_ext_handler`](crate::MsgpackWriterOptions::with_ext_handler)
+/// to encode values as them.
+///
+/// # Example
+/// ```
+/// use facet::Facet;
+/// use facet_msgpack::{DecodeError, ExtHandler, MsgpackReaderOptions, MsgpackWriterOptions};
+/// use facet_reflect::{Peek, Wip};
+///
+/// /// Written as ext type 7, with the three channels as data
+/// #[derive(Debug, Facet, PartialEq)]
+/// struct Rgb(u8, u8, u8);
+///
+/// struct RgbExt;
+///
+/// impl ExtHandler for RgbExt {
+///     fn ext_type(&self) -> i8 {
+///         7
+///     }
+///
+///     fn decode<'facet>(&self, data: &[u8], wip: Wip<'facet>) -> Result<Wip<'facet>, DecodeError> {
+///         let [r, g, b] = data else {
+///             return Err(DecodeError::InvalidData);
+///         };
+///         wip.put(Rgb(*r, *g, *b))
+///             .map_err(|e| DecodeError::UnsupportedType(e.to_string()))
+///     }
+///
+///     fn encode(&self, value: Peek<'_>) -> Option<Vec<u8>> {
+///         let rgb = value.get::<Rgb>().ok()?;
+///         Some(vec![rgb.0, rgb.1, rgb.2])
+///     }
+/// }
+///
+/// let writer = MsgpackWriterOptions::new().with_ext_handler(RgbExt);
+/// let bytes = facet_msgpack::to_vec_with_options(&Rgb(255, 128, 0), &writer).unwrap();
+/// assert_eq!(bytes, [0xc7, 3, 7, 255, 128, 0]);
+///
+/// let reader = MsgpackReaderOptions::new().with_ext_handler(RgbExt);
+/// let rgb: Rgb = facet_msgpack::from_slice_with_options(&bytes, &reader).unwrap();
+/// assert_eq!(rgb, Rgb(255, 128, 0));
+/// ```
+pub trait ExtHandler: Send + Sync {
+    /// The extension type handled. Applications use 0 to 127; negative types
+    /// are reserved by the MessagePack specification.
+    fn ext_type(&self) -> i8;
+
+    /// Fills `wip` from the data of an extension value of this type.
+    ///
+    /// This is called for whatever shape is expected where the extension
+    /// value was found: return [`DecodeError::UnexpectedType`] for shapes it
+    /// can't be decoded into.
+    fn decode<'facet>(&self, data: &[u8], wip: Wip<'facet>) -> Result<Wip<'facet>, DecodeError>;
+
+    /// Returns the data to write `value` as an extension value of this type,
+    /// or `None` to write it as usual. Handlers only decode by default.
+    fn encode(&self, value: Peek<'_>) -> Option<Vec<u8>> {
+        let _ = value;
+        None
+    }
+}
+
+/// The extension handlers registered on reader or writer options
+#[derive(Clone, Default)]
+pub(crate) struct ExtHandlers(Vec<Arc<dyn ExtHandler>>);
+
+impl ExtHandlers {
+    /// Registers `handler`, replacing any handler for the same extension type
+    pub(crate) fn insert(&mut self, handler: impl ExtHandler + 'static) {
+        self.0.retain(|h| h.ext_type() != handler.ext_type());
+        self.0.push(Arc::new(handler));
+    }
+
+    pub(crate) fn get(&self, ext_type: i8) -> Option<&dyn ExtHandler> {
+        self.0
+            .iter()
+            .find(|h| h.ext_type() == ext_type)
+            .map(|h| h.as_ref())
+    }
+
+    /// Returns the extension type and data `value` is written as, if a handler encodes it
+    pub(crate) fn encode(&self, value: Peek<'_>) -> Option<(i8, Vec<u8>)> {
+        self.0
+            .iter()
+            .find_map(|h| h.encode(value).map(|data| (h.ext_type(), data)))
+    }
+}
+
+impl fmt::Debug for ExtHandlers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.0.iter().map(|h| h.ext_type()))
+            .finish()
+    }
+}
@@ -1,12 +1,60 @@
 use crate::constants::*;
 use crate::errors::Error as DecodeError;
+use crate::ext::{ExtHandler, ExtHandlers};
+use crate::timestamp;
 
-use facet_core::{Def, Facet};
-use facet_reflect::{HeapValue, Wip};
+use facet_core::{Def, EnumTagging, Facet, Field, StructKind, TaggedFields};
+use facet_reflect::{HeapValue, ReflectError, Wip};
 use log::trace;
 
+/// How deeply arrays and maps may nest by default, like facet-json. Deeper
+/// input is rejected, rather than overflowing the stack.
+const DEFAULT_MAX_DEPTH: usize = 128;
+
+/// Options controlling how [`from_slice_with_options`] and [`Decoder`](crate::Decoder)
+/// decode their input.
+#[derive(Debug, Clone)]
+pub struct MsgpackReaderOptions {
+    ext_handlers: ExtHandlers,
+    max_depth: usize,
+}
+
+impl Default for MsgpackReaderOptions {
+    fn default() -> Self {
+        Self {
+            ext_handlers: ExtHandlers::default(),
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+}
+
+impl MsgpackReaderOptions {
+    /// Creates the default options: extension values are rejected, and arrays
+    /// and maps may nest 128 deep
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how deeply arrays and maps may nest, including in skipped unknown
+    /// fields. Deeper input fails with [`DecodeError::DepthLimitExceeded`].
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Decodes extension values of `handler`'s extension type with it,
+    /// replacing any handler previously registered for that type
+    pub fn with_ext_handler(mut self, handler: impl ExtHandler + 'static) -> Self {
+        self.ext_handlers.insert(handler);
+        self
+    }
+}
+
 /// Deserializes MessagePack-encoded data into a type that implements `Facet`.
 ///
+/// Map keys that aren't fields of the struct are skipped, unless the struct
+/// has `#[facet(deny_unknown_fields)]`.
+///
 /// # Example
 /// ```
 /// use facet::Facet;
@@ -28,15 +76,24 @@ use log::trace;
 /// let user: User = from_str(&msgpack_data).unwrap();
 /// assert_eq!(user, User { id: 42, username: "user123".to_string() });
 /// ```
-pub fn from_slice<T: Facet>(msgpack: &[u8]) -> Result<T, DecodeError> {
-    from_slice_value(Wip::alloc::<T>(), msgpack)?
+pub fn from_slice<'a, T: Facet<'a>>(msgpack: &[u8]) -> Result<T, DecodeError> {
+    from_slice_with_options(msgpack, &MsgpackReaderOptions::default())
+}
+
+/// Deserializes MessagePack-encoded data into a type that implements `Facet`,
+/// with the given options
+pub fn from_slice_with_options<'a, T: Facet<'a>>(
+    msgpack: &[u8],
+    options: &MsgpackReaderOptions,
+) -> Result<T, DecodeError> {
+    from_slice_value_with_options(Wip::alloc::<T>(), msgpack, options)?
         .materialize::<T>()
         .map_err(|e| DecodeError::UnsupportedType(e.to_string()))
 }
 
 /// Alias for from_slice for backward compatibility
 #[deprecated(since = "0.1.0", note = "Use from_slice instead")]
-pub fn from_str<T: Facet>(msgpack: &[u8]) -> Result<T, DecodeError> {
+pub fn from_str<'a, T: Facet<'a>>(msgpack: &[u8]) -> Result<T, DecodeError> {
     from_slice(msgpack)
 }
 
@@ -79,26 +136,65 @@ pub fn from_str<T: Facet>(msgpack: &[u8]) -> Result<T, DecodeError> {
 /// # MessagePack Format
 /// This implementation follows the MessagePack specification:
 /// <https://github.com/msgpack/msgpack/blob/master/spec.md>
-#[allow(clippy::needless_lifetimes)]
-pub fn from_slice_value<'mem>(
-    wip: Wip<'mem>,
-    msgpack: &'mem [u8],
-) -> Result<HeapValue<'mem>, DecodeError> {
-    let mut decoder = Decoder::new(msgpack);
+pub fn from_slice_value<'facet>(
+    wip: Wip<'facet>,
+    msgpack: &[u8],
+) -> Result<HeapValue<'facet>, DecodeError> {
+    from_slice_value_with_options(wip, msgpack, &MsgpackReaderOptions::default())
+}
+
+/// Deserializes MessagePack-encoded data into a Facet value, with the given options
+pub fn from_slice_value_with_options<'facet>(
+    wip: Wip<'facet>,
+    msgpack: &[u8],
+    options: &MsgpackReaderOptions,
+) -> Result<HeapValue<'facet>, DecodeError> {
+    let mut decoder = SliceDecoder::new(msgpack, options);
     decoder
         .deserialize_value(wip)?
         .build()
         .map_err(|e| DecodeError::UnsupportedType(e.to_string()))
 }
 
-struct Decoder<'input> {
+/// Decodes values from a MessagePack slice held in memory
+struct SliceDecoder<'input> {
     input: &'input [u8],
     offset: usize,
+    options: &'input MsgpackReaderOptions,
+    /// how many arrays and maps the current value is nested in
+    depth: usize,
 }
 
-impl<'input> Decoder<'input> {
-    fn new(input: &'input [u8]) -> Self {
-        Decoder { input, offset: 0 }
+impl<'input> SliceDecoder<'input> {
+    fn new(input: &'input [u8], options: &'input MsgpackReaderOptions) -> Self {
+        SliceDecoder {
+            input,
+            offset: 0,
+            options,
+            depth: 0,
+        }
+    }
+
+    /// Runs `f` on the value at the current offset, one level deeper if that
+    /// value is an array or map. Fails past the configured max depth.
+    fn nested<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<T, DecodeError>,
+    ) -> Result<T, DecodeError> {
+        if !self
+            .input
+            .get(self.offset)
+            .is_some_and(|&prefix| is_array_prefix(prefix) || is_map_prefix(prefix))
+        {
+            return f(self);
+        }
+        if self.depth >= self.options.max_depth {
+            return Err(DecodeError::DepthLimitExceeded);
+        }
+        self.depth += 1;
+        let result = f(self);
+        self.depth -= 1;
+        result
     }
 
     /// Decodes a single byte from the input.
@@ -136,31 +232,45 @@ impl<'input> Decoder<'input> {
         Ok(value)
     }
 
-    /// Decodes a MessagePack-encoded unsigned 64-bit integer.
+    /// Decodes a 64-bit unsigned integer in big-endian byte order.
+    /// This is a low-level method used by other decoders.
+    fn decode_raw_u64(&mut self) -> Result<u64, DecodeError> {
+        if self.offset + 8 > self.input.len() {
+            return Err(DecodeError::InsufficientData);
+        }
+        let value =
+            u64::from_be_bytes(self.input[self.offset..self.offset + 8].try_into().unwrap());
+        self.offset += 8;
+        Ok(value)
+    }
+
+    /// Decodes a MessagePack-encoded integer, signed or unsigned. `i128` holds
+    /// every value of every format.
     /// Handles the following MessagePack types:
     /// - positive fixint (0x00 - 0x7f): single-byte positive integer
+    /// - negative fixint (0xe0 - 0xff): single-byte negative integer
     /// - uint8 (0xcc): 8-bit unsigned integer
     /// - uint16 (0xcd): 16-bit unsigned integer (big-endian)
     /// - uint32 (0xce): 32-bit unsigned integer (big-endian)
     /// - uint64 (0xcf): 64-bit unsigned integer (big-endian)
+    /// - int8 (0xd0): 8-bit signed integer
+    /// - int16 (0xd1): 16-bit signed integer (big-endian)
+    /// - int32 (0xd2): 32-bit signed integer (big-endian)
+    /// - int64 (0xd3): 64-bit signed integer (big-endian)
     ///
     /// Ref: <https://github.com/msgpack/msgpack/blob/master/spec.md#int-format-family>
-    fn decode_u64(&mut self) -> Result<u64, DecodeError> {
+    fn decode_i128(&mut self) -> Result<i128, DecodeError> {
         match self.decode_u8()? {
-            MSGPACK_UINT8 => Ok(self.decode_u8()? as u64),
-            MSGPACK_UINT16 => Ok(self.decode_u16()? as u64),
-            MSGPACK_UINT32 => Ok(self.decode_u32()? as u64),
-            MSGPACK_UINT64 => {
-                if self.offset + 8 > self.input.len() {
-                    return Err(DecodeError::InsufficientData);
-                }
-                let value = u64::from_be_bytes(
-                    self.input[self.offset..self.offset + 8].try_into().unwrap(),
-                );
-                self.offset += 8;
-                Ok(value)
-            }
-            prefix @ MSGPACK_POSFIXINT_MIN..=MSGPACK_POSFIXINT_MAX => Ok(prefix as u64),
+            prefix @ MSGPACK_POSFIXINT_MIN..=MSGPACK_POSFIXINT_MAX => Ok(prefix as i128),
+            prefix if (prefix as i8) >= MSGPACK_NEGFIXINT_MIN => Ok(prefix as i8 as i128),
+            MSGPACK_UINT8 => Ok(self.decode_u8()? as i128),
+            MSGPACK_UINT16 => Ok(self.decode_u16()? as i128),
+            MSGPACK_UINT32 => Ok(self.decode_u32()? as i128),
+            MSGPACK_UINT64 => Ok(self.decode_raw_u64()? as i128),
+            MSGPACK_INT8 => Ok(self.decode_u8()? as i8 as i128),
+            MSGPACK_INT16 => Ok(self.decode_u16()? as i16 as i128),
+            MSGPACK_INT32 => Ok(self.decode_u32()? as i32 as i128),
+            MSGPACK_INT64 => Ok(self.decode_raw_u64()? as i64 as i128),
             _ => Err(DecodeError::UnexpectedType),
         }
     }
@@ -219,7 +329,6 @@ impl<'input> Decoder<'input> {
     /// - array32 (0xdd): array with up to 4294967295 elements
     ///
     /// Ref: <https://github.com/msgpack/msgpack/blob/master/spec.md#formats-array>
-    #[allow(dead_code)]
     fn decode_array_len(&mut self) -> Result<usize, DecodeError> {
         let prefix = self.decode_u8()?;
 
@@ -250,7 +359,6 @@ impl<'input> Decoder<'input> {
     /// - nil (0xc0): nil/null value
     ///
     /// Ref: <https://github.com/msgpack/msgpack/blob/master/spec.md#formats-nil>
-    #[allow(dead_code)]
     fn decode_nil(&mut self) -> Result<(), DecodeError> {
         match self.decode_u8()? {
             MSGPACK_NIL => Ok(()),
@@ -258,19 +366,108 @@ impl<'input> Decoder<'input> {
         }
     }
 
+    /// Decodes a MessagePack-encoded extension value into its type and data.
+    /// Handles the following MessagePack types:
+    /// - fixext1/2/4/8/16 (0xd4 - 0xd8): 1, 2, 4, 8 or 16 bytes of data
+    /// - ext8 (0xc7): up to 255 bytes of data
+    /// - ext16 (0xc8): up to 65535 bytes of data
+    /// - ext32 (0xc9): up to 4294967295 bytes of data
+    ///
+    /// Ref: <https://github.com/msgpack/msgpack/blob/master/spec.md#ext-format-family>
+    fn decode_ext(&mut self) -> Result<(i8, &'input [u8]), DecodeError> {
+        let len = match self.decode_u8()? {
+            MSGPACK_FIXEXT1 => 1,
+            MSGPACK_FIXEXT2 => 2,
+            MSGPACK_FIXEXT4 => 4,
+            MSGPACK_FIXEXT8 => 8,
+            MSGPACK_FIXEXT16 => 16,
+            MSGPACK_EXT8 => self.decode_u8()? as usize,
+            MSGPACK_EXT16 => self.decode_u16()? as usize,
+            MSGPACK_EXT32 => self.decode_u32()? as usize,
+            _ => return Err(DecodeError::UnexpectedType),
+        };
+        let ext_type = self.decode_u8()? as i8;
+
+        if self.offset + len > self.input.len() {
+            return Err(DecodeError::InsufficientData);
+        }
+        let data = &self.input[self.offset..self.offset + len];
+        self.offset += len;
+        Ok((ext_type, data))
+    }
+
     /// Peeks at the next byte to check if it's a nil value without advancing the offset.
     /// Returns true if the next value is nil, false otherwise.
-    #[allow(dead_code)]
     fn peek_nil(&mut self) -> Result<bool, DecodeError> {
-        if self.offset >= self.input.len() {
+        Ok(self.peek_u8()? == MSGPACK_NIL)
+    }
+
+    /// Returns the next byte (the prefix of the next value) without advancing the offset.
+    fn peek_u8(&self) -> Result<u8, DecodeError> {
+        self.input
+            .get(self.offset)
+            .copied()
+            .ok_or(DecodeError::InsufficientData)
+    }
+
+    /// Advances the offset past `len` bytes.
+    fn skip_bytes(&mut self, len: usize) -> Result<(), DecodeError> {
+        if self.offset + len > self.input.len() {
             return Err(DecodeError::InsufficientData);
         }
-        Ok(self.input[self.offset] == MSGPACK_NIL)
+        self.offset += len;
+        Ok(())
+    }
+
+    /// Decodes a MessagePack-encoded integer into any integer type it fits in.
+    fn decode_int<T: TryFrom<i128>>(&mut self) -> Result<T, DecodeError> {
+        T::try_from(self.decode_i128()?).map_err(|_| DecodeError::IntegerOverflow)
+    }
+
+    /// Decodes a MessagePack-encoded float.
+    /// Handles the following MessagePack types:
+    /// - float32 (0xca): IEEE 754 single precision float (big-endian)
+    /// - float64 (0xcb): IEEE 754 double precision float (big-endian)
+    ///
+    /// Ref: <https://github.com/msgpack/msgpack/blob/master/spec.md#float-format-family>
+    fn decode_f64(&mut self) -> Result<f64, DecodeError> {
+        match self.decode_u8()? {
+            MSGPACK_FLOAT32 => Ok(f32::from_bits(self.decode_u32()?) as f64),
+            MSGPACK_FLOAT64 => Ok(f64::from_bits(self.decode_raw_u64()?)),
+            _ => Err(DecodeError::UnexpectedType),
+        }
+    }
+
+    /// Decodes a MessagePack-encoded byte array.
+    /// Handles the following MessagePack types:
+    /// - bin8 (0xc4): byte array up to 255 bytes
+    /// - bin16 (0xc5): byte array up to 65535 bytes
+    /// - bin32 (0xc6): byte array up to 4294967295 bytes
+    ///
+    /// Ref: <https://github.com/msgpack/msgpack/blob/master/spec.md#bin-format-family>
+    fn decode_bin(&mut self) -> Result<Vec<u8>, DecodeError> {
+        let len = match self.decode_u8()? {
+            MSGPACK_BIN8 => self.decode_u8()? as usize,
+            MSGPACK_BIN16 => self.decode_u16()? as usize,
+            MSGPACK_BIN32 => self.decode_u32()? as usize,
+            _ => return Err(DecodeError::UnexpectedType),
+        };
+
+        if self.offset + len > self.input.len() {
+            return Err(DecodeError::InsufficientData);
+        }
+        let value = self.input[self.offset..self.offset + len].to_vec();
+        self.offset += len;
+        Ok(value)
     }
 
     /// Skips a MessagePack value of any type.
     /// This is used when encountering unknown field names in a struct.
     fn skip_value(&mut self) -> Result<(), DecodeError> {
+        self.nested(Self::skip_nested_value)
+    }
+
+    fn skip_nested_value(&mut self) -> Result<(), DecodeError> {
         let prefix = self.decode_u8()?;
 
         match prefix {
@@ -341,7 +538,46 @@ impl<'input> Decoder<'input> {
                 self.offset += 8;
                 Ok(())
             }
-            // Fixed integers are already handled by decode_u8
+            // Fixed integers carry their value in the prefix
+            MSGPACK_POSFIXINT_MIN..=MSGPACK_POSFIXINT_MAX => Ok(()),
+            prefix if (prefix as i8) >= MSGPACK_NEGFIXINT_MIN => Ok(()),
+
+            // Float formats
+            MSGPACK_FLOAT32 => self.skip_bytes(4),
+            MSGPACK_FLOAT64 => self.skip_bytes(8),
+
+            // Binary formats
+            MSGPACK_BIN8 => {
+                let len = self.decode_u8()? as usize;
+                self.skip_bytes(len)
+            }
+            MSGPACK_BIN16 => {
+                let len = self.decode_u16()? as usize;
+                self.skip_bytes(len)
+            }
+            MSGPACK_BIN32 => {
+                let len = self.decode_u32()? as usize;
+                self.skip_bytes(len)
+            }
+
+            // Extension formats: a type byte, then the data
+            MSGPACK_FIXEXT1 => self.skip_bytes(1 + 1),
+            MSGPACK_FIXEXT2 => self.skip_bytes(1 + 2),
+            MSGPACK_FIXEXT4 => self.skip_bytes(1 + 4),
+            MSGPACK_FIXEXT8 => self.skip_bytes(1 + 8),
+            MSGPACK_FIXEXT16 => self.skip_bytes(1 + 16),
+            MSGPACK_EXT8 => {
+                let len = self.decode_u8()? as usize;
+                self.skip_bytes(1 + len)
+            }
+            MSGPACK_EXT16 => {
+                let len = self.decode_u16()? as usize;
+                self.skip_bytes(1 + len)
+            }
+            MSGPACK_EXT32 => {
+                let len = self.decode_u32()? as usize;
+                self.skip_bytes(1 + len)
+            }
 
             // Boolean and nil
             MSGPACK_NIL | MSGPACK_TRUE | MSGPACK_FALSE => Ok(()),
@@ -399,85 +635,492 @@ impl<'input> Decoder<'input> {
         }
     }
 
-    fn deserialize_value(&mut self, wip: Wip<'input>) -> Result<Wip<'input>, DecodeError> {
+    fn deserialize_value<'facet>(&mut self, wip: Wip<'facet>) -> Result<Wip<'facet>, DecodeError> {
+        self.nested(|decoder| decoder.deserialize_nested_value(wip))
+    }
+
+    fn deserialize_nested_value<'facet>(
+        &mut self,
+        wip: Wip<'facet>,
+    ) -> Result<Wip<'facet>, DecodeError> {
         let shape = wip.shape();
         trace!("Deserializing {:?}", shape);
 
-        let wip = match shape.def {
-            Def::Scalar(_) => {
-                trace!("Deserializing scalar");
-                if shape.is_type::<String>() {
-                    let s = self.decode_string()?;
-                    wip.put(s).unwrap()
-                } else if shape.is_type::<u64>() {
-                    let n = self.decode_u64()?;
-                    wip.put(n).unwrap()
-                } else if shape.is_type::<u32>() {
-                    let n = self.decode_u64()?;
-                    if n > u32::MAX as u64 {
-                        return Err(DecodeError::IntegerOverflow);
-                    }
-                    wip.put(n as u32).unwrap()
-                } else if shape.is_type::<u16>() {
-                    let n = self.decode_u64()?;
-                    if n > u16::MAX as u64 {
-                        return Err(DecodeError::IntegerOverflow);
-                    }
-                    wip.put(n as u16).unwrap()
-                } else if shape.is_type::<u8>() {
-                    let n = self.decode_u64()?;
-                    if n > u8::MAX as u64 {
-                        return Err(DecodeError::IntegerOverflow);
-                    }
-                    wip.put(n as u8).unwrap()
-                } else if shape.is_type::<i64>() {
-                    // This is a simplification - need to implement proper int decoding
-                    let n = self.decode_u64()?;
-                    if n > i64::MAX as u64 {
-                        return Err(DecodeError::IntegerOverflow);
-                    }
-                    wip.put(n as i64).unwrap()
-                } else if shape.is_type::<i32>() {
-                    let n = self.decode_u64()?;
-                    if n > i32::MAX as u64 {
-                        return Err(DecodeError::IntegerOverflow);
-                    }
-                    wip.put(n as i32).unwrap()
-                } else if shape.is_type::<bool>() {
-                    let b = self.decode_bool()?;
-                    wip.put(b).unwrap()
+        // Options are unwrapped first, so handlers see the shape of their value
+        if !matches!(shape.def, Def::Option(_)) && is_ext_prefix(self.peek_u8()?) {
+            let (ext_type, data) = self.decode_ext()?;
+            trace!("Decoding extension type {}", ext_type);
+            if let Some(handler) = self.options.ext_handlers.get(ext_type) {
+                return handler.decode(data, wip);
+            }
+            if ext_type == MSGPACK_EXT_TIMESTAMP && timestamp::is_time_scalar(shape) {
+                // time scalars are read from the RFC 3339 text of the timestamp
+                let (seconds, nanoseconds) =
+                    timestamp::decode(data).ok_or(DecodeError::InvalidData)?;
+                return wip
+                    .parse(&timestamp::to_rfc3339(seconds, nanoseconds))
+                    .map_err(reflect_err);
+            }
+            return Err(DecodeError::UnknownExtType(ext_type));
+        }
+
+        match shape.def {
+            Def::Scalar(_) => self.deserialize_scalar(wip),
+            Def::Struct(sd) => match sd.kind {
+                StructKind::Struct => self.deserialize_fields(wip, sd.fields),
+                StructKind::TupleStruct | StructKind::Tuple => {
+                    self.deserialize_fields_array(wip, sd.fields.len())
+                }
+                StructKind::Unit => {
+                    self.decode_nil()?;
+                    Ok(wip)
+                }
+                _ => Err(DecodeError::UnsupportedShape(format!("{:?}", shape))),
+            },
+            Def::List(_) => self.deserialize_list(wip),
+            Def::Array(ad) => self.deserialize_fields_array(wip, ad.n),
+            Def::Map(_) => self.deserialize_map(wip),
+            Def::Option(_) => {
+                if self.peek_nil()? {
+                    self.decode_nil()?;
+                    wip.put_default().map_err(reflect_err)
                 } else {
-                    return Err(DecodeError::UnsupportedType(format!("{}", shape)));
+                    let wip = wip.push_some().map_err(reflect_err)?;
+                    let wip = self.deserialize_value(wip)?;
+                    wip.pop().map_err(reflect_err)
                 }
             }
-            Def::Struct(_) => {
-                trace!("Deserializing struct");
-                let map_len = self.decode_map_len()?;
-
-                let mut wip = wip;
-                for _ in 0..map_len {
-                    let key = self.decode_string()?;
-                    match wip.field_index(&key) {
-                        Some(index) => {
-                            wip = self
-                                .deserialize_value(wip.field(index).unwrap())?
-                                .pop()
-                                .unwrap();
-                        }
-                        None => {
-                            // Skip unknown field value
-                            self.skip_value()?;
-                            trace!("Skipping unknown field: {}", key);
-                        }
+            Def::Enum(_) => self.deserialize_enum(wip),
+            _ => Err(DecodeError::UnsupportedShape(format!("{:?}", shape))),
+        }
+    }
+
+    fn deserialize_scalar<'facet>(&mut self, wip: Wip<'facet>) -> Result<Wip<'facet>, DecodeError> {
+        let shape = wip.shape();
+        trace!("Deserializing scalar");
+
+        let wip = if shape.is_type::<String>() {
+            let s = self.decode_string()?;
+            wip.put(s)
+        } else if shape.is_type::<char>() {
+            let s = self.decode_string()?;
+            let mut chars = s.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => wip.put(c),
+                _ => return Err(DecodeError::InvalidData),
+            }
+        } else if shape.is_type::<bool>() {
+            let b = self.decode_bool()?;
+            wip.put(b)
+        } else if shape.is_type::<f64>() {
+            let n = self.decode_f64()?;
+            wip.put(n)
+        } else if shape.is_type::<f32>() {
+            let n = self.decode_f64()?;
+            wip.put(n as f32)
+        } else if shape.is_type::<u128>() {
+            wip.put(self.decode_int::<u128>()?)
+        } else if shape.is_type::<u64>() {
+            wip.put(self.decode_int::<u64>()?)
+        } else if shape.is_type::<u32>() {
+            wip.put(self.decode_int::<u32>()?)
+        } else if shape.is_type::<u16>() {
+            wip.put(self.decode_int::<u16>()?)
+        } else if shape.is_type::<u8>() {
+            wip.put(self.decode_int::<u8>()?)
+        } else if shape.is_type::<usize>() {
+            wip.put(self.decode_int::<usize>()?)
+        } else if shape.is_type::<i128>() {
+            wip.put(self.decode_int::<i128>()?)
+        } else if shape.is_type::<i64>() {
+            wip.put(self.decode_int::<i64>()?)
+        } else if shape.is_type::<i32>() {
+            wip.put(self.decode_int::<i32>()?)
+        } else if shape.is_type::<i16>() {
+            wip.put(self.decode_int::<i16>()?)
+        } else if shape.is_type::<i8>() {
+            wip.put(self.decode_int::<i8>()?)
+        } else if shape.is_type::<isize>() {
+            wip.put(self.decode_int::<isize>()?)
+        } else if shape.is_type::<()>() {
+            self.decode_nil()?;
+            wip.put(())
+        } else if timestamp::is_time_scalar(shape) {
+            // time scalars are timestamp extension values (see `deserialize_value`),
+            // or the text they parse
+            let s = self.decode_string()?;
+            wip.parse(&s)
+        } else {
+            return Err(DecodeError::UnsupportedType(format!("{}", shape)));
+        };
+        wip.map_err(reflect_err)
+    }
+
+    /// Fills the fields of a struct (or struct variant) from either a map keyed
+    /// by field name, or an array of field values in declaration order, as
+    /// written with `as_array`.
+    fn deserialize_fields<'facet>(
+        &mut self,
+        wip: Wip<'facet>,
+        fields: &'static [Field],
+    ) -> Result<Wip<'facet>, DecodeError> {
+        if is_array_prefix(self.peek_u8()?) {
+            self.deserialize_fields_positional(wip, fields)
+        } else {
+            self.deserialize_fields_map(wip, fields)
+        }
+    }
+
+    /// Fills the fields of a struct (or struct variant) from an array of field
+    /// values in declaration order. Trailing `Option` fields may be left out,
+    /// and are then `None`.
+    fn deserialize_fields_positional<'facet>(
+        &mut self,
+        mut wip: Wip<'facet>,
+        fields: &'static [Field],
+    ) -> Result<Wip<'facet>, DecodeError> {
+        trace!("Deserializing struct from array");
+        let array_len = self.decode_array_len()?;
+        if array_len > fields.len() {
+            return Err(DecodeError::LengthMismatch {
+                expected: fields.len(),
+                got: array_len,
+            });
+        }
+
+        for index in 0..array_len {
+            wip = wip.field(index).map_err(reflect_err)?;
+            wip = self.deserialize_value(wip)?;
+            wip = wip.pop().map_err(reflect_err)?;
+        }
+        default_missing_fields(wip, fields)
+    }
+
+    /// Fills the fields of a struct (or struct variant) from a map keyed by
+    /// field name. Unknown fields are skipped (unless the shape denies them), and
+    /// absent `Option` fields are `None`.
+    fn deserialize_fields_map<'facet>(
+        &mut self,
+        mut wip: Wip<'facet>,
+        fields: &'static [Field],
+    ) -> Result<Wip<'facet>, DecodeError> {
+        trace!("Deserializing struct");
+        let map_len = self.decode_map_len()?;
+
+        for _ in 0..map_len {
+            let key = self.decode_string()?;
+            match wip.field_index(&key) {
+                Some(index) => {
+                    if wip.is_field_set(index).map_err(reflect_err)? {
+                        return Err(DecodeError::DuplicateField(key));
                     }
+                    wip = wip.field(index).map_err(reflect_err)?;
+                    wip = self.deserialize_value(wip)?;
+                    wip = wip.pop().map_err(reflect_err)?;
+                }
+                None if wip.shape().denies_unknown_fields() => {
+                    return Err(DecodeError::UnknownField(key));
+                }
+                None => {
+                    // Skip unknown field value
+                    self.skip_value()?;
+                    trace!("Skipping unknown field: {}", key);
                 }
-                wip
             }
-            _ => {
-                return Err(DecodeError::UnsupportedShape(format!("{:?}", shape)));
+        }
+
+        default_missing_fields(wip, fields)
+    }
+
+    /// Fills the `len` fields of a tuple, tuple struct, tuple variant or
+    /// fixed-size array from an array of exactly `len` elements.
+    fn deserialize_fields_array<'facet>(
+        &mut self,
+        mut wip: Wip<'facet>,
+        len: usize,
+    ) -> Result<Wip<'facet>, DecodeError> {
+        let array_len = self.decode_array_len()?;
+        if array_len != len {
+            return Err(DecodeError::LengthMismatch {
+                expected: len,
+                got: array_len,
+            });
+        }
+
+        for index in 0..len {
+            wip = wip.field(index).map_err(reflect_err)?;
+            wip = self.deserialize_value(wip)?;
+            wip = wip.pop().map_err(reflect_err)?;
+        }
+        Ok(wip)
+    }
+
+    /// Deserializes a list from an array. A list of bytes, like `Vec<u8>`, can
+    /// also be read from a bin.
+    fn deserialize_list<'facet>(&mut self, wip: Wip<'facet>) -> Result<Wip<'facet>, DecodeError> {
+        let is_bytes = matches!(wip.shape().def, Def::List(ld) if (ld.t)().is_type::<u8>());
+        if is_bytes
+            && matches!(
+                self.peek_u8()?,
+                MSGPACK_BIN8 | MSGPACK_BIN16 | MSGPACK_BIN32
+            )
+        {
+            let bytes = self.decode_bin()?;
+            let mut wip = wip.begin_pushback().map_err(reflect_err)?;
+            for byte in bytes {
+                wip = wip
+                    .push()
+                    .and_then(|wip| wip.put(byte))
+                    .and_then(|wip| wip.pop())
+                    .map_err(reflect_err)?;
             }
-        };
+            return Ok(wip);
+        }
 
+        let len = self.decode_array_len()?;
+        let mut wip = wip.begin_pushback().map_err(reflect_err)?;
+        for _ in 0..len {
+            wip = wip.push().map_err(reflect_err)?;
+            wip = self.deserialize_value(wip)?;
+            wip = wip.pop().map_err(reflect_err)?;
+        }
         Ok(wip)
     }
+
+    fn deserialize_map<'facet>(&mut self, wip: Wip<'facet>) -> Result<Wip<'facet>, DecodeError> {
+        let len = self.decode_map_len()?;
+        let mut wip = wip.begin_map_insert().map_err(reflect_err)?;
+        for _ in 0..len {
+            wip = wip.push_map_key().map_err(reflect_err)?;
+            wip = self.deserialize_value(wip)?;
+            wip = wip.push_map_value().map_err(reflect_err)?;
+            wip = self.deserialize_value(wip)?;
+            wip = wip.pop().map_err(reflect_err)?;
+        }
+        Ok(wip)
+    }
+
+    /// Deserializes an enum according to its tagging. By default (like
+    /// rmp-serde), unit variants are their name as a string, and other variants
+    /// a single-entry map from their name to their data.
+    fn deserialize_enum<'facet>(&mut self, wip: Wip<'facet>) -> Result<Wip<'facet>, DecodeError> {
+        match wip.shape().enum_tagging() {
+            EnumTagging::Internal { tag } => self.deserialize_internally_tagged(wip, tag),
+            EnumTagging::Adjacent { tag, content } => {
+                self.deserialize_adjacently_tagged(wip, tag, content)
+            }
+            EnumTagging::Untagged => self.deserialize_untagged(wip),
+            _ => self.deserialize_externally_tagged(wip),
+        }
+    }
+
+    fn deserialize_externally_tagged<'facet>(
+        &mut self,
+        wip: Wip<'facet>,
+    ) -> Result<Wip<'facet>, DecodeError> {
+        if is_str_prefix(self.peek_u8()?) {
+            let name = self.decode_string()?;
+            let wip = select_variant(wip, &name)?;
+            if wip.selected_variant().unwrap().data.kind != StructKind::Unit {
+                return Err(DecodeError::UnexpectedType);
+            }
+            return Ok(wip);
+        }
+
+        if self.decode_map_len()? != 1 {
+            return Err(DecodeError::InvalidData);
+        }
+        let name = self.decode_string()?;
+        let wip = select_variant(wip, &name)?;
+        self.deserialize_variant_payload(wip)
+    }
+
+    /// `{"type": "Variant", ...fields}`
+    fn deserialize_internally_tagged<'facet>(
+        &mut self,
+        wip: Wip<'facet>,
+        tag: &'static str,
+    ) -> Result<Wip<'facet>, DecodeError> {
+        let name = self.find_tag(tag)?;
+        let wip = select_variant(wip, &name)?;
+        let variant = wip.selected_variant().unwrap();
+
+        match variant.internally_tagged_fields() {
+            Some(TaggedFields::Unit) => {
+                self.skip_value()?;
+                Ok(wip)
+            }
+            Some(TaggedFields::Struct(fields)) => self.deserialize_fields_map(wip, fields),
+            Some(TaggedFields::Newtype(fields)) => {
+                let wip = wip.field(0).map_err(reflect_err)?;
+                let wip = self.deserialize_fields_map(wip, fields)?;
+                wip.pop().map_err(reflect_err)
+            }
+            _ => Err(DecodeError::UnsupportedShape(format!("{:?}", wip.shape()))),
+        }
+    }
+
+    /// `{"t": "Variant", "c": payload}`, with the keys in any order. Unit
+    /// variants may leave out the content.
+    fn deserialize_adjacently_tagged<'facet>(
+        &mut self,
+        wip: Wip<'facet>,
+        tag: &'static str,
+        content: &'static str,
+    ) -> Result<Wip<'facet>, DecodeError> {
+        let name = self.find_tag(tag)?;
+        let mut wip = select_variant(wip, &name)?;
+        let variant = wip.selected_variant().unwrap();
+
+        let mut has_content = false;
+        let len = self.decode_map_len()?;
+        for _ in 0..len {
+            let key = self.decode_string()?;
+            if key == content && !has_content {
+                wip = self.deserialize_variant_payload(wip)?;
+                has_content = true;
+            } else {
+                self.skip_value()?;
+            }
+        }
+
+        if !has_content && variant.data.kind != StructKind::Unit {
+            return Err(DecodeError::MissingField(content.to_string()));
+        }
+        Ok(wip)
+    }
+
+    /// Just the payload: tries each variant in declaration order, and picks
+    /// the first one the value deserializes into
+    fn deserialize_untagged<'facet>(
+        &mut self,
+        wip: Wip<'facet>,
+    ) -> Result<Wip<'facet>, DecodeError> {
+        let shape = wip.shape();
+        let start = self.offset;
+        let variant = Wip::find_untagged_variant(shape, |scratch| {
+            let attempt = self.deserialize_variant_payload(scratch);
+            self.offset = start;
+            attempt
+        });
+
+        if let Some(variant) = variant {
+            trace!("Untagged value matches variant {}", variant.name);
+            let wip = wip.variant_named(variant.name).map_err(reflect_err)?;
+            return self.deserialize_variant_payload(wip);
+        }
+        Err(DecodeError::NoMatchingVariant(shape.to_string()))
+    }
+
+    /// Deserializes the data of the selected variant: newtype variants hold
+    /// their value directly, tuple variants are arrays and struct variants maps.
+    /// Unit variants have nil data.
+    fn deserialize_variant_payload<'facet>(
+        &mut self,
+        wip: Wip<'facet>,
+    ) -> Result<Wip<'facet>, DecodeError> {
+        let variant = wip.selected_variant().unwrap();
+        match variant.data.kind {
+            StructKind::Unit => {
+                self.decode_nil()?;
+                Ok(wip)
+            }
+            StructKind::Tuple | StructKind::TupleStruct if variant.data.fields.len() == 1 => {
+                let wip = wip.field(0).map_err(reflect_err)?;
+                let wip = self.deserialize_value(wip)?;
+                wip.pop().map_err(reflect_err)
+            }
+            StructKind::Tuple | StructKind::TupleStruct => {
+                self.deserialize_fields_array(wip, variant.data.fields.len())
+            }
+            _ => self.deserialize_fields(wip, variant.data.fields),
+        }
+    }
+
+    /// Finds the string stored under `tag` in the map that comes next, and
+    /// rewinds to the start of the map
+    fn find_tag(&mut self, tag: &'static str) -> Result<String, DecodeError> {
+        let start = self.offset;
+        let len = self.decode_map_len()?;
+
+        let mut found = None;
+        for _ in 0..len {
+            let key = self.decode_string()?;
+            if key == tag && found.is_none() {
+                found = Some(self.decode_string()?);
+            } else {
+                self.skip_value()?;
+            }
+        }
+
+        self.offset = start;
+        found.ok_or_else(|| DecodeError::MissingField(tag.to_string()))
+    }
+}
+
+/// Whether `prefix` starts an extension value
+fn is_ext_prefix(prefix: u8) -> bool {
+    matches!(
+        prefix,
+        MSGPACK_FIXEXT1..=MSGPACK_FIXEXT16 | MSGPACK_EXT8 | MSGPACK_EXT16 | MSGPACK_EXT32
+    )
+}
+
+/// Whether `prefix` starts an array value
+fn is_array_prefix(prefix: u8) -> bool {
+    matches!(
+        prefix,
+        MSGPACK_FIXARRAY_MIN..=MSGPACK_FIXARRAY_MAX | MSGPACK_ARRAY16 | MSGPACK_ARRAY32
+    )
+}
+
+/// Whether `prefix` starts a map value
+fn is_map_prefix(prefix: u8) -> bool {
+    matches!(
+        prefix,
+        MSGPACK_FIXMAP_MIN..=MSGPACK_FIXMAP_MAX | MSGPACK_MAP16 | MSGPACK_MAP32
+    )
+}
+
+/// Whether `prefix` starts a str value
+fn is_str_prefix(prefix: u8) -> bool {
+    matches!(
+        prefix,
+        MSGPACK_FIXSTR_MIN..=MSGPACK_FIXSTR_MAX | MSGPACK_STR8 | MSGPACK_STR16 | MSGPACK_STR32
+    )
+}
+
+/// Sets the fields that were not in the input to `None`, which only `Option`
+/// fields may be left out for
+fn default_missing_fields<'facet>(
+    mut wip: Wip<'facet>,
+    fields: &'static [Field],
+) -> Result<Wip<'facet>, DecodeError> {
+    for (index, field) in fields.iter().enumerate() {
+        if wip.is_field_set(index).map_err(reflect_err)? {
+            continue;
+        }
+        if !matches!(field.shape().def, Def::Option(_)) {
+            return Err(DecodeError::MissingField(field.name.to_string()));
+        }
+        wip = wip
+            .field(index)
+            .and_then(|wip| wip.put_default())
+            .and_then(|wip| wip.pop())
+            .map_err(reflect_err)?;
+    }
+    Ok(wip)
+}
+
+/// Selects the enum variant called `name`
+fn select_variant<'facet>(wip: Wip<'facet>, name: &str) -> Result<Wip<'facet>, DecodeError> {
+    if wip.find_variant(name).is_none() {
+        return Err(DecodeError::UnknownVariant(name.to_string()));
+    }
+    wip.variant_named(name).map_err(reflect_err)
+}
+
+/// Errors from building the value are reported like the ones from `build`
+fn reflect_err(e: ReflectError) -> DecodeError {
+    DecodeError::UnsupportedType(e.to_string())
 }
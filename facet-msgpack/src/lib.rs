@@ -8,8 +8,16 @@ pub use errors::Error as DecodeError;
 mod constants;
 pub use constants::*;
 
+mod ext;
+pub use ext::ExtHandler;
+
+mod timestamp;
+
 mod from_msgpack;
 pub use from_msgpack::*;
 
 mod to_msgpack;
 pub use to_msgpack::*;
+
+mod stream;
+pub use stream::Decoder;
@@ -0,0 +1,196 @@
+use std::io::{self, Read};
+
+use facet_core::Facet;
+use log::trace;
+
+use crate::constants::*;
+use crate::errors::Error as DecodeError;
+use crate::from_msgpack::{MsgpackReaderOptions, from_slice_with_options};
+
+/// Decodes a sequence of concatenated MessagePack values from a reader, one
+/// value at a time, like messages framed over a socket or records in a file.
+///
+/// The decoder reads exactly the bytes of each value, so it never blocks
+/// waiting for input past the end of the value being decoded. It makes small
+/// reads: wrap unbuffered readers like sockets and files in a
+/// [`BufReader`](std::io::BufReader).
+///
+/// Timestamp extension values are read into time scalars as RFC 3339 text in
+/// UTC, like `2025-03-01T02:00:00Z`, whatever offset they were written with.
+///
+/// # Example
+/// ```
+/// use facet::Facet;
+/// use facet_msgpack::Decoder;
+///
+/// #[derive(Debug, Facet, PartialEq)]
+/// struct Ping {
+///     seq: u32,
+/// }
+///
+/// let mut input = Vec::new();
+/// facet_msgpack::to_writer(&Ping { seq: 1 }, &mut input).unwrap();
+/// facet_msgpack::to_writer(&Ping { seq: 2 }, &mut input).unwrap();
+///
+/// let mut decoder = Decoder::new(input.as_slice());
+/// assert_eq!(decoder.decode::<Ping>().unwrap(), Some(Ping { seq: 1 }));
+/// assert_eq!(decoder.decode::<Ping>().unwrap(), Some(Ping { seq: 2 }));
+/// assert_eq!(decoder.decode::<Ping>().unwrap(), None);
+/// ```
+#[derive(Debug)]
+pub struct Decoder<R> {
+    reader: R,
+    options: MsgpackReaderOptions,
+    buffer: Vec<u8>,
+}
+
+impl<R: Read> Decoder<R> {
+    /// Creates a decoder reading values from `reader`
+    pub fn new(reader: R) -> Self {
+        Self::with_options(reader, MsgpackReaderOptions::default())
+    }
+
+    /// Creates a decoder reading values from `reader`, with the given options
+    pub fn with_options(reader: R, options: MsgpackReaderOptions) -> Self {
+        Decoder {
+            reader,
+            options,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Decodes the next value, or returns `None` if the input ended cleanly
+    /// before it. An input ending in the middle of a value is
+    /// [`DecodeError::InsufficientData`].
+    ///
+    /// If the value is read but doesn't decode into `T`, the decoder moves past
+    /// it, and the next call decodes the value after it.
+    pub fn decode<'facet, T: Facet<'facet>>(&mut self) -> Result<Option<T>, DecodeError> {
+        if !self.read_value()? {
+            return Ok(None);
+        }
+        from_slice_with_options(&self.buffer, &self.options).map(Some)
+    }
+
+    /// Returns a reference to the underlying reader
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Returns the underlying reader, positioned right after the last value read
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Reads the bytes of the next value into the buffer, by walking its
+    /// structure. Returns `false` if the input ends before the value starts.
+    fn read_value(&mut self) -> Result<bool, DecodeError> {
+        self.buffer.clear();
+
+        // the values left to read: arrays and maps add their elements
+        let mut pending: u64 = 1;
+        while pending > 0 {
+            pending -= 1;
+
+            let prefix = match self.read_prefix()? {
+                Some(prefix) => prefix,
+                None if self.buffer.is_empty() => return Ok(false),
+                None => return Err(DecodeError::InsufficientData),
+            };
+
+            match prefix {
+                MSGPACK_POSFIXINT_MIN..=MSGPACK_POSFIXINT_MAX
+                | MSGPACK_NIL
+                | MSGPACK_FALSE
+                | MSGPACK_TRUE => {}
+                prefix if (prefix as i8) >= MSGPACK_NEGFIXINT_MIN => {}
+                MSGPACK_FIXMAP_MIN..=MSGPACK_FIXMAP_MAX => {
+                    pending += 2 * (prefix & 0x0f) as u64;
+                }
+                MSGPACK_FIXARRAY_MIN..=MSGPACK_FIXARRAY_MAX => {
+                    pending += (prefix & 0x0f) as u64;
+                }
+                MSGPACK_FIXSTR_MIN..=MSGPACK_FIXSTR_MAX => {
+                    self.read_bytes((prefix & 0x1f) as u64)?;
+                }
+                MSGPACK_UINT8 | MSGPACK_INT8 => self.read_bytes(1)?,
+                MSGPACK_UINT16 | MSGPACK_INT16 => self.read_bytes(2)?,
+                MSGPACK_UINT32 | MSGPACK_INT32 | MSGPACK_FLOAT32 => self.read_bytes(4)?,
+                MSGPACK_UINT64 | MSGPACK_INT64 | MSGPACK_FLOAT64 => self.read_bytes(8)?,
+                MSGPACK_STR8 | MSGPACK_BIN8 => {
+                    let len = self.read_len(1)?;
+                    self.read_bytes(len)?;
+                }
+                MSGPACK_STR16 | MSGPACK_BIN16 => {
+                    let len = self.read_len(2)?;
+                    self.read_bytes(len)?;
+                }
+                MSGPACK_STR32 | MSGPACK_BIN32 => {
+                    let len = self.read_len(4)?;
+                    self.read_bytes(len)?;
+                }
+                // the extension type, then the data
+                MSGPACK_FIXEXT1 => self.read_bytes(1 + 1)?,
+                MSGPACK_FIXEXT2 => self.read_bytes(1 + 2)?,
+                MSGPACK_FIXEXT4 => self.read_bytes(1 + 4)?,
+                MSGPACK_FIXEXT8 => self.read_bytes(1 + 8)?,
+                MSGPACK_FIXEXT16 => self.read_bytes(1 + 16)?,
+                MSGPACK_EXT8 => {
+                    let len = self.read_len(1)?;
+                    self.read_bytes(1 + len)?;
+                }
+                MSGPACK_EXT16 => {
+                    let len = self.read_len(2)?;
+                    self.read_bytes(1 + len)?;
+                }
+                MSGPACK_EXT32 => {
+                    let len = self.read_len(4)?;
+                    self.read_bytes(1 + len)?;
+                }
+                MSGPACK_ARRAY16 => pending += self.read_len(2)?,
+                MSGPACK_ARRAY32 => pending += self.read_len(4)?,
+                MSGPACK_MAP16 => pending += 2 * self.read_len(2)?,
+                MSGPACK_MAP32 => pending += 2 * self.read_len(4)?,
+                _ => return Err(DecodeError::InvalidData),
+            }
+        }
+
+        trace!("Read a value of {} bytes", self.buffer.len());
+        Ok(true)
+    }
+
+    /// Reads the first byte of a value, or returns `None` at the end of the input
+    fn read_prefix(&mut self) -> Result<Option<u8>, DecodeError> {
+        let mut prefix = [0u8];
+        loop {
+            match self.reader.read(&mut prefix) {
+                Ok(0) => return Ok(None),
+                Ok(_) => {
+                    self.buffer.push(prefix[0]);
+                    return Ok(Some(prefix[0]));
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    /// Reads a big-endian length of `size` bytes
+    fn read_len(&mut self, size: usize) -> Result<u64, DecodeError> {
+        let start = self.buffer.len();
+        self.read_bytes(size as u64)?;
+        Ok(self.buffer[start..]
+            .iter()
+            .fold(0, |len, &byte| (len << 8) | byte as u64))
+    }
+
+    /// Reads exactly `len` bytes. The buffer grows as data actually arrives,
+    /// so a bogus length can't allocate more than the input holds.
+    fn read_bytes(&mut self, len: u64) -> Result<(), DecodeError> {
+        let read = (&mut self.reader).take(len).read_to_end(&mut self.buffer)?;
+        if (read as u64) < len {
+            return Err(DecodeError::InsufficientData);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,183 @@
+//! The timestamp extension type, read into and written from time-affinity
+//! scalars through their RFC 3339 text.
+//!
+//! Ref: <https://github.com/msgpack/msgpack/blob/master/spec.md#timestamp-extension-type>
+
+use facet_core::{Def, ScalarAffinity, Shape};
+
+/// Whether `shape` is a scalar with a time affinity, like datetime types
+pub(crate) fn is_time_scalar(shape: &Shape) -> bool {
+    matches!(shape.def, Def::Scalar(sd) if matches!(sd.affinity, ScalarAffinity::Time(_)))
+}
+
+/// Whether `shape` is a time scalar whose values are absolute instants, like
+/// UTC or offset datetimes, which a timestamp can hold
+pub(crate) fn is_instant_scalar(shape: &Shape) -> bool {
+    matches!(shape.def, Def::Scalar(sd) if matches!(sd.affinity, ScalarAffinity::Time(ta) if ta.is_absolute()))
+}
+
+/// Reads the seconds and nanoseconds since the Unix epoch from the data of a
+/// timestamp 32, 64 or 96
+pub(crate) fn decode(data: &[u8]) -> Option<(i64, u32)> {
+    let (seconds, nanoseconds) = match data.len() {
+        4 => (u32::from_be_bytes(data.try_into().unwrap()) as i64, 0),
+        8 => {
+            let value = u64::from_be_bytes(data.try_into().unwrap());
+            ((value & 0x3_ffff_ffff) as i64, (value >> 34) as u32)
+        }
+        12 => (
+            i64::from_be_bytes(data[4..].try_into().unwrap()),
+            u32::from_be_bytes(data[..4].try_into().unwrap()),
+        ),
+        _ => return None,
+    };
+    (nanoseconds < 1_000_000_000).then_some((seconds, nanoseconds))
+}
+
+/// Writes the data of the smallest timestamp form that holds the given time
+pub(crate) fn encode(seconds: i64, nanoseconds: u32) -> Vec<u8> {
+    if seconds >> 34 == 0 {
+        let value = ((nanoseconds as u64) << 34) | seconds as u64;
+        if value >> 32 == 0 {
+            // timestamp 32
+            (value as u32).to_be_bytes().to_vec()
+        } else {
+            // timestamp 64
+            value.to_be_bytes().to_vec()
+        }
+    } else {
+        // timestamp 96
+        let mut data = nanoseconds.to_be_bytes().to_vec();
+        data.extend_from_slice(&seconds.to_be_bytes());
+        data
+    }
+}
+
+/// Formats a time as RFC 3339 text in UTC, like `2025-03-01T02:00:00.5Z`
+pub(crate) fn to_rfc3339(seconds: i64, nanoseconds: u32) -> String {
+    let (year, month, day) = civil_from_days(seconds.div_euclid(86_400));
+    let second_of_day = seconds.rem_euclid(86_400);
+    let mut text = format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        second_of_day / 3600,
+        second_of_day / 60 % 60,
+        second_of_day % 60
+    );
+    if nanoseconds != 0 {
+        let fraction = format!("{:09}", nanoseconds);
+        text.push('.');
+        text.push_str(fraction.trim_end_matches('0'));
+    }
+    text.push('Z');
+    text
+}
+
+/// Parses RFC 3339 text with an offset, like `2025-03-01T03:00:00.5+01:00`,
+/// into the seconds and nanoseconds since the Unix epoch
+pub(crate) fn from_rfc3339(text: &str) -> Option<(i64, u32)> {
+    let bytes = text.as_bytes();
+    if !text.is_ascii()
+        || bytes.len() < 20
+        || bytes[4] != b'-'
+        || bytes[7] != b'-'
+        || !matches!(bytes[10], b'T' | b't' | b' ')
+        || bytes[13] != b':'
+        || bytes[16] != b':'
+    {
+        return None;
+    }
+
+    let year = digits(&text[0..4])?;
+    let month = digits(&text[5..7])?;
+    let day = digits(&text[8..10])?;
+    let hour = digits(&text[11..13])?;
+    let minute = digits(&text[14..16])?;
+    let second = digits(&text[17..19])?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 {
+        return None;
+    }
+    // leap seconds are folded into the next second
+    if second > 60 {
+        return None;
+    }
+
+    let mut rest = &text[19..];
+    let mut nanoseconds = 0;
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let len = fraction
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(fraction.len());
+        if len == 0 {
+            return None;
+        }
+        // digits past nanoseconds are truncated
+        for (index, digit) in fraction[..len].bytes().take(9).enumerate() {
+            nanoseconds += (digit - b'0') as u32 * 10u32.pow(8 - index as u32);
+        }
+        rest = &fraction[len..];
+    }
+
+    let offset = match rest {
+        "Z" | "z" => 0,
+        _ => {
+            let sign = match rest.as_bytes().first()? {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            if rest.len() != 6 || rest.as_bytes()[3] != b':' {
+                return None;
+            }
+            let hours = digits(&rest[1..3])?;
+            let minutes = digits(&rest[4..6])?;
+            if hours > 23 || minutes > 59 {
+                return None;
+            }
+            sign * (hours * 3600 + minutes * 60)
+        }
+    };
+
+    let days = days_from_civil(year, month, day);
+    let seconds = days * 86_400 + hour * 3600 + minute * 60 + second - offset;
+    Some((seconds, nanoseconds))
+}
+
+/// Parses a fixed number of ASCII digits
+fn digits(text: &str) -> Option<i64> {
+    if !text.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    text.parse().ok()
+}
+
+/// Days since 1970-01-01 of a date in the proleptic Gregorian calendar
+///
+/// Ref: <https://howardhinnant.github.io/date_algorithms.html#days_from_civil>
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Date in the proleptic Gregorian calendar of a number of days since 1970-01-01
+///
+/// Ref: <https://howardhinnant.github.io/date_algorithms.html#civil_from_days>
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
@@ -1,19 +1,119 @@
-use facet_core::{Def, Facet};
+use facet_core::{Def, EnumTagging, Facet, Field, StructKind};
 
-use facet_reflect::Peek;
+use crate::constants::MSGPACK_EXT_TIMESTAMP;
+use crate::ext::{ExtHandler, ExtHandlers};
+use crate::timestamp;
+use facet_reflect::{Peek, PeekEnum};
 use log::trace;
 use std::io::{self, Write};
 
+/// Options controlling how [`to_vec_with_options`] and [`to_writer_with_options`]
+/// encode values.
+#[derive(Debug, Clone, Default)]
+pub struct MsgpackWriterOptions {
+    ext_handlers: ExtHandlers,
+    struct_as_array: bool,
+    skip_trailing_none: bool,
+}
+
+impl MsgpackWriterOptions {
+    /// Creates the default options: no value is written as an extension value,
+    /// and structs are written as maps keyed by field name
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Writes the values `handler` encodes as extension values of its type,
+    /// replacing any handler previously registered for that type
+    pub fn with_ext_handler(mut self, handler: impl ExtHandler + 'static) -> Self {
+        self.ext_handlers.insert(handler);
+        self
+    }
+
+    /// Writes every struct and struct variant as an array of its field values,
+    /// in declaration order, like rmp-serde's compact mode. Without this, only
+    /// types marked `#[facet(as_array)]` are written that way.
+    ///
+    /// Internally tagged enums still write their struct variants as maps, since
+    /// the tag is a key of that map.
+    pub fn with_struct_as_array(mut self, struct_as_array: bool) -> Self {
+        self.struct_as_array = struct_as_array;
+        self
+    }
+
+    /// Leaves the trailing `None` fields out of structs written as arrays,
+    /// which decode back as `None`
+    pub fn with_skip_trailing_none(mut self, skip_trailing_none: bool) -> Self {
+        self.skip_trailing_none = skip_trailing_none;
+        self
+    }
+}
+
 /// Serializes any Facet type to MessagePack bytes
-pub fn to_vec<T: Facet>(value: &T) -> Vec<u8> {
+///
+/// # Time scalars
+///
+/// Time scalars that are absolute instants (see
+/// [`TimeAffinity::is_absolute`](facet_core::TimeAffinity::is_absolute)) and
+/// display as RFC 3339 are written as timestamp extension values. A timestamp
+/// doesn't keep an offset: `2025-03-01T03:00:00+01:00` is read back as
+/// `2025-03-01T02:00:00Z`. Other time scalars, like local datetimes, are
+/// written as strings.
+///
+/// # Panics
+///
+/// Panics if the value can't be represented as MessagePack.
+/// [`to_vec_with_options`] and [`to_writer`] report that as an error instead.
+pub fn to_vec<'a, T: Facet<'a>>(value: &T) -> Vec<u8> {
+    to_vec_with_options(value, &MsgpackWriterOptions::default()).unwrap()
+}
+
+/// Serializes any Facet type to MessagePack bytes, with the given options
+///
+/// # Errors
+///
+/// Fails on values MessagePack can't hold, like integers past 64 bits, and on
+/// shapes that can't be written, like tuple variants of internally tagged enums.
+pub fn to_vec_with_options<'a, T: Facet<'a>>(
+    value: &T,
+    options: &MsgpackWriterOptions,
+) -> io::Result<Vec<u8>> {
     let mut buffer = Vec::new();
+    to_writer_with_options(value, &mut buffer, options)?;
+    Ok(buffer)
+}
+
+/// Serializes any Facet type as MessagePack to a writer, like a socket or a
+/// file. Values written one after the other can be read back with a
+/// [`Decoder`](crate::Decoder).
+///
+/// The value is written with many small writes: wrap unbuffered writers in a
+/// [`BufWriter`](std::io::BufWriter).
+pub fn to_writer<'a, T: Facet<'a>, W: Write>(value: &T, writer: W) -> io::Result<()> {
+    to_writer_with_options(value, writer, &MsgpackWriterOptions::default())
+}
+
+/// Serializes any Facet type as MessagePack to a writer, with the given options
+pub fn to_writer_with_options<'a, T: Facet<'a>, W: Write>(
+    value: &T,
+    mut writer: W,
+    options: &MsgpackWriterOptions,
+) -> io::Result<()> {
     let peek = Peek::new(value);
-    serialize(peek, &mut buffer).unwrap();
-    buffer
+    serialize(peek, options, &mut writer)
 }
 
 /// Serializes any Facet type to a writer in MessagePack format
-fn serialize<W: Write>(pv: Peek<'_>, writer: &mut W) -> io::Result<()> {
+fn serialize<W: Write>(
+    pv: Peek<'_>,
+    options: &MsgpackWriterOptions,
+    writer: &mut W,
+) -> io::Result<()> {
+    if let Some((ext_type, data)) = options.ext_handlers.encode(pv) {
+        trace!("Serializing extension type {}", ext_type);
+        return write_ext(writer, ext_type, &data);
+    }
+
     let shape = pv.shape();
     match shape.def {
         Def::Scalar(_) => {
@@ -21,9 +121,29 @@ fn serialize<W: Write>(pv: Peek<'_>, writer: &mut W) -> io::Result<()> {
             if pv.shape().is_type::<String>() {
                 let value = pv.get::<String>().unwrap();
                 write_str(writer, value)
+            } else if pv.shape().is_type::<&str>() {
+                let value = pv.get::<&str>().unwrap();
+                write_str(writer, value)
+            } else if pv.shape().is_type::<std::borrow::Cow<'_, str>>() {
+                let value = pv.get::<std::borrow::Cow<'_, str>>().unwrap();
+                write_str(writer, value)
+            } else if pv.shape().is_type::<char>() {
+                let value = pv.get::<char>().unwrap();
+                write_str(writer, value.encode_utf8(&mut [0; 4]))
+            } else if pv.shape().is_type::<bool>() {
+                let value = pv.get::<bool>().unwrap();
+                write_bool(writer, *value)
+            } else if pv.shape().is_type::<()>() {
+                write_nil(writer)
+            } else if pv.shape().is_type::<u128>() {
+                let value = pv.get::<u128>().unwrap();
+                write_u64(writer, u64::try_from(*value).map_err(other)?)
             } else if pv.shape().is_type::<u64>() {
                 let value = pv.get::<u64>().unwrap();
                 write_u64(writer, *value)
+            } else if pv.shape().is_type::<usize>() {
+                let value = pv.get::<usize>().unwrap();
+                write_u64(writer, *value as u64)
             } else if pv.shape().is_type::<u32>() {
                 let value = pv.get::<u32>().unwrap();
                 write_u32(writer, *value)
@@ -33,9 +153,15 @@ fn serialize<W: Write>(pv: Peek<'_>, writer: &mut W) -> io::Result<()> {
             } else if pv.shape().is_type::<u8>() {
                 let value = pv.get::<u8>().unwrap();
                 write_u8(writer, *value)
+            } else if pv.shape().is_type::<i128>() {
+                let value = pv.get::<i128>().unwrap();
+                write_i64(writer, i64::try_from(*value).map_err(other)?)
             } else if pv.shape().is_type::<i64>() {
                 let value = pv.get::<i64>().unwrap();
                 write_i64(writer, *value)
+            } else if pv.shape().is_type::<isize>() {
+                let value = pv.get::<isize>().unwrap();
+                write_i64(writer, *value as i64)
             } else if pv.shape().is_type::<i32>() {
                 let value = pv.get::<i32>().unwrap();
                 write_i32(writer, *value)
@@ -47,6 +173,27 @@ fn serialize<W: Write>(pv: Peek<'_>, writer: &mut W) -> io::Result<()> {
                     .get::<i8>()
                     .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
                 write_i8(writer, *value)
+            } else if pv.shape().is_type::<f64>() {
+                let value = pv.get::<f64>().unwrap();
+                write_f64(writer, *value)
+            } else if pv.shape().is_type::<f32>() {
+                let value = pv.get::<f32>().unwrap();
+                write_f32(writer, *value)
+            } else if timestamp::is_time_scalar(shape) {
+                // instants are written as timestamps when they display as
+                // RFC 3339, and other time scalars as strings
+                let display = pv.to_string();
+                if !timestamp::is_instant_scalar(shape) {
+                    return write_str(writer, &display);
+                }
+                match timestamp::from_rfc3339(&display) {
+                    Some((seconds, nanoseconds)) => write_ext(
+                        writer,
+                        MSGPACK_EXT_TIMESTAMP,
+                        &timestamp::encode(seconds, nanoseconds),
+                    ),
+                    None => write_str(writer, &display),
+                }
             } else {
                 return Err(io::Error::new(
                     io::ErrorKind::Other,
@@ -56,21 +203,74 @@ fn serialize<W: Write>(pv: Peek<'_>, writer: &mut W) -> io::Result<()> {
         }
         Def::Struct(sd) => {
             trace!("Serializing struct");
-            let ps = pv
-                .into_struct()
+            let ps = pv.into_struct().map_err(other)?;
+            match sd.kind {
+                StructKind::Unit => write_nil(writer),
+                StructKind::Tuple | StructKind::TupleStruct => serialize_array(
+                    ps.fields().map(|(_, field_peek)| field_peek),
+                    options,
+                    writer,
+                ),
+                _ => {
+                    let as_array = options.struct_as_array || shape.is_as_array();
+                    serialize_fields(ps.fields().collect(), as_array, options, writer)
+                }
+            }
+        }
+        Def::List(ld) if (ld.t)().is_type::<u8>() => {
+            trace!("Serializing byte list as bin");
+            let pl = pv
+                .into_list()
                 .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
-
-            // Write map header
-            let fields = sd.fields;
-            write_map_len(writer, fields.len())?;
-
-            // Write fields
-            for (field, field_peek) in ps.fields() {
-                write_str(writer, field.name)?;
-                serialize(field_peek, writer)?;
+            let bytes = pl
+                .iter()
+                .map(|item| item.get::<u8>().copied())
+                .collect::<Result<Vec<u8>, _>>()
+                .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+            write_bin(writer, &bytes)
+        }
+        Def::List(_) => {
+            trace!("Serializing list");
+            serialize_array(pv.into_list().map_err(other)?.iter(), options, writer)
+        }
+        Def::Array(_) => {
+            trace!("Serializing array");
+            serialize_array(pv.into_array().map_err(other)?.iter(), options, writer)
+        }
+        Def::Slice(_) => {
+            trace!("Serializing slice");
+            serialize_array(pv.into_slice().map_err(other)?.iter(), options, writer)
+        }
+        Def::Map(_) => {
+            trace!("Serializing map");
+            let pm = pv.into_map().map_err(other)?;
+            write_map_len(writer, pm.len())?;
+            for (key, value) in pm.iter() {
+                serialize(key, options, writer)?;
+                serialize(value, options, writer)?;
             }
             Ok(())
         }
+        Def::Option(_) => {
+            trace!("Serializing option");
+            match pv.into_option().map_err(other)?.value() {
+                Some(value) => serialize(value, options, writer),
+                None => write_nil(writer),
+            }
+        }
+        Def::SmartPointer(_) => {
+            trace!("Serializing smart pointer");
+            let inner = pv
+                .into_smart_pointer()
+                .map_err(other)?
+                .borrow_inner()
+                .ok_or_else(|| other(format!("Cannot borrow from smart pointer: {}", shape)))?;
+            serialize(inner, options, writer)
+        }
+        Def::Enum(_) => {
+            trace!("Serializing enum");
+            serialize_enum(pv, options, writer)
+        }
         _ => Err(io::Error::new(
             io::ErrorKind::Other,
             format!("Unsupported type: {:?}", pv.shape()),
@@ -78,6 +278,149 @@ fn serialize<W: Write>(pv: Peek<'_>, writer: &mut W) -> io::Result<()> {
     }
 }
 
+/// Writes the fields of a struct or struct variant, as a map keyed by field
+/// name or, with `as_array`, as an array of field values
+fn serialize_fields<W: Write>(
+    mut fields: Vec<(&'static Field, Peek<'_>)>,
+    as_array: bool,
+    options: &MsgpackWriterOptions,
+    writer: &mut W,
+) -> io::Result<()> {
+    if !as_array {
+        write_map_len(writer, fields.len())?;
+        for (field, field_peek) in fields {
+            write_str(writer, field.name)?;
+            serialize(field_peek, options, writer)?;
+        }
+        return Ok(());
+    }
+
+    if options.skip_trailing_none {
+        while fields
+            .last()
+            .is_some_and(|(_, field_peek)| is_none(field_peek))
+        {
+            fields.pop();
+        }
+    }
+    serialize_array(
+        fields.into_iter().map(|(_, field_peek)| field_peek),
+        options,
+        writer,
+    )
+}
+
+fn serialize_array<'mem, W: Write>(
+    items: impl Iterator<Item = Peek<'mem>>,
+    options: &MsgpackWriterOptions,
+    writer: &mut W,
+) -> io::Result<()> {
+    let items = items.collect::<Vec<_>>();
+    write_array_len(writer, items.len())?;
+    for item in items {
+        serialize(item, options, writer)?;
+    }
+    Ok(())
+}
+
+/// Writes an enum in the representation selected by its tagging attributes,
+/// the way `from_slice` reads it
+fn serialize_enum<W: Write>(
+    pv: Peek<'_>,
+    options: &MsgpackWriterOptions,
+    writer: &mut W,
+) -> io::Result<()> {
+    let shape = pv.shape();
+    let pe = pv.into_enum().map_err(other)?;
+    let variant = pe.active_variant();
+    let is_unit = variant.data.kind == StructKind::Unit;
+
+    match shape.enum_tagging() {
+        EnumTagging::Untagged => serialize_variant_payload(pe, options, writer),
+        EnumTagging::External if is_unit => write_str(writer, variant.name),
+        EnumTagging::External => {
+            write_map_len(writer, 1)?;
+            write_str(writer, variant.name)?;
+            serialize_variant_payload(pe, options, writer)
+        }
+        EnumTagging::Adjacent { tag, content } => {
+            write_map_len(writer, if is_unit { 1 } else { 2 })?;
+            write_str(writer, tag)?;
+            write_str(writer, variant.name)?;
+            if !is_unit {
+                write_str(writer, content)?;
+                serialize_variant_payload(pe, options, writer)?;
+            }
+            Ok(())
+        }
+        EnumTagging::Internal { tag } => {
+            let Some(fields) = pe.internally_tagged_fields() else {
+                return Err(other(format!(
+                    "Internally tagged variant {}::{} must be a unit, struct or newtype struct variant",
+                    shape, variant.name
+                )));
+            };
+            let fields = fields.collect::<Vec<_>>();
+            write_map_len(writer, fields.len() + 1)?;
+            write_str(writer, tag)?;
+            write_str(writer, variant.name)?;
+            for (field, field_peek) in fields {
+                write_str(writer, field.name)?;
+                serialize(field_peek, options, writer)?;
+            }
+            Ok(())
+        }
+        _ => Err(other(format!("Unsupported enum tagging for {}", shape))),
+    }
+}
+
+/// Writes the data of the active variant: newtype variants are their value,
+/// tuple variants arrays and struct variants maps (or arrays, with `as_array`).
+/// Unit variants have nil data.
+fn serialize_variant_payload<W: Write>(
+    pe: PeekEnum<'_>,
+    options: &MsgpackWriterOptions,
+    writer: &mut W,
+) -> io::Result<()> {
+    let variant = pe.active_variant();
+    match variant.data.kind {
+        StructKind::Unit => write_nil(writer),
+        StructKind::Tuple | StructKind::TupleStruct if variant.data.fields.len() == 1 => {
+            let field = pe
+                .field(0)
+                .ok_or_else(|| other("Failed to access enum field"))?;
+            serialize(field, options, writer)
+        }
+        StructKind::Tuple | StructKind::TupleStruct => serialize_array(
+            pe.fields().map(|(_, field_peek)| field_peek),
+            options,
+            writer,
+        ),
+        _ => {
+            let as_array = options.struct_as_array || pe.shape().is_as_array();
+            serialize_fields(pe.fields().collect(), as_array, options, writer)
+        }
+    }
+}
+
+/// Whether `peek` is a `None`
+fn is_none(peek: &Peek<'_>) -> bool {
+    peek.into_option()
+        .is_ok_and(|option_peek| option_peek.is_none())
+}
+
+fn other(e: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, e)
+}
+
+fn write_nil<W: Write>(writer: &mut W) -> io::Result<()> {
+    writer.write_all(&[0xc0])
+}
+
+fn write_bool<W: Write>(writer: &mut W, b: bool) -> io::Result<()> {
+    writer.write_all(&[if b { 0xc3 } else { 0xc2 }])
+}
+
 fn write_str<W: Write>(writer: &mut W, s: &str) -> io::Result<()> {
     let bytes = s.as_bytes();
     let len = bytes.len();
@@ -105,6 +448,68 @@ fn write_str<W: Write>(writer: &mut W, s: &str) -> io::Result<()> {
     writer.write_all(bytes)
 }
 
+fn write_bin<W: Write>(writer: &mut W, bytes: &[u8]) -> io::Result<()> {
+    let len = bytes.len();
+
+    match len {
+        0..=255 => {
+            // bin8
+            writer.write_all(&[0xc4, len as u8])?;
+        }
+        256..=65535 => {
+            // bin16
+            writer.write_all(&[0xc5])?;
+            writer.write_all(&(len as u16).to_be_bytes())?;
+        }
+        _ => {
+            // bin32
+            writer.write_all(&[0xc6])?;
+            writer.write_all(&(len as u32).to_be_bytes())?;
+        }
+    }
+    writer.write_all(bytes)
+}
+
+fn write_ext<W: Write>(writer: &mut W, ext_type: i8, data: &[u8]) -> io::Result<()> {
+    let len = data.len();
+
+    match len {
+        1 => writer.write_all(&[0xd4])?,  // fixext1
+        2 => writer.write_all(&[0xd5])?,  // fixext2
+        4 => writer.write_all(&[0xd6])?,  // fixext4
+        8 => writer.write_all(&[0xd7])?,  // fixext8
+        16 => writer.write_all(&[0xd8])?, // fixext16
+        0..=255 => {
+            // ext8
+            writer.write_all(&[0xc7, len as u8])?;
+        }
+        256..=65535 => {
+            // ext16
+            writer.write_all(&[0xc8])?;
+            writer.write_all(&(len as u16).to_be_bytes())?;
+        }
+        _ => {
+            // ext32
+            writer.write_all(&[0xc9])?;
+            writer.write_all(&(len as u32).to_be_bytes())?;
+        }
+    }
+    writer.write_all(&[ext_type as u8])?;
+    writer.write_all(data)
+}
+
+fn write_f32<W: Write>(writer: &mut W, n: f32) -> io::Result<()> {
+    // float32
+    writer.write_all(&[0xca])?;
+    writer.write_all(&n.to_be_bytes())
+}
+
+fn write_f64<W: Write>(writer: &mut W, n: f64) -> io::Result<()> {
+    // float64
+    writer.write_all(&[0xcb])?;
+    writer.write_all(&n.to_be_bytes())
+}
+
 fn write_u8<W: Write>(writer: &mut W, n: u8) -> io::Result<()> {
     match n {
         0..=127 => {
@@ -327,6 +732,25 @@ fn write_i64<W: Write>(writer: &mut W, n: i64) -> io::Result<()> {
     }
 }
 
+fn write_array_len<W: Write>(writer: &mut W, len: usize) -> io::Result<()> {
+    match len {
+        0..=15 => {
+            // fixarray
+            writer.write_all(&[(0x90 | len as u8)])
+        }
+        16..=65535 => {
+            // array16
+            writer.write_all(&[0xdc])?;
+            writer.write_all(&(len as u16).to_be_bytes())
+        }
+        _ => {
+            // array32
+            writer.write_all(&[0xdd])?;
+            writer.write_all(&(len as u32).to_be_bytes())
+        }
+    }
+}
+
 fn write_map_len<W: Write>(writer: &mut W, len: usize) -> io::Result<()> {
     match len {
         0..=15 => {
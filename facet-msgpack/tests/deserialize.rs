@@ -31,3 +31,493 @@ fn it_works() {
         }
     );
 }
+
+#[test]
+fn test_scalars() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Scalars {
+        f: f64,
+        g: f32,
+        c: char,
+        small: i8,
+        medium: i16,
+        big: u128,
+        unit: (),
+    }
+
+    let data = [
+        0x87, // Fixmap with 7 elements
+        0xa1, b'f', // "f"
+        0xcb, 0x3f, 0xf8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // float64 1.5
+        0xa1, b'g', // "g"
+        0xca, 0x40, 0x20, 0x00, 0x00, // float32 2.5
+        0xa1, b'c', // "c"
+        0xa2, 0xc3, 0xa9, // "é"
+        0xa5, b's', b'm', b'a', b'l', b'l', // "small"
+        0x7f, // 127
+        0xa6, b'm', b'e', b'd', b'i', b'u', b'm', // "medium"
+        0xcd, 0x01, 0x00, // uint16 256
+        0xa3, b'b', b'i', b'g', // "big"
+        0xcf, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, // uint64 max
+        0xa4, b'u', b'n', b'i', b't', // "unit"
+        0xc0, // nil
+    ];
+
+    let result: Scalars = facet_msgpack::from_slice(&data).unwrap();
+    assert_eq!(
+        result,
+        Scalars {
+            f: 1.5,
+            g: 2.5,
+            c: 'é',
+            small: 127,
+            medium: 256,
+            big: u64::MAX as u128,
+            unit: (),
+        }
+    );
+
+    // "ab" is not a char
+    assert!(facet_msgpack::from_slice::<char>(&[0xa2, b'a', b'b']).is_err());
+    // 128 doesn't fit in an i8
+    assert!(matches!(
+        facet_msgpack::from_slice::<i8>(&[0xcc, 0x80]),
+        Err(facet_msgpack::DecodeError::IntegerOverflow)
+    ));
+}
+
+#[test]
+fn test_collections() {
+    facet_testhelpers::setup();
+
+    use std::collections::HashMap;
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Collections {
+        list: Vec<u32>,
+        bytes: Vec<u8>,
+        small_bytes: Vec<u8>,
+        fixed: [u16; 2],
+        map: HashMap<String, bool>,
+        pair: (u8, String),
+    }
+
+    let data = [
+        0x86, // Fixmap with 6 elements
+        0xa4, b'l', b'i', b's', b't', // "list"
+        0x93, 0x01, 0x02, 0xcc, 0xff, // [1, 2, 255]
+        0xa5, b'b', b'y', b't', b'e', b's', // "bytes"
+        0xc4, 0x03, 0xde, 0xad, 0x00, // bin8 with 3 bytes
+        0xab, b's', b'm', b'a', b'l', b'l', b'_', b'b', b'y', b't', b'e',
+        b's', // "small_bytes"
+        0x92, 0x07, 0x08, // [7, 8]
+        0xa5, b'f', b'i', b'x', b'e', b'd', // "fixed"
+        0x92, 0x0a, 0xcd, 0x01, 0x00, // [10, 256]
+        0xa3, b'm', b'a', b'p', // "map"
+        0x81, 0xa2, b'o', b'n', 0xc3, // {"on": true}
+        0xa4, b'p', b'a', b'i', b'r', // "pair"
+        0x92, 0x05, 0xa1, b'x', // [5, "x"]
+    ];
+
+    let result: Collections = facet_msgpack::from_slice(&data).unwrap();
+    assert_eq!(
+        result,
+        Collections {
+            list: vec![1, 2, 255],
+            bytes: vec![0xde, 0xad, 0x00],
+            small_bytes: vec![7, 8],
+            fixed: [10, 256],
+            map: HashMap::from([("on".to_string(), true)]),
+            pair: (5, "x".to_string()),
+        }
+    );
+
+    assert!(matches!(
+        facet_msgpack::from_slice::<[u8; 3]>(&[0x92, 0x01, 0x02]),
+        Err(facet_msgpack::DecodeError::LengthMismatch {
+            expected: 3,
+            got: 2
+        })
+    ));
+}
+
+#[test]
+fn test_options() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Options {
+        set: Option<u8>,
+        nil: Option<u8>,
+        absent: Option<String>,
+    }
+
+    let data = [
+        0x82, // Fixmap with 2 elements
+        0xa3, b's', b'e', b't', 0x05, // "set": 5
+        0xa3, b'n', b'i', b'l', 0xc0, // "nil": nil
+    ];
+    let result: Options = facet_msgpack::from_slice(&data).unwrap();
+    assert_eq!(
+        result,
+        Options {
+            set: Some(5),
+            nil: None,
+            absent: None,
+        }
+    );
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Required {
+        id: u8,
+    }
+    assert!(matches!(
+        facet_msgpack::from_slice::<Required>(&[0x80]),
+        Err(facet_msgpack::DecodeError::MissingField(name)) if name == "id"
+    ));
+}
+
+#[test]
+fn test_skip_unknown_fields() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Point {
+        x: u8,
+    }
+
+    let data = [
+        0x85, // Fixmap with 5 elements
+        0xa1, b'a', 0x05, // positive fixint
+        0xa1, b'b', 0xcb, 0, 0, 0, 0, 0, 0, 0, 0, // float64
+        0xa1, b'c', 0xc4, 0x02, 0x01, 0x02, // bin8
+        0xa1, b'd', 0xd6, 0x01, 0, 0, 0, 0, // fixext4
+        0xa1, b'x', 0x07, // "x": 7
+    ];
+    let result: Point = facet_msgpack::from_slice(&data).unwrap();
+    assert_eq!(result, Point { x: 7 });
+}
+
+#[test]
+fn test_deny_unknown_fields() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[facet(deny_unknown_fields)]
+    struct Point {
+        x: u8,
+    }
+
+    let data = [
+        0x82, // Fixmap with 2 elements
+        0xa1, b'x', 0x07, // "x": 7
+        0xa1, b'y', 0x08, // "y": 8
+    ];
+    let err = facet_msgpack::from_slice::<Point>(&data).unwrap_err();
+    assert!(
+        matches!(&err, facet_msgpack::DecodeError::UnknownField(field) if field == "y"),
+        "{err}"
+    );
+}
+
+#[test]
+fn test_deeply_nested_unknown_field() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Point {
+        x: u8,
+    }
+
+    // {"z": [[[...nil...]]], "x": 7}
+    let nested = |depth: usize| {
+        let mut data = vec![0x82, 0xa1, b'z'];
+        data.extend(std::iter::repeat_n(0x91, depth)); // fixarray with 1 element
+        data.extend([0xc0, 0xa1, b'x', 0x07]);
+        data
+    };
+
+    let result: Point = facet_msgpack::from_slice(&nested(100)).unwrap();
+    assert_eq!(result, Point { x: 7 });
+
+    // too deep to skip without overflowing the stack
+    let data = nested(200_000);
+    assert!(matches!(
+        facet_msgpack::from_slice::<Point>(&data),
+        Err(facet_msgpack::DecodeError::DepthLimitExceeded)
+    ));
+    let mut decoder = facet_msgpack::Decoder::new(data.as_slice());
+    assert!(matches!(
+        decoder.decode::<Point>(),
+        Err(facet_msgpack::DecodeError::DepthLimitExceeded)
+    ));
+
+    let options = facet_msgpack::MsgpackReaderOptions::new().with_max_depth(10);
+    assert!(matches!(
+        facet_msgpack::from_slice_with_options::<Point>(&nested(10), &options),
+        Err(facet_msgpack::DecodeError::DepthLimitExceeded)
+    ));
+    let result: Point = facet_msgpack::from_slice_with_options(&nested(9), &options).unwrap();
+    assert_eq!(result, Point { x: 7 });
+}
+
+#[test]
+fn test_duplicate_field() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Point {
+        a: u8,
+    }
+
+    let data = [
+        0x82, // Fixmap with 2 elements
+        0xa1, b'a', 0x01, // "a": 1
+        0xa1, b'a', 0x02, // "a": 2
+    ];
+    let err = facet_msgpack::from_slice::<Point>(&data).unwrap_err();
+    assert!(
+        matches!(&err, facet_msgpack::DecodeError::DuplicateField(field) if field == "a"),
+        "{err}"
+    );
+}
+
+#[test]
+fn test_enums() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[repr(u8)]
+    enum Shape {
+        Empty,
+        Circle(u8),
+        Line(u8, u8),
+        Rect { w: u8, h: u8 },
+    }
+
+    let data = [
+        0x94, // Fixarray with 4 elements
+        0xa5, b'E', b'm', b'p', b't', b'y', // "Empty"
+        0x81, 0xa6, b'C', b'i', b'r', b'c', b'l', b'e', 0x03, // {"Circle": 3}
+        0x81, 0xa4, b'L', b'i', b'n', b'e', 0x92, 0x01, 0x02, // {"Line": [1, 2]}
+        0x81, 0xa4, b'R', b'e', b'c', b't', // {"Rect":
+        0x82, 0xa1, b'w', 0x04, 0xa1, b'h', 0x05, // {"w": 4, "h": 5}}
+    ];
+    let result: Vec<Shape> = facet_msgpack::from_slice(&data).unwrap();
+    assert_eq!(
+        result,
+        vec![
+            Shape::Empty,
+            Shape::Circle(3),
+            Shape::Line(1, 2),
+            Shape::Rect { w: 4, h: 5 },
+        ]
+    );
+
+    assert!(matches!(
+        facet_msgpack::from_slice::<Shape>(&[0xa4, b'O', b'v', b'a', b'l']),
+        Err(facet_msgpack::DecodeError::UnknownVariant(name)) if name == "Oval"
+    ));
+}
+
+#[test]
+fn test_tagged_enums() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[repr(u8)]
+    #[facet(tag = "type")]
+    enum Internal {
+        Ping,
+        Move { x: u8 },
+    }
+
+    let data = [
+        0x82, // Fixmap with 2 elements
+        0xa1, b'x', 0x01, // "x": 1
+        0xa4, b't', b'y', b'p', b'e', 0xa4, b'M', b'o', b'v', b'e', // "type": "Move"
+    ];
+    let result: Internal = facet_msgpack::from_slice(&data).unwrap();
+    assert_eq!(result, Internal::Move { x: 1 });
+    let data = [
+        0x81, 0xa4, b't', b'y', b'p', b'e', 0xa4, b'P', b'i', b'n', b'g',
+    ];
+    let result: Internal = facet_msgpack::from_slice(&data).unwrap();
+    assert_eq!(result, Internal::Ping);
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[repr(u8)]
+    #[facet(tag = "t", content = "c")]
+    enum Adjacent {
+        Say(String),
+    }
+
+    let data = [
+        0x82, // Fixmap with 2 elements
+        0xa1, b'c', 0xa2, b'h', b'i', // "c": "hi"
+        0xa1, b't', 0xa3, b'S', b'a', b'y', // "t": "Say"
+    ];
+    let result: Adjacent = facet_msgpack::from_slice(&data).unwrap();
+    assert_eq!(result, Adjacent::Say("hi".to_string()));
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[repr(u8)]
+    #[facet(untagged)]
+    enum Untagged {
+        Flag(bool),
+        Number(u32),
+        Text(String),
+    }
+
+    let data = [0x93, 0xc3, 0x2a, 0xa1, b'a']; // [true, 42, "a"]
+    let result: Vec<Untagged> = facet_msgpack::from_slice(&data).unwrap();
+    assert_eq!(
+        result,
+        vec![
+            Untagged::Flag(true),
+            Untagged::Number(42),
+            Untagged::Text("a".to_string()),
+        ]
+    );
+    assert!(matches!(
+        facet_msgpack::from_slice::<Untagged>(&[0xc0]),
+        Err(facet_msgpack::DecodeError::NoMatchingVariant(_))
+    ));
+}
+
+#[test]
+fn test_signed_integers() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Signed {
+        neg_fixint: i8,
+        int8: i16,
+        int16: i32,
+        int32: i64,
+        int64: i64,
+        from_uint8: i32,
+    }
+
+    let data = [
+        0x86, // map with 6 elements
+        0xaa, b'n', b'e', b'g', b'_', b'f', b'i', b'x', b'i', b'n', b't', 0xe0, // -32
+        0xa4, b'i', b'n', b't', b'8', 0xd0, 0x80, // -128
+        0xa5, b'i', b'n', b't', b'1', b'6', 0xd1, 0x80, 0x00, // -32768
+        0xa5, b'i', b'n', b't', b'3', b'2', 0xd2, 0xff, 0xff, 0xff, 0xfe, // -2
+        0xa5, b'i', b'n', b't', b'6', b'4', 0xd3, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, // i64::MIN
+        0xaa, b'f', b'r', b'o', b'm', b'_', b'u', b'i', b'n', b't', b'8', 0xcc, 0xff, // 255
+    ];
+
+    let signed: Signed = facet_msgpack::from_slice(&data).unwrap();
+    assert_eq!(
+        signed,
+        Signed {
+            neg_fixint: -32,
+            int8: -128,
+            int16: -32768,
+            int32: -2,
+            int64: i64::MIN,
+            from_uint8: 255,
+        }
+    );
+
+    // Negative values don't fit unsigned targets
+    assert!(facet_msgpack::from_slice::<u64>(&[0xff]).is_err());
+    assert!(facet_msgpack::from_slice::<i8>(&[0xd1, 0xff, 0x00]).is_err());
+}
+
+#[test]
+fn test_floats_and_bin_roundtrip() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Reading {
+        celsius: f64,
+        ratio: f32,
+        offset: i32,
+        raw: Vec<u8>,
+    }
+
+    let reading = Reading {
+        celsius: -12.5,
+        ratio: 0.25,
+        offset: -70000,
+        raw: vec![0, 1, 254, 255],
+    };
+
+    let bytes = facet_msgpack::to_vec(&reading);
+    assert!(bytes.ends_with(&[0xa3, b'r', b'a', b'w', 0xc4, 4, 0, 1, 254, 255]));
+    assert_eq!(
+        facet_msgpack::from_slice::<Reading>(&bytes).unwrap(),
+        reading
+    );
+
+    // float32 widens into f64
+    let f: f64 = facet_msgpack::from_slice(&[0xca, 0x3e, 0x80, 0x00, 0x00]).unwrap();
+    assert_eq!(f, 0.25);
+}
+
+#[test]
+fn test_ext_handler() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Point {
+        x: i16,
+        y: i16,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Shape {
+        origin: Point,
+        corner: Option<Point>,
+    }
+
+    struct PointExt;
+
+    impl facet_msgpack::ExtHandler for PointExt {
+        fn ext_type(&self) -> i8 {
+            42
+        }
+
+        fn decode<'facet>(
+            &self,
+            data: &[u8],
+            wip: facet_reflect::Wip<'facet>,
+        ) -> Result<facet_reflect::Wip<'facet>, facet_msgpack::DecodeError> {
+            let [x0, x1, y0, y1] = data else {
+                return Err(facet_msgpack::DecodeError::InvalidData);
+            };
+            wip.put(Point {
+                x: i16::from_be_bytes([*x0, *x1]),
+                y: i16::from_be_bytes([*y0, *y1]),
+            })
+            .map_err(|e| facet_msgpack::DecodeError::UnsupportedType(e.to_string()))
+        }
+    }
+
+    let data = [
+        0x82, // map with 2 elements
+        0xa6, b'o', b'r', b'i', b'g', b'i', b'n', 0xd6, 42, 0x00, 0x01, 0xff, 0xfe, // fixext4
+        0xa6, b'c', b'o', b'r', b'n', b'e', b'r', 0xc7, 4, 42, 0x00, 0x03, 0x00, 0x04, // ext8
+    ];
+
+    let options = facet_msgpack::MsgpackReaderOptions::new().with_ext_handler(PointExt);
+    let shape: Shape = facet_msgpack::from_slice_with_options(&data, &options).unwrap();
+    assert_eq!(
+        shape,
+        Shape {
+            origin: Point { x: 1, y: -2 },
+            corner: Some(Point { x: 3, y: 4 }),
+        }
+    );
+
+    assert!(matches!(
+        facet_msgpack::from_slice::<Shape>(&data),
+        Err(facet_msgpack::DecodeError::UnknownExtType(42))
+    ));
+}
@@ -1,5 +1,5 @@
 use facet::Facet;
-use facet_msgpack::to_vec;
+use facet_msgpack::{MsgpackWriterOptions, to_vec, to_vec_with_options};
 
 #[test]
 fn test_integers() {
@@ -120,3 +120,193 @@ fn test_struct() {
 
     assert_eq!(msgpack, expected);
 }
+
+#[test]
+fn test_struct_as_array() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Quote {
+        symbol: String,
+        bid: u32,
+        ask: Option<u32>,
+        venue: Option<String>,
+    }
+
+    let quote = Quote {
+        symbol: "ACME".to_string(),
+        bid: 100,
+        ask: None,
+        venue: None,
+    };
+
+    let options = facet_msgpack::MsgpackWriterOptions::new().with_struct_as_array(true);
+    let msgpack = facet_msgpack::to_vec_with_options(&quote, &options).unwrap();
+    assert_eq!(
+        msgpack,
+        [0x94, 0xa4, b'A', b'C', b'M', b'E', 0x64, 0xc0, 0xc0]
+    );
+    assert_eq!(facet_msgpack::from_slice::<Quote>(&msgpack).unwrap(), quote);
+
+    let options = options.with_skip_trailing_none(true);
+    let msgpack = facet_msgpack::to_vec_with_options(&quote, &options).unwrap();
+    assert_eq!(msgpack, [0x92, 0xa4, b'A', b'C', b'M', b'E', 0x64]);
+    assert_eq!(facet_msgpack::from_slice::<Quote>(&msgpack).unwrap(), quote);
+
+    // only trailing `None`s are left out
+    let quote = Quote {
+        venue: Some("X".to_string()),
+        ..quote
+    };
+    let msgpack = facet_msgpack::to_vec_with_options(&quote, &options).unwrap();
+    assert_eq!(
+        msgpack,
+        [0x94, 0xa4, b'A', b'C', b'M', b'E', 0x64, 0xc0, 0xa1, b'X']
+    );
+    assert_eq!(facet_msgpack::from_slice::<Quote>(&msgpack).unwrap(), quote);
+
+    // required fields can't be left out, and extra values are rejected
+    assert!(matches!(
+        facet_msgpack::from_slice::<Quote>(&[0x91, 0xa1, b'A']),
+        Err(facet_msgpack::DecodeError::MissingField(field)) if field == "bid"
+    ));
+    assert!(matches!(
+        facet_msgpack::from_slice::<Quote>(&[0x95, 0xa1, b'A', 1, 2, 3, 4]),
+        Err(facet_msgpack::DecodeError::LengthMismatch {
+            expected: 4,
+            got: 5
+        })
+    ));
+}
+
+#[test]
+fn test_as_array_attribute() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[facet(as_array)]
+    struct Tick {
+        price: i64,
+        size: u32,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Named {
+        name: String,
+        tick: Tick,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[facet(as_array)]
+    #[repr(u8)]
+    enum Event {
+        Trade { price: i64, size: u32 },
+        Halt,
+    }
+
+    let named = Named {
+        name: "a".to_string(),
+        tick: Tick {
+            price: -5,
+            size: 10,
+        },
+    };
+    let msgpack = facet_msgpack::to_vec(&named);
+    assert_eq!(
+        msgpack,
+        [
+            0x82, 0xa4, b'n', b'a', b'm', b'e', 0xa1, b'a', // name
+            0xa4, b't', b'i', b'c', b'k', 0x92, 0xfb, 0x0a, // tick: [-5, 10]
+        ]
+    );
+    assert_eq!(facet_msgpack::from_slice::<Named>(&msgpack).unwrap(), named);
+
+    let trade = Event::Trade {
+        price: 7,
+        size: 300,
+    };
+    let msgpack = facet_msgpack::to_vec(&trade);
+    assert_eq!(
+        msgpack,
+        [
+            0x81, 0xa5, b'T', b'r', b'a', b'd', b'e', 0x92, 0x07, 0xcd, 0x01, 0x2c
+        ]
+    );
+    assert_eq!(facet_msgpack::from_slice::<Event>(&msgpack).unwrap(), trade);
+
+    let msgpack = facet_msgpack::to_vec(&Event::Halt);
+    assert_eq!(msgpack, [0xa4, b'H', b'a', b'l', b't']);
+    assert_eq!(
+        facet_msgpack::from_slice::<Event>(&msgpack).unwrap(),
+        Event::Halt
+    );
+}
+
+#[test]
+fn test_roundtrip() {
+    facet_testhelpers::setup();
+
+    use std::collections::HashMap;
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[facet(tag = "kind")]
+    #[repr(u8)]
+    enum Shape {
+        Circle { radius: f64 },
+        Empty,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Everything {
+        flag: bool,
+        letter: char,
+        pair: (u8, i16),
+        items: Vec<String>,
+        lookup: HashMap<String, u32>,
+        maybe: Option<i32>,
+        shapes: Vec<Shape>,
+    }
+
+    let everything = Everything {
+        flag: true,
+        letter: 'λ',
+        pair: (1, -300),
+        items: vec!["x".to_string(), "y".to_string()],
+        lookup: HashMap::from([("k".to_string(), 9)]),
+        maybe: None,
+        shapes: vec![Shape::Circle { radius: 1.5 }, Shape::Empty],
+    };
+
+    let msgpack = facet_msgpack::to_vec(&everything);
+    assert_eq!(
+        facet_msgpack::from_slice::<Everything>(&msgpack).unwrap(),
+        everything
+    );
+
+    let options = facet_msgpack::MsgpackWriterOptions::new().with_struct_as_array(true);
+    let compact = facet_msgpack::to_vec_with_options(&everything, &options).unwrap();
+    assert!(compact.len() < msgpack.len());
+    assert_eq!(
+        facet_msgpack::from_slice::<Everything>(&compact).unwrap(),
+        everything
+    );
+}
+
+#[test]
+fn test_unencodable_values() {
+    facet_testhelpers::setup();
+
+    let options = MsgpackWriterOptions::default();
+    assert!(to_vec_with_options(&(u64::MAX as u128), &options).is_ok());
+    assert!(to_vec_with_options(&(u64::MAX as u128 + 1), &options).is_err());
+
+    #[derive(Debug, Facet)]
+    #[facet(tag = "kind")]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Message {
+        Pair(u32, u32),
+    }
+
+    assert!(to_vec_with_options(&Message::Pair(1, 2), &options).is_err());
+}
@@ -0,0 +1,126 @@
+use std::io::{self, BufReader, Read};
+
+use facet::Facet;
+use facet_msgpack::{DecodeError, Decoder, to_vec, to_writer};
+
+/// A reader handing out at most one byte per read, like a slow socket
+struct Trickle<R>(R);
+
+impl<R: Read> Read for Trickle<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = buf.len().min(1);
+        self.0.read(&mut buf[..len])
+    }
+}
+
+#[test]
+fn test_to_writer() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Request {
+        params: Vec<i64>,
+        trace: Option<String>,
+        blob: Vec<u8>,
+    }
+
+    let requests = [
+        Request {
+            params: vec![],
+            trace: None,
+            blob: vec![],
+        },
+        Request {
+            params: vec![-1, 300, i64::MAX],
+            trace: Some("x".repeat(40)),
+            blob: vec![7; 300],
+        },
+    ];
+    for request in &requests {
+        let mut written = Vec::new();
+        to_writer(request, &mut written).unwrap();
+        assert_eq!(written, to_vec(request));
+    }
+}
+
+#[test]
+fn test_decode_concatenated() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Request {
+        id: u32,
+        method: String,
+    }
+
+    let requests: Vec<_> = (1..=3)
+        .map(|id| Request {
+            id,
+            method: "x".repeat(40 * id as usize),
+        })
+        .collect();
+    let mut input = Vec::new();
+    for request in &requests {
+        to_writer(request, &mut input).unwrap();
+    }
+
+    let mut decoder = Decoder::new(BufReader::new(Trickle(input.as_slice())));
+    for request in requests {
+        assert_eq!(decoder.decode::<Request>().unwrap(), Some(request));
+    }
+    assert_eq!(decoder.decode::<Request>().unwrap(), None);
+}
+
+#[test]
+fn test_decode_reads_one_value_at_a_time() {
+    facet_testhelpers::setup();
+
+    let mut input = to_vec(&vec![1u32, 300, u32::MAX]);
+    input.extend_from_slice(b"trailing");
+
+    // the decoder stops right after the value
+    let mut decoder = Decoder::new(input.as_slice());
+    assert_eq!(
+        decoder.decode::<Vec<u32>>().unwrap(),
+        Some(vec![1, 300, u32::MAX])
+    );
+    assert_eq!(decoder.into_inner(), b"trailing");
+}
+
+#[test]
+fn test_decode_errors() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Request {
+        id: u32,
+        method: String,
+    }
+
+    let request = Request {
+        id: 1,
+        method: "ping".to_string(),
+    };
+    let input = to_vec(&request);
+
+    // input ending in the middle of a value
+    let mut decoder = Decoder::new(&input[..input.len() - 1]);
+    assert!(matches!(
+        decoder.decode::<Request>(),
+        Err(DecodeError::InsufficientData)
+    ));
+
+    // a value of the wrong type is skipped over
+    let mut input = to_vec(&"not a request".to_string());
+    input.extend(to_vec(&request));
+    let mut decoder = Decoder::new(input.as_slice());
+    assert!(decoder.decode::<Request>().is_err());
+    assert_eq!(decoder.decode::<Request>().unwrap(), Some(request));
+
+    // 0xc1 is never used
+    let mut decoder = Decoder::new(&[0xc1][..]);
+    assert!(matches!(
+        decoder.decode::<u8>(),
+        Err(DecodeError::InvalidData)
+    ));
+}
@@ -0,0 +1,163 @@
+use std::{alloc::Layout, fmt, str::FromStr};
+
+use facet::{ConstTypeId, Def, Facet, ScalarAffinity, ScalarDef, Shape, value_vtable};
+use facet_msgpack::{DecodeError, from_slice, to_vec};
+
+/// Declares a datetime scalar, like the ones of datetime crates: it parses and
+/// displays RFC 3339 text and has the given time affinity
+macro_rules! time_scalar {
+    ($name:ident, $affinity:expr) => {
+        #[derive(Debug, Clone, PartialEq)]
+        struct $name(String);
+
+        impl FromStr for $name {
+            type Err = &'static str;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                if s.starts_with(|c: char| c.is_ascii_digit()) {
+                    Ok($name(s.to_string()))
+                } else {
+                    Err(concat!("not a ", stringify!($name)))
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        unsafe impl Facet<'_> for $name {
+            const SHAPE: &'static Shape = &const {
+                Shape::builder()
+                    .id(ConstTypeId::of::<Self>())
+                    .layout(Layout::new::<Self>())
+                    .def(Def::Scalar(
+                        ScalarDef::builder().affinity($affinity).build(),
+                    ))
+                    .vtable(value_vtable!($name, |f, _opts| write!(
+                        f,
+                        stringify!($name)
+                    )))
+                    .build()
+            };
+        }
+    };
+}
+
+// an absolute instant, like `2025-03-01T02:00:00Z`
+time_scalar!(Timestamp, ScalarAffinity::time().absolute().build());
+
+// a local date, time or datetime, which doesn't name a single instant
+time_scalar!(LocalDatetime, ScalarAffinity::time().build());
+
+fn timestamp(s: &str) -> Timestamp {
+    Timestamp(s.to_string())
+}
+
+#[test]
+fn test_decode_timestamp_forms() {
+    facet_testhelpers::setup();
+
+    // timestamp 32
+    let t: Timestamp = from_slice(&[0xd6, 0xff, 0x67, 0xc2, 0x6a, 0x20]).unwrap();
+    assert_eq!(t, timestamp("2025-03-01T02:00:00Z"));
+
+    // timestamp 64
+    let t: Timestamp =
+        from_slice(&[0xd7, 0xff, 0x77, 0x35, 0x94, 0x00, 0x67, 0xc2, 0x6a, 0x20]).unwrap();
+    assert_eq!(t, timestamp("2025-03-01T02:00:00.5Z"));
+
+    // timestamp 96
+    let t: Timestamp = from_slice(&[
+        0xc7, 12, 0xff, 0x00, 0x00, 0x00, 0x01, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    ])
+    .unwrap();
+    assert_eq!(t, timestamp("1969-12-31T23:59:59.000000001Z"));
+
+    // nanoseconds past a second are invalid
+    assert!(matches!(
+        from_slice::<Timestamp>(&[
+            0xc7, 12, 0xff, 0x3b, 0x9a, 0xca, 0x00, 0, 0, 0, 0, 0, 0, 0, 0
+        ]),
+        Err(DecodeError::InvalidData)
+    ));
+
+    // only time scalars are read from timestamps
+    assert!(matches!(
+        from_slice::<u32>(&[0xd6, 0xff, 0x67, 0xc2, 0x6a, 0x20]),
+        Err(DecodeError::UnknownExtType(-1))
+    ));
+}
+
+#[test]
+fn test_encode_timestamp_forms() {
+    facet_testhelpers::setup();
+
+    assert_eq!(
+        to_vec(&timestamp("2025-03-01T02:00:00Z")),
+        [0xd6, 0xff, 0x67, 0xc2, 0x6a, 0x20]
+    );
+    // offsets are folded into the time
+    assert_eq!(
+        to_vec(&timestamp("2025-03-01T03:30:00+01:30")),
+        [0xd6, 0xff, 0x67, 0xc2, 0x6a, 0x20]
+    );
+    assert_eq!(
+        to_vec(&timestamp("2025-03-01T02:00:00.5Z")),
+        [0xd7, 0xff, 0x77, 0x35, 0x94, 0x00, 0x67, 0xc2, 0x6a, 0x20]
+    );
+    // past 2106, seconds need the 64-bit form
+    assert_eq!(
+        to_vec(&timestamp("2500-01-01T00:00:00Z")),
+        [0xd7, 0xff, 0x00, 0x00, 0x00, 0x03, 0xe4, 0xe6, 0xb0, 0x80]
+    );
+    assert_eq!(
+        to_vec(&timestamp("1969-12-31T23:59:59.000000001Z")),
+        [
+            0xc7, 12, 0xff, 0x00, 0x00, 0x00, 0x01, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff
+        ]
+    );
+}
+
+#[test]
+fn test_roundtrip() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Window {
+        starts: Timestamp,
+        ends: Option<Timestamp>,
+        at: LocalDatetime,
+    }
+
+    let window = Window {
+        starts: timestamp("1979-05-27T07:32:00.999999Z"),
+        ends: Some(timestamp("2038-01-19T03:14:08Z")),
+        // not an instant, so written as a string
+        at: LocalDatetime("02:30:00".to_string()),
+    };
+
+    let msgpack = to_vec(&window);
+    assert!(msgpack.ends_with(&[0xa8, b'0', b'2', b':', b'3', b'0', b':', b'0', b'0']));
+    assert_eq!(from_slice::<Window>(&msgpack).unwrap(), window);
+}
+
+#[test]
+fn test_encode_as_string() {
+    facet_testhelpers::setup();
+
+    // local datetimes are strings, even when they look like RFC 3339
+    let local = LocalDatetime("2025-03-01T02:00:00Z".to_string());
+    let mut expected = vec![0xb4];
+    expected.extend_from_slice(b"2025-03-01T02:00:00Z");
+    assert_eq!(to_vec(&local), expected);
+    assert_eq!(from_slice::<LocalDatetime>(&expected).unwrap(), local);
+
+    // and so are instants that don't display as RFC 3339
+    let odd = timestamp("2025-03-01T02:00:0\u{e9}Z");
+    let mut expected = vec![0xb5];
+    expected.extend_from_slice(odd.0.as_bytes());
+    assert_eq!(to_vec(&odd), expected);
+}
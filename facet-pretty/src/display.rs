@@ -6,19 +6,19 @@ use crate::printer::PrettyPrinter;
 use facet_core::Facet;
 
 /// Display wrapper for any type that implements Facet
-pub struct PrettyDisplay<'a, T: Facet> {
+pub struct PrettyDisplay<'a, T> {
     pub(crate) value: &'a T,
     pub(crate) printer: PrettyPrinter,
 }
 
-impl<T: Facet> Display for PrettyDisplay<'_, T> {
+impl<'f, T: Facet<'f>> Display for PrettyDisplay<'_, T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         self.printer.format_to(self.value, f)
     }
 }
 
 /// Extension trait for Facet types to easily pretty-print them
-pub trait FacetPretty: Facet {
+pub trait FacetPretty<'f>: Facet<'f> {
     /// Get a displayable wrapper that pretty-prints this value
     fn pretty(&self) -> PrettyDisplay<'_, Self>;
 
@@ -26,7 +26,7 @@ pub trait FacetPretty: Facet {
     fn pretty_with(&self, printer: PrettyPrinter) -> PrettyDisplay<'_, Self>;
 }
 
-impl<T: Facet> FacetPretty for T {
+impl<'f, T: Facet<'f>> FacetPretty<'f> for T {
     fn pretty(&self) -> PrettyDisplay<'_, Self> {
         PrettyDisplay {
             value: self,
@@ -85,7 +85,7 @@ impl PrettyPrinter {
     }
 
     /// Format a value to a string
-    pub fn format<T: Facet>(&self, value: &T) -> String {
+    pub fn format<'a, T: Facet<'a>>(&self, value: &T) -> String {
         let value = Peek::new(value);
 
         let mut output = String::new();
@@ -96,7 +96,11 @@ impl PrettyPrinter {
     }
 
     /// Format a value to a formatter
-    pub fn format_to<T: Facet>(&self, value: &T, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    pub fn format_to<'a, T: Facet<'a>>(
+        &self,
+        value: &T,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
         let value = Peek::new(value);
         self.format_peek_internal(value, f, &mut HashMap::new())
     }
@@ -0,0 +1,183 @@
+//! Building blocks for the errors of format crates: the path of the value
+//! being deserialized, and the snippet of input that points at the error.
+
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use core::fmt::{self, Formatter, Write as _};
+use core::ops::Range;
+use facet_ansi::{ColorStyle as _, Style, Stylize as _};
+
+/// How many characters of context to show on each side of the error, for long lines
+const SNIPPET_CONTEXT: usize = 40;
+
+/// One step of the path to the value being deserialized
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment<'a> {
+    /// A struct field, or the payload of an enum variant
+    Field(&'static str),
+    /// An element of a list, tuple or array
+    Index(usize),
+    /// The value for a key of a map
+    Key(Cow<'a, str>),
+}
+
+/// Renders a path like `config.servers[2].port`, from its outermost segment
+/// to its innermost one
+pub fn format_path<'p, 'a: 'p>(segments: impl IntoIterator<Item = &'p PathSegment<'a>>) -> String {
+    let mut path = String::new();
+    for segment in segments {
+        match segment {
+            PathSegment::Field(name) => push_name(&mut path, name),
+            PathSegment::Key(key) => push_name(&mut path, key),
+            PathSegment::Index(index) => {
+                let _ = write!(path, "[{}]", index);
+            }
+        }
+    }
+    path
+}
+
+fn push_name(path: &mut String, name: &str) {
+    if !path.is_empty() {
+        path.push('.');
+    }
+    path.push_str(name);
+}
+
+/// Where an error is in the input, and the part of its line to show, with a
+/// caret underline below the error span.
+///
+/// Only a window around the error is kept, so long (e.g. minified) lines stay
+/// readable, and the snippet doesn't hold on to the input.
+#[derive(Debug, Clone)]
+pub struct Snippet {
+    pos: usize,
+    line: usize,
+    column: usize,
+    /// the shown part of the line, with tabs as spaces and `…` where it's cut
+    shown: String,
+    /// how many characters of `shown` come before the caret underline
+    caret_offset: usize,
+    /// how many characters to underline
+    carets: usize,
+}
+
+impl Snippet {
+    /// Locates the given byte span of the input. The input may be invalid
+    /// UTF-8, and the span is cut at the end of its first line.
+    pub fn new(input: &[u8], span: Range<usize>) -> Self {
+        let pos = span.start.min(input.len());
+        let line_start = input[..pos]
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map_or(0, |i| i + 1);
+        let mut line_end = input[pos..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(input.len(), |i| pos + i);
+        if line_end > line_start && input[line_end - 1] == b'\r' {
+            line_end -= 1;
+        }
+        let line = 1 + input[..pos].iter().filter(|&&b| b == b'\n').count();
+        let column = 1 + char_count(&input[line_start..pos]);
+
+        let text = String::from_utf8_lossy(&input[line_start..line_end]);
+
+        let col = column - 1;
+        let skip = col.saturating_sub(SNIPPET_CONTEXT);
+        let total = text.chars().count();
+        let take = SNIPPET_CONTEXT * 2;
+        let mut shown = String::new();
+        if skip > 0 {
+            shown.push('…');
+        }
+        shown.extend(
+            text.chars()
+                .skip(skip)
+                .take(take)
+                .map(|c| if c == '\t' { ' ' } else { c }),
+        );
+        if skip + take < total {
+            shown.push('…');
+        }
+
+        let span_end = span.end.min(line_end).max(pos);
+        let carets =
+            char_count(&input[pos..span_end]).clamp(1, (take + 1).saturating_sub(col - skip));
+
+        Self {
+            pos,
+            line,
+            column,
+            shown,
+            caret_offset: col - skip + usize::from(skip > 0),
+            carets,
+        }
+    }
+
+    /// Returns the byte offset in the input at which the error occurred
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns the 1-based line at which the error occurred
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the 1-based column (in characters) at which the error occurred
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Writes the location, then the line with a caret underline, followed by
+    /// `label` if any:
+    ///
+    /// ```text
+    ///  --> line 4, column 11 (byte 53)
+    ///   |
+    /// 4 |     port: 80000
+    ///   |           ^^^^^
+    /// ```
+    pub fn write(&self, f: &mut Formatter<'_>, label: Option<&str>, colors: bool) -> fmt::Result {
+        let style = |style: Style| if colors { style } else { Style::new() };
+        let gutter = style(Style::new().fg_blue().fg_bold());
+        let caret = style(Style::new().fg_red().fg_bold());
+        let number = self.line.to_string();
+        let pad = " ".repeat(number.len());
+
+        writeln!(
+            f,
+            " --> line {}, column {} (byte {})",
+            self.line, self.column, self.pos
+        )?;
+        writeln!(f, "{} {}", pad, "|".style(gutter))?;
+        writeln!(
+            f,
+            "{} {} {}",
+            number.style(gutter),
+            "|".style(gutter),
+            self.shown
+        )?;
+        write!(
+            f,
+            "{} {} {}{}",
+            pad,
+            "|".style(gutter),
+            " ".repeat(self.caret_offset),
+            "^".repeat(self.carets).style(caret)
+        )?;
+        if let Some(label) = label {
+            write!(f, " {}", label.style(caret))?;
+        }
+        Ok(())
+    }
+}
+
+/// Counts the characters in a (possibly invalid) UTF-8 byte slice
+fn char_count(bytes: &[u8]) -> usize {
+    bytes
+        .utf8_chunks()
+        .map(|chunk| chunk.valid().chars().count() + chunk.invalid().len().min(1))
+        .sum()
+}
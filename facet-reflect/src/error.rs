@@ -52,6 +52,14 @@ pub enum ReflectError {
         variant_name: &'static str,
     },
 
+    /// An element of an array was not initialized during build
+    UninitializedArrayElement {
+        /// The array shape
+        shape: &'static Shape,
+        /// The index of the element that wasn't initialized
+        index: usize,
+    },
+
     /// An enum had no variant selected during build
     NoVariantSelected {
         /// The enum shape
@@ -135,6 +143,9 @@ impl core::fmt::Display for ReflectError {
                     shape, field_name, variant_name
                 )
             }
+            ReflectError::UninitializedArrayElement { shape, index } => {
+                write!(f, "Element {} of '{}' was not initialized", index, shape)
+            }
             ReflectError::NoVariantSelected { shape } => {
                 write!(f, "Enum '{}' had no variant selected", shape)
             }
@@ -20,3 +20,8 @@ pub use peek::*;
 
 mod scalar;
 pub use scalar::*;
+
+#[cfg(feature = "alloc")]
+mod diagnostic;
+#[cfg(feature = "alloc")]
+pub use diagnostic::*;
@@ -0,0 +1,70 @@
+use super::Peek;
+use facet_core::ArrayDef;
+
+/// Iterator over a `PeekArray`
+pub struct PeekArrayIter<'mem> {
+    array: PeekArray<'mem>,
+    index: usize,
+}
+
+impl<'mem> Iterator for PeekArrayIter<'mem> {
+    type Item = Peek<'mem>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.array.get(self.index)?;
+        self.index += 1;
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.array.len().saturating_sub(self.index);
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for PeekArrayIter<'_> {}
+
+/// Lets you read from a fixed-size array (implements read-only [`facet_core::ArrayVTable`] proxies)
+#[derive(Clone, Copy)]
+pub struct PeekArray<'mem> {
+    pub(crate) value: Peek<'mem>,
+    pub(crate) def: ArrayDef,
+}
+
+impl<'mem> PeekArray<'mem> {
+    /// Get the length of the array
+    pub fn len(&self) -> usize {
+        self.def.n
+    }
+
+    /// Returns true if the array is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get an item from the array at the specified index, or `None` if out of bounds
+    pub fn get(&self, index: usize) -> Option<Peek<'mem>> {
+        if index >= self.len() {
+            return None;
+        }
+
+        let item_ptr = unsafe { (self.def.vtable.get_item_ptr)(self.value.data(), index) };
+        Some(Peek {
+            data: item_ptr,
+            shape: self.def.t(),
+        })
+    }
+
+    /// Returns an iterator over the array
+    pub fn iter(self) -> PeekArrayIter<'mem> {
+        PeekArrayIter {
+            array: self,
+            index: 0,
+        }
+    }
+
+    /// Def getter
+    pub fn def(&self) -> ArrayDef {
+        self.def
+    }
+}
@@ -1,4 +1,4 @@
-use facet_core::{EnumDef, EnumRepr, Shape, Variant};
+use facet_core::{EnumDef, EnumRepr, Shape, TaggedFields, Variant};
 
 /// Lets you read from an enum (implements read-only enum operations)
 #[derive(Clone, Copy)]
@@ -173,4 +173,26 @@ impl<'mem> PeekEnum<'mem> {
             (field, peek)
         })
     }
+
+    /// Returns the fields written next to the tag when the enum is internally
+    /// tagged (see [`Variant::internally_tagged_fields`]), or `None` if the
+    /// active variant can't be
+    pub fn internally_tagged_fields(
+        self,
+    ) -> Option<impl Iterator<Item = (&'static facet_core::Field, crate::Peek<'mem>)>> {
+        let (data, fields) = match self.active_variant().internally_tagged_fields()? {
+            TaggedFields::Unit => (self.value.data(), &[][..]),
+            TaggedFields::Struct(fields) => (self.value.data(), fields),
+            TaggedFields::Newtype(fields) => (self.field(0)?.data(), fields),
+            _ => return None,
+        };
+        Some(fields.iter().map(move |field| {
+            let field_data = unsafe { data.field(field.offset) };
+            let peek = crate::Peek {
+                data: field_data,
+                shape: field.shape(),
+            };
+            (field, peek)
+        }))
+    }
 }
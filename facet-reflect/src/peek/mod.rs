@@ -15,6 +15,12 @@ pub use list::*;
 mod map;
 pub use map::*;
 
+mod array;
+pub use array::*;
+
+mod slice;
+pub use slice::*;
+
 mod option;
 pub use option::*;
 
@@ -0,0 +1,75 @@
+use super::Peek;
+use facet_core::SliceDef;
+
+/// Iterator over a `PeekSlice`
+pub struct PeekSliceIter<'mem> {
+    slice: PeekSlice<'mem>,
+    index: usize,
+    len: usize,
+}
+
+impl<'mem> Iterator for PeekSliceIter<'mem> {
+    type Item = Peek<'mem>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.len {
+            return None;
+        }
+        let item = self.slice.get(self.index);
+        self.index += 1;
+        item
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len.saturating_sub(self.index);
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for PeekSliceIter<'_> {}
+
+/// Lets you read from a slice (implements read-only [`facet_core::SliceVTable`] proxies)
+#[derive(Clone, Copy)]
+pub struct PeekSlice<'mem> {
+    pub(crate) value: Peek<'mem>,
+    pub(crate) def: SliceDef,
+}
+
+impl<'mem> PeekSlice<'mem> {
+    /// Get the length of the slice
+    pub fn len(&self) -> usize {
+        unsafe { (self.def.vtable.len)(self.value.data()) }
+    }
+
+    /// Returns true if the slice is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get an item from the slice at the specified index, or `None` if out of bounds
+    pub fn get(&self, index: usize) -> Option<Peek<'mem>> {
+        if index >= self.len() {
+            return None;
+        }
+
+        let item_ptr = unsafe { (self.def.vtable.get_item_ptr)(self.value.data(), index) };
+        Some(Peek {
+            data: item_ptr,
+            shape: self.def.t(),
+        })
+    }
+
+    /// Returns an iterator over the slice
+    pub fn iter(self) -> PeekSliceIter<'mem> {
+        PeekSliceIter {
+            slice: self,
+            index: 0,
+            len: self.len(),
+        }
+    }
+
+    /// Def getter
+    pub fn def(&self) -> SliceDef {
+        self.def
+    }
+}
@@ -7,16 +7,26 @@ use super::Peek;
 /// This struct holds the value being pointed to and the definition of the smart pointer type.
 pub struct PeekSmartPointer<'mem> {
     /// The value being pointed to by this smart pointer.
-    #[expect(dead_code)]
     pub(crate) value: Peek<'mem>,
 
     /// The definition of this smart pointer type.
     pub(crate) def: SmartPointerDef,
 }
 
-impl PeekSmartPointer<'_> {
+impl<'mem> PeekSmartPointer<'mem> {
     /// Returns a reference to the smart pointer definition.
     pub fn def(&self) -> &SmartPointerDef {
         &self.def
     }
+
+    /// Borrows the value the smart pointer points to.
+    ///
+    /// Returns `None` if the pointee is opaque, or if the smart pointer can't be
+    /// borrowed from directly (e.g. weak pointers, which must be upgraded first).
+    pub fn borrow_inner(&self) -> Option<Peek<'mem>> {
+        let shape = self.def.pointee?;
+        let borrow_fn = self.def.vtable.borrow_fn?;
+        let data = unsafe { borrow_fn(self.value.data()) };
+        Some(Peek { data, shape })
+    }
 }
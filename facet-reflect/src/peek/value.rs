@@ -3,7 +3,7 @@ use facet_core::{Def, Facet, Opaque, OpaqueConst, Shape, TypeNameOpts, ValueVTab
 
 use crate::{ReflectError, ScalarType};
 
-use super::{PeekEnum, PeekList, PeekMap, PeekSmartPointer, PeekStruct};
+use super::{PeekArray, PeekEnum, PeekList, PeekMap, PeekSlice, PeekSmartPointer, PeekStruct};
 
 /// A unique identifier for a peek value
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
@@ -42,7 +42,7 @@ pub struct Peek<'mem> {
 
 impl<'mem> Peek<'mem> {
     /// Creates a new `PeekValue` instance for a value of type `T`.
-    pub fn new<T: Facet + 'mem>(t: &'mem T) -> Self {
+    pub fn new<'a, T: Facet<'a>>(t: &'mem T) -> Self {
         Self {
             data: OpaqueConst::new(t as *const T),
             shape: T::SHAPE,
@@ -168,7 +168,7 @@ impl<'mem> Peek<'mem> {
     /// # Panics
     ///
     /// Panics if the shape doesn't match the type `T`.
-    pub fn get<T: Facet>(&self) -> Result<&T, ReflectError> {
+    pub fn get<'a, T: Facet<'a>>(&self) -> Result<&T, ReflectError> {
         if self.shape != T::SHAPE {
             Err(ReflectError::WrongShape {
                 expected: self.shape,
@@ -227,6 +227,30 @@ impl<'mem> Peek<'mem> {
         }
     }
 
+    /// Tries to identify this value as a fixed-size array
+    pub fn into_array(self) -> Result<PeekArray<'mem>, ReflectError> {
+        if let Def::Array(def) = self.shape.def {
+            Ok(PeekArray { value: self, def })
+        } else {
+            Err(ReflectError::WasNotA {
+                expected: "array",
+                actual: self.shape,
+            })
+        }
+    }
+
+    /// Tries to identify this value as a slice
+    pub fn into_slice(self) -> Result<PeekSlice<'mem>, ReflectError> {
+        if let Def::Slice(def) = self.shape.def {
+            Ok(PeekSlice { value: self, def })
+        } else {
+            Err(ReflectError::WasNotA {
+                expected: "slice",
+                actual: self.shape,
+            })
+        }
+    }
+
     /// Tries to identify this value as a smart pointer
     pub fn into_smart_pointer(self) -> Result<PeekSmartPointer<'mem>, ReflectError> {
         if let Def::SmartPointer(def) = self.shape.def {
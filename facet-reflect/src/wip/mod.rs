@@ -13,6 +13,7 @@ fn def_kind(def: &Def) -> &'static str {
         Def::Struct(_) => "struct",
         Def::Map(_) => "map",
         Def::List(_) => "list",
+        Def::Array(_) => "array",
         Def::Enum(_) => "enum",
         Def::Option(_) => "option",
         Def::SmartPointer(_) => "smart_ptr",
@@ -70,6 +71,7 @@ impl Frame {
     fn is_fully_initialized(&self) -> bool {
         match self.shape.def {
             Def::Struct(sd) => self.istate.fields.are_all_set(sd.fields.len()),
+            Def::Array(ad) => ad.n <= ISet::MAX_INDEX + 1 && self.istate.fields.are_all_set(ad.n),
             Def::Enum(_) => match self.istate.variant.as_ref() {
                 None => false,
                 Some(v) => self.istate.fields.are_all_set(v.data.fields.len()),
@@ -84,6 +86,11 @@ impl Frame {
             Def::Struct(sd) => {
                 self.istate.fields = ISet::all(sd.fields);
             }
+            Def::Array(ad) => {
+                for index in 0..ad.n.min(ISet::MAX_INDEX + 1) {
+                    self.istate.fields.set(index);
+                }
+            }
             Def::Enum(_) => {
                 if let Some(variant) = &self.istate.variant {
                     self.istate.fields = ISet::all(variant.data.fields);
@@ -134,6 +141,8 @@ pub enum FrameMode {
     MapKey,
     /// Frame represents a map value with the given key frame index
     MapValue(usize),
+    /// Frame represents the inner value of an `Option::Some`
+    OptionSome,
 }
 
 /// A work-in-progress heap-allocated value
@@ -147,8 +156,9 @@ pub struct Wip<'a> {
     /// keeps track of initialization of out-of-tree frames
     istates: FlatMap<ValueId, IState>,
 
-    /// lifetime of the shortest reference we hold
-    phantom: PhantomData<&'a ()>,
+    /// lifetime of the data we borrow from. Invariant, so that a value borrowing for
+    /// `'a` can't be put into a frame expecting a longer-lived borrow.
+    phantom: PhantomData<fn(&'a ()) -> &'a ()>,
 }
 
 impl<'a> Wip<'a> {
@@ -178,10 +188,32 @@ impl<'a> Wip<'a> {
     }
 
     /// Allocates a new value of type `S`
-    pub fn alloc<S: Facet>() -> Self {
+    pub fn alloc<S: Facet<'a>>() -> Self {
         Self::alloc_shape(S::SHAPE)
     }
 
+    /// For untagged enums: returns the first variant of `shape`, in
+    /// declaration order, whose payload `deserialize` reads and builds. Each
+    /// attempt runs on a scratch value, which is then dropped.
+    ///
+    /// `deserialize` is called with the variant already selected, and should
+    /// rewind its input before returning.
+    pub fn find_untagged_variant<E>(
+        shape: &'static Shape,
+        mut deserialize: impl FnMut(Self) -> Result<Self, E>,
+    ) -> Option<&'static Variant> {
+        let Def::Enum(ed) = shape.def else {
+            return None;
+        };
+        ed.variants.iter().find(|variant| {
+            Self::alloc_shape(shape)
+                .variant_named(variant.name)
+                .ok()
+                .and_then(|scratch| deserialize(scratch).ok())
+                .is_some_and(|scratch| scratch.build().is_ok())
+        })
+    }
+
     fn pop_inner(&mut self) -> Option<Frame> {
         let frame = self.frames.pop()?;
         let frame_shape = frame.shape;
@@ -245,6 +277,7 @@ impl<'a> Wip<'a> {
         for (id, is) in self.istates.iter() {
             let field_count = match id.shape.def {
                 Def::Struct(def) => def.fields.len(),
+                Def::Array(def) => def.n,
                 Def::Enum(_) => {
                     if let Some(variant) = &is.variant {
                         variant.data.fields.len()
@@ -282,6 +315,16 @@ impl<'a> Wip<'a> {
                             return Err(ReflectError::NoVariantSelected { shape: id.shape });
                         }
                     }
+                    Def::Array(ad) => {
+                        for index in 0..ad.n {
+                            if !is.fields.has(index) {
+                                return Err(ReflectError::UninitializedArrayElement {
+                                    shape: id.shape,
+                                    index,
+                                });
+                            }
+                        }
+                    }
                     Def::Scalar(_) => {
                         return Err(ReflectError::UninitializedScalar { shape: id.shape });
                     }
@@ -310,7 +353,8 @@ impl<'a> Wip<'a> {
         })
     }
 
-    /// Selects a field of a struct or enum variant by index and pushes it onto the frame stack.
+    /// Selects a field of a struct or enum variant (or an element of a fixed-size
+    /// array) by index and pushes it onto the frame stack.
     ///
     /// # Arguments
     ///
@@ -319,12 +363,52 @@ impl<'a> Wip<'a> {
     /// # Returns
     ///
     /// * `Ok(Self)` if the field was successfully selected and pushed.
-    /// * `Err(ReflectError)` if the current frame is not a struct or an enum with a selected variant,
-    ///   or if the field doesn't exist.
+    /// * `Err(ReflectError)` if the current frame is not a struct, an array or an enum with
+    ///   a selected variant, or if the field doesn't exist.
     pub fn field(mut self, index: usize) -> Result<Self, ReflectError> {
         let frame = self.frames.last_mut().unwrap();
         let shape = frame.shape;
 
+        if let Def::Array(ad) = shape.def {
+            if index >= ad.n {
+                return Err(ReflectError::FieldError {
+                    shape,
+                    field_error: FieldError::IndexOutOfBounds,
+                });
+            }
+            if ad.n > ISet::MAX_INDEX + 1 {
+                return Err(ReflectError::OperationFailed {
+                    shape,
+                    operation: "arrays of more than 64 elements can't be initialized element by element",
+                });
+            }
+
+            let element_shape = ad.t();
+            let element_data = unsafe {
+                frame
+                    .data
+                    .field_uninit_at(index * element_shape.layout.size())
+            };
+            let mut frame = Frame {
+                data: element_data,
+                shape: element_shape,
+                index: Some(index),
+                istate: IState::new(self.frames.len()),
+            };
+            trace!(
+                "[{}] Selecting element {} ({}) of {}",
+                self.frames.len(),
+                index.yellow(),
+                element_shape.green(),
+                shape.blue(),
+            );
+            if let Some(iset) = self.istates.remove(&frame.id()) {
+                frame.istate = iset;
+            }
+            self.frames.push(frame);
+            return Ok(self);
+        }
+
         let (field, field_offset) = match shape.def {
             Def::Struct(def) => {
                 if index >= def.fields.len() {
@@ -414,6 +498,47 @@ impl<'a> Wip<'a> {
         }
     }
 
+    /// Returns whether the field at the given index of the current struct or
+    /// enum variant has been initialized.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(bool)` with the initialization state of the field.
+    /// * `Err(ReflectError)` if the current frame is not a struct or an enum with a selected variant,
+    ///   or if the field doesn't exist.
+    pub fn is_field_set(&self, index: usize) -> Result<bool, ReflectError> {
+        let frame = self.frames.last().unwrap();
+        let shape = frame.shape;
+
+        let field_count = match shape.def {
+            Def::Struct(def) => def.fields.len(),
+            Def::Enum(_) => {
+                let Some(variant) = frame.istate.variant.as_ref() else {
+                    return Err(ReflectError::OperationFailed {
+                        shape,
+                        operation: "tried to check a field but no variant was selected",
+                    });
+                };
+                variant.data.fields.len()
+            }
+            _ => {
+                return Err(ReflectError::WasNotA {
+                    expected: "struct or enum",
+                    actual: shape,
+                });
+            }
+        };
+
+        if index >= field_count {
+            return Err(ReflectError::FieldError {
+                shape,
+                field_error: FieldError::IndexOutOfBounds,
+            });
+        }
+
+        Ok(frame.istate.fields.has(index))
+    }
+
     /// Selects a field of a struct or enum variant by name and pushes it onto the frame stack.
     ///
     /// # Arguments
@@ -457,10 +582,7 @@ impl<'a> Wip<'a> {
     ///
     /// * `Ok(Self)` if the value was successfully put into the frame.
     /// * `Err(ReflectError)` if there was an error putting the value into the frame.
-    pub fn put<'val, T: Facet + 'val>(mut self, t: T) -> Result<Wip<'val>, ReflectError>
-    where
-        'a: 'val,
-    {
+    pub fn put<T: Facet<'a>>(mut self, t: T) -> Result<Self, ReflectError> {
         let Some(frame) = self.frames.last_mut() else {
             return Err(ReflectError::OperationFailed {
                 shape: T::SHAPE,
@@ -511,6 +633,22 @@ impl<'a> Wip<'a> {
                         }
                     }
                 }
+                Def::Array(ad) => {
+                    let element_shape = ad.t();
+                    if let Some(drop_fn) = element_shape.vtable.drop_in_place {
+                        for i in 0..ad.n.min(ISet::MAX_INDEX + 1) {
+                            if frame.istate.fields.has(i) {
+                                unsafe {
+                                    let element_ptr = frame
+                                        .data
+                                        .as_mut_byte_ptr()
+                                        .add(i * element_shape.layout.size());
+                                    drop_fn(Opaque::new(element_ptr));
+                                }
+                            }
+                        }
+                    }
+                }
                 _ => {
                     // For scalar types, nothing to do if not fully initialized
                 }
@@ -654,6 +792,7 @@ impl<'a> Wip<'a> {
 
         match shape.def {
             Def::List(list_def) => Ok(list_def.t()),
+            Def::Array(array_def) => Ok(array_def.t()),
             _ => Err(ReflectError::WasNotA {
                 expected: "list or array",
                 actual: shape,
@@ -885,6 +1024,50 @@ impl<'a> Wip<'a> {
         Ok(self)
     }
 
+    /// Pushes a frame for the inner value of an option
+    ///
+    /// This creates a new frame for the `T` in `Option<T>`. When this frame is
+    /// popped, the option will be set to `Some` of the inner value.
+    pub fn push_some(mut self) -> Result<Self, ReflectError> {
+        // Make sure we're initializing an option
+        let frame = self.frames.last().unwrap();
+        let option_shape = frame.shape;
+
+        let Def::Option(option_def) = option_shape.def else {
+            return Err(ReflectError::WasNotA {
+                expected: "option",
+                actual: option_shape,
+            });
+        };
+
+        // Get the inner type
+        let inner_shape = option_def.t;
+
+        // Allocate memory for the inner value
+        let inner_data = inner_shape.allocate();
+
+        // Create a new frame for the inner value
+        let mut inner_frame = Frame {
+            data: inner_data,
+            shape: inner_shape,
+            index: None,
+            istate: IState::new(self.frames.len()),
+        };
+
+        // Mark this as the inner value of an option
+        inner_frame.istate.mode = FrameMode::OptionSome;
+
+        trace!(
+            "[{}] Pushing some value of type {} for option {}",
+            self.frames.len(),
+            inner_shape.green(),
+            option_shape.blue(),
+        );
+
+        self.frames.push(inner_frame);
+        Ok(self)
+    }
+
     /// Pushes a new key frame for a map entry
     ///
     /// This creates a new frame for the key. After setting the key value,
@@ -1168,6 +1351,46 @@ impl<'a> Wip<'a> {
                 }
             }
 
+            // Handle option inner value frames
+            FrameMode::OptionSome if frame.is_fully_initialized() => {
+                let frame_len = self.frames.len();
+                let parent_frame = self.frames.last_mut().unwrap();
+                let parent_shape = parent_frame.shape;
+
+                let Def::Option(option_def) = parent_shape.def else {
+                    return Err(ReflectError::WasNotA {
+                        expected: "option",
+                        actual: parent_shape,
+                    });
+                };
+
+                trace!(
+                    "[{}] Wrapping value in Some for option {}",
+                    frame_len,
+                    parent_shape.blue()
+                );
+                unsafe {
+                    let value = frame.data.assume_init().as_const();
+                    if parent_frame.istate.fields.has(0) {
+                        // The option was already initialized, replace (and drop) its old value
+                        (option_def.vtable.replace_with_fn)(
+                            parent_frame.data.assume_init(),
+                            Some(value),
+                        );
+                    } else {
+                        (option_def.vtable.init_some_fn)(parent_frame.data, value);
+                    }
+                    parent_frame.mark_fully_initialized();
+
+                    // the value has been moved into the option, deallocate it without dropping
+                    if frame.shape.layout.size() != 0 {
+                        alloc::alloc::dealloc(frame.data.as_mut_byte_ptr(), frame.shape.layout);
+                    }
+                }
+
+                return Ok(self);
+            }
+
             // Map keys are just tracked, they don't need special handling when popped
             // FIXME: that's not true, we need to deallocate them at least??
             FrameMode::MapKey => {}
@@ -1222,7 +1445,7 @@ impl Drop for Wip<'_> {
                     );
 
                     if is.fields.is_any_set() {
-                        if matches!(id.shape.def, Def::Struct(_) | Def::Enum(_)) {
+                        if matches!(id.shape.def, Def::Struct(_) | Def::Enum(_) | Def::Array(_)) {
                             // if it's a composite, rely on the fact that each individual field was deinitialized
                             log::trace!("  Skipping composite type drop: individual fields already deinitialized");
                             return false;
@@ -1248,7 +1471,10 @@ impl Drop for Wip<'_> {
                     }
 
                     match is.mode {
-                        FrameMode::MapKey | FrameMode::MapValue(_) | FrameMode::ListElement => {
+                        FrameMode::MapKey
+                        | FrameMode::MapValue(_)
+                        | FrameMode::ListElement
+                        | FrameMode::OptionSome => {
                             // hey we initialized those, we have to free them
                             unsafe {
                                 trace!("  Freeing {}", id.shape.green());
@@ -1356,7 +1582,7 @@ impl ISet {
 pub struct HeapValue<'a> {
     guard: Option<Guard>,
     shape: &'static Shape,
-    phantom: PhantomData<&'a ()>,
+    phantom: PhantomData<fn(&'a ()) -> &'a ()>,
 }
 
 impl Drop for HeapValue<'_> {
@@ -1372,7 +1598,7 @@ impl Drop for HeapValue<'_> {
 
 impl<'a> HeapValue<'a> {
     /// Turn this heapvalue into a concrete type
-    pub fn materialize<T: Facet + 'a>(mut self) -> Result<T, ReflectError> {
+    pub fn materialize<T: Facet<'a>>(mut self) -> Result<T, ReflectError> {
         if self.shape != T::SHAPE {
             return Err(ReflectError::WrongShape {
                 expected: self.shape,
@@ -0,0 +1,70 @@
+use core::fmt;
+
+use facet_reflect::{PathSegment, Snippet, format_path};
+
+struct Render<'a>(&'a Snippet, Option<&'a str>);
+
+impl fmt::Display for Render<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.write(f, self.1, false)
+    }
+}
+
+#[test]
+fn format_path_from_outermost() {
+    facet_testhelpers::setup();
+
+    let path = [
+        PathSegment::Field("config"),
+        PathSegment::Key("servers".into()),
+        PathSegment::Index(2),
+        PathSegment::Field("port"),
+    ];
+    assert_eq!(format_path(&path), "config.servers[2].port");
+    // paths collected while unwinding are innermost first
+    assert_eq!(format_path(path.iter().rev()), "port[2].servers.config");
+    assert_eq!(format_path(&[]), "");
+}
+
+#[test]
+fn snippet_underlines_span() {
+    facet_testhelpers::setup();
+
+    let input = "name: web\n\tport: 80000\r\nnext: 1";
+    let start = input.find("80000").unwrap();
+    let snippet = Snippet::new(input.as_bytes(), start..start + 5);
+    assert_eq!((snippet.line(), snippet.column()), (2, 8));
+    assert_eq!(snippet.position(), start);
+    assert_eq!(
+        Render(&snippet, Some("expected a port")).to_string(),
+        " --> line 2, column 8 (byte 17)
+  |
+2 |  port: 80000
+  |        ^^^^^ expected a port"
+    );
+
+    // the underline stops at the end of the line, and is never empty
+    let snippet = Snippet::new(input.as_bytes(), start..input.len());
+    assert!(
+        Render(&snippet, None)
+            .to_string()
+            .ends_with("|        ^^^^^")
+    );
+    let snippet = Snippet::new(input.as_bytes(), input.len()..input.len());
+    assert!(Render(&snippet, None).to_string().ends_with("|        ^"));
+}
+
+#[test]
+fn snippet_windows_long_lines() {
+    facet_testhelpers::setup();
+
+    let input = format!("[{}\"x\"{}]", "1,".repeat(100), ",2".repeat(100));
+    let start = input.find('"').unwrap();
+    let rendered = Render(&Snippet::new(input.as_bytes(), start..start + 3), None).to_string();
+    let lines: Vec<&str> = rendered.lines().collect();
+    assert!(lines[2].starts_with("1 | …"), "{rendered}");
+    assert!(lines[2].ends_with('…'), "{rendered}");
+    let caret = lines[3].find('^').unwrap();
+    let underlined: String = lines[2].chars().skip(caret).take(3).collect();
+    assert_eq!(underlined, "\"x\"");
+}
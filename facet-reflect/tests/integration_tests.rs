@@ -1,2 +1,3 @@
+mod diagnostic;
 mod peek;
 mod wip;
@@ -0,0 +1,41 @@
+use facet_reflect::Peek;
+
+#[test]
+fn peek_array() -> Result<(), Box<dyn std::error::Error>> {
+    facet_testhelpers::setup();
+
+    let test_array = [10u16, 20, 30];
+    let peek_array = Peek::new(&test_array).into_array()?;
+
+    assert_eq!(peek_array.len(), 3);
+    assert_eq!(*peek_array.get(0).unwrap().get::<u16>()?, 10);
+    assert_eq!(*peek_array.get(2).unwrap().get::<u16>()?, 30);
+    assert!(peek_array.get(3).is_none());
+
+    let values: Vec<u16> = peek_array
+        .iter()
+        .map(|item| *item.get::<u16>().unwrap())
+        .collect();
+    assert_eq!(values, vec![10, 20, 30]);
+
+    Ok(())
+}
+
+#[test]
+fn peek_slice() -> Result<(), Box<dyn std::error::Error>> {
+    facet_testhelpers::setup();
+
+    let test_slice: &[&str] = &["a", "b"];
+    let peek_slice = Peek::new(&test_slice).into_slice()?;
+
+    assert_eq!(peek_slice.len(), 2);
+    let values: Vec<&str> = peek_slice
+        .iter()
+        .map(|item| *item.get::<&str>().unwrap())
+        .collect();
+    assert_eq!(values, vec!["a", "b"]);
+
+    assert!(Peek::new(&test_slice).into_array().is_err());
+
+    Ok(())
+}
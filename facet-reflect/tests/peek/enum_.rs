@@ -48,3 +48,48 @@ fn peek_enum() -> eyre::Result<()> {
 
     Ok(())
 }
+
+#[derive(Facet)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Facet)]
+#[repr(u8)]
+#[allow(dead_code)]
+enum Figure {
+    Dot,
+    Circle { radius: u32 },
+    At(Point),
+    Pair(u32, u32),
+}
+
+#[test]
+fn peek_enum_internally_tagged_fields() -> eyre::Result<()> {
+    facet_testhelpers::setup();
+
+    let names = |value: &Figure| -> eyre::Result<Option<Vec<&'static str>>> {
+        let fields = Peek::new(value).into_enum()?.internally_tagged_fields();
+        Ok(fields.map(|fields| fields.map(|(field, _)| field.name).collect()))
+    };
+
+    assert_eq!(names(&Figure::Dot)?, Some(vec![]));
+    assert_eq!(names(&Figure::Circle { radius: 2 })?, Some(vec!["radius"]));
+    assert_eq!(
+        names(&Figure::At(Point { x: 1, y: 2 }))?,
+        Some(vec!["x", "y"])
+    );
+    assert_eq!(names(&Figure::Pair(1, 2))?, None);
+
+    let point = Figure::At(Point { x: 1, y: 2 });
+    let peek_enum = Peek::new(&point).into_enum()?;
+    let values = peek_enum
+        .internally_tagged_fields()
+        .unwrap()
+        .map(|(_, peek)| *peek.get::<i32>().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(values, [1, 2]);
+
+    Ok(())
+}
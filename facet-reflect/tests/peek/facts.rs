@@ -4,9 +4,9 @@ use facet::Facet;
 use facet_ansi::{ColorStyle, Style, Stylize as _};
 use facet_reflect::{Peek, Wip};
 
-fn check_facts<T>(val1: T, val2: T, expected_facts: HashSet<Fact>)
+fn check_facts<'a, T>(val1: T, val2: T, expected_facts: HashSet<Fact>)
 where
-    T: Facet + 'static,
+    T: Facet<'a> + 'static,
 {
     let mut facts: HashSet<Fact> = HashSet::new();
     let name = format!("{}", T::SHAPE);
@@ -1,3 +1,4 @@
+mod array;
 mod c_test;
 mod enum_;
 #[cfg(feature = "std")]
@@ -104,3 +104,19 @@ fn test_smart_pointer_flags() {
         assert_eq!(known_type, facet_core::KnownSmartPointer::Arc);
     }
 }
+
+#[test]
+fn test_peek_arc_borrow_inner() {
+    facet_testhelpers::setup();
+
+    let source = Arc::new("Hello, world!".to_string());
+    let peek_smart_pointer = Peek::new(&source).into_smart_pointer().unwrap();
+
+    let inner = peek_smart_pointer.borrow_inner().unwrap();
+    assert_eq!(inner.get::<String>().unwrap(), "Hello, world!");
+
+    // weak pointers must be upgraded before they can be borrowed from
+    let weak = Arc::downgrade(&source);
+    let peek_weak = Peek::new(&weak).into_smart_pointer().unwrap();
+    assert!(peek_weak.borrow_inner().is_none());
+}
@@ -0,0 +1,68 @@
+use facet_reflect::{ReflectError, Wip};
+
+#[test]
+fn wip_array_build() -> eyre::Result<()> {
+    facet_testhelpers::setup();
+
+    let array = Wip::alloc::<[String; 3]>()
+        .field(0)?
+        .put("a".to_string())?
+        .pop()?
+        .field(2)?
+        .put("c".to_string())?
+        .pop()?
+        .field(1)?
+        .put("b".to_string())?
+        .pop()?
+        .build()?
+        .materialize::<[String; 3]>()?;
+    assert_eq!(array, ["a", "b", "c"]);
+    Ok(())
+}
+
+#[test]
+fn wip_array_nested() -> eyre::Result<()> {
+    facet_testhelpers::setup();
+
+    let array = Wip::alloc::<[[u8; 2]; 2]>()
+        .field(0)?
+        .field(0)?
+        .put(1u8)?
+        .pop()?
+        .field(1)?
+        .put(2u8)?
+        .pop()?
+        .pop()?
+        .field(1)?
+        .field(0)?
+        .put(3u8)?
+        .pop()?
+        .field(1)?
+        .put(4u8)?
+        .pop()?
+        .pop()?
+        .build()?
+        .materialize::<[[u8; 2]; 2]>()?;
+    assert_eq!(array, [[1, 2], [3, 4]]);
+    Ok(())
+}
+
+#[test]
+fn wip_array_errors() -> eyre::Result<()> {
+    facet_testhelpers::setup();
+
+    let result = Wip::alloc::<[u32; 2]>().field(2);
+    assert!(matches!(result, Err(ReflectError::FieldError { .. })));
+
+    // the element that was set gets dropped, not the one that wasn't
+    let result = Wip::alloc::<[String; 2]>()
+        .field(0)?
+        .put("set".to_string())?
+        .pop()?
+        .build();
+    assert!(matches!(
+        result,
+        Err(ReflectError::UninitializedArrayElement { index: 1, .. })
+    ));
+    Ok(())
+}
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use facet::Facet;
-use facet_reflect::Wip;
+use facet_reflect::{ReflectError, Wip};
 
 #[derive(Facet, PartialEq, Eq, Debug)]
 struct Outer {
@@ -202,6 +202,33 @@ fn wip_enum_with_data() -> eyre::Result<()> {
     Ok(())
 }
 
+#[test]
+fn wip_find_untagged_variant() -> eyre::Result<()> {
+    facet_testhelpers::setup();
+
+    let shape = EnumWithData::SHAPE;
+
+    // the first variant that reads, in declaration order
+    let variant = Wip::find_untagged_variant(shape, Ok::<_, ReflectError>);
+    assert_eq!(variant.map(|v| v.name), Some("Empty"));
+
+    let variant = Wip::find_untagged_variant(shape, |wip| wip.field(0)?.put(42)?.pop());
+    assert_eq!(variant.map(|v| v.name), Some("Single"));
+
+    // a partially initialized variant doesn't match
+    let variant = Wip::find_untagged_variant(shape, |wip| {
+        wip.field_named("y")?.put(String::from("World"))?.pop()
+    });
+    assert_eq!(variant, None);
+
+    assert_eq!(
+        Wip::find_untagged_variant(u32::SHAPE, Ok::<_, ReflectError>),
+        None
+    );
+
+    Ok(())
+}
+
 #[test]
 fn wip_enum_error_cases() -> eyre::Result<()> {
     facet_testhelpers::setup();
@@ -405,3 +432,44 @@ fn wip_map() -> eyre::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn wip_option() -> eyre::Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, PartialEq, Eq, Debug)]
+    struct WithOptions {
+        name: Option<String>,
+        age: Option<u32>,
+    }
+
+    let wip = Wip::alloc::<WithOptions>()
+        .field_named("name")?
+        .push_some()?
+        .put(String::from("Alice"))?
+        .pop()?
+        .pop()?;
+    assert!(wip.is_field_set(0)?);
+    assert!(!wip.is_field_set(1)?);
+
+    let v = wip
+        .field_named("age")?
+        .put_default()?
+        .pop()?
+        .build()?
+        .materialize::<WithOptions>()?;
+
+    assert_eq!(
+        v,
+        WithOptions {
+            name: Some(String::from("Alice")),
+            age: None,
+        }
+    );
+
+    // Test error: trying to push_some on a non-option type
+    let result = Wip::alloc::<i32>().push_some();
+    assert!(result.is_err());
+
+    Ok(())
+}
@@ -18,3 +18,6 @@ mod struct_leak;
 
 #[cfg(feature = "std")]
 mod put_vec_leak;
+
+#[cfg(feature = "std")]
+mod array;
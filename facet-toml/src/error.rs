@@ -1,25 +1,208 @@
 //! Errors from parsing TOML documents.
 
-/// Any error
-#[derive(Debug, Clone)]
-pub struct AnyErr(pub(crate) String);
+use std::{
+    fmt::{self, Display, Formatter},
+    ops::Range,
+};
 
-impl core::fmt::Display for AnyErr {
-    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "{}", self.0)
+use facet_ansi::{ColorStyle as _, Style, Stylize as _};
+use facet_core::Shape;
+use facet_reflect::{PathSegment, ReflectError, Snippet, format_path};
+
+/// A TOML deserialization error, located in the input.
+///
+/// Besides the [kind](TomlErrorKind) of error, this records where in the input
+/// it happened (byte span, line and column) and the path of the value being
+/// deserialized (e.g. `servers.alpha.port`).
+///
+/// The `Display` implementation renders the offending line of input with a
+/// caret underline. Call [`with_colors`](Self::with_colors) to style it
+/// for a terminal.
+#[derive(Debug)]
+pub struct TomlError {
+    kind: TomlErrorKind,
+    span: Range<usize>,
+    snippet: Snippet,
+    path: String,
+    colors: bool,
+}
+
+impl TomlError {
+    /// Creates a new error of the given kind, for the given byte span of the input
+    pub(crate) fn new(kind: TomlErrorKind, input: &str, span: Range<usize>) -> Self {
+        let start = span.start.min(input.len());
+        Self {
+            kind,
+            snippet: Snippet::new(input.as_bytes(), span.clone()),
+            span: start..span.end.max(start),
+            path: String::new(),
+            colors: false,
+        }
+    }
+
+    /// Records the path of the value that was being deserialized
+    pub(crate) fn with_path(mut self, path: String) -> Self {
+        self.path = path;
+        self
+    }
+
+    /// Enable or disable ANSI colors when displaying this error
+    pub fn with_colors(mut self, colors: bool) -> Self {
+        self.colors = colors;
+        self
+    }
+
+    /// Returns the kind of error that occurred
+    pub fn kind(&self) -> &TomlErrorKind {
+        &self.kind
+    }
+
+    /// Returns the byte span of the input the error is about
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+
+    /// Returns the 1-based line at which the error occurred
+    pub fn line(&self) -> usize {
+        self.snippet.line()
+    }
+
+    /// Returns the 1-based column (in characters) at which the error occurred
+    pub fn column(&self) -> usize {
+        self.snippet.column()
+    }
+
+    /// Returns the path of the value that was being deserialized, like
+    /// `servers.alpha.port`. Empty for the top-level table.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    fn style(&self, style: Style) -> Style {
+        if self.colors { style } else { Style::new() }
     }
 }
 
-impl std::error::Error for AnyErr {}
+impl Display for TomlError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let error = self.style(Style::new().fg_red().fg_bold());
+        let path = self.style(Style::new().fg_yellow());
 
-impl From<String> for AnyErr {
-    fn from(s: String) -> Self {
-        Self(s)
+        write!(f, "{}: {}", "TOML error".style(error), self.kind)?;
+        if !self.path.is_empty() {
+            write!(f, " (at {})", self.path.as_str().style(path))?;
+        }
+        writeln!(f)?;
+        self.snippet.write(f, None, self.colors)
     }
 }
 
-impl From<&str> for AnyErr {
-    fn from(s: &str) -> Self {
-        Self(s.to_string())
+impl std::error::Error for TomlError {}
+
+/// The different kinds of errors that can occur while deserializing TOML
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum TomlErrorKind {
+    /// The input isn't a valid TOML document
+    Syntax(String),
+    /// The TOML value doesn't have the right type for the shape being deserialized
+    TypeMismatch {
+        /// The shape we were deserializing
+        expected: &'static Shape,
+        /// The kind of TOML value we got instead (e.g. "string", "table")
+        got: &'static str,
+    },
+    /// The value could not be converted into the shape being deserialized
+    /// (e.g. a number out of range, or a malformed IP address)
+    InvalidValue(&'static Shape),
+    /// An array doesn't have as many elements as the fixed-size array it's read into
+    WrongLength {
+        /// The number of elements of the fixed-size array
+        expected: usize,
+        /// The number of elements in the TOML array
+        got: usize,
+    },
+    /// A field required by a struct was not present in the table
+    MissingField(&'static str),
+    /// The table has a key that isn't a field of the struct
+    UnknownField(String),
+    /// The enum has no variant with this name
+    UnknownVariant(String),
+    /// None of the variants of an untagged enum could be deserialized from the value
+    NoMatchingVariant(&'static Shape),
+    /// The shape is not supported by the TOML deserializer
+    UnsupportedShape(&'static Shape),
+    /// An error occurred while building the value
+    ReflectError(ReflectError),
+}
+
+impl Display for TomlErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TomlErrorKind::Syntax(message) => write!(f, "{}", message),
+            TomlErrorKind::TypeMismatch { expected, got } => {
+                write!(f, "Expected {}, got {}", expected, got)
+            }
+            TomlErrorKind::InvalidValue(shape) => write!(f, "Invalid value for {}", shape),
+            TomlErrorKind::WrongLength { expected, got } => {
+                write!(f, "Expected {} elements, got {}", expected, got)
+            }
+            TomlErrorKind::MissingField(name) => write!(f, "Missing field: {}", name),
+            TomlErrorKind::UnknownField(name) => write!(f, "Unknown field: {}", name),
+            TomlErrorKind::UnknownVariant(name) => write!(f, "Unknown variant: {}", name),
+            TomlErrorKind::NoMatchingVariant(shape) => {
+                write!(f, "Data did not match any variant of {}", shape)
+            }
+            TomlErrorKind::UnsupportedShape(shape) => write!(f, "Unsupported shape: {}", shape),
+            TomlErrorKind::ReflectError(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// An error serializing a value to TOML
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum TomlSerializeError {
+    /// The value (or part of it) can't be represented as TOML
+    Unsupported(String),
+}
+
+impl Display for TomlSerializeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TomlSerializeError::Unsupported(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for TomlSerializeError {}
+
+/// A deserialization error that hasn't been located in the input yet: the
+/// path is filled in as the error bubbles up through the values it's in
+pub(crate) struct DeError {
+    kind: TomlErrorKind,
+    span: Range<usize>,
+    /// innermost segment first
+    path: Vec<PathSegment<'static>>,
+}
+
+impl DeError {
+    pub(crate) fn new(kind: TomlErrorKind, span: Range<usize>) -> Self {
+        Self {
+            kind,
+            span,
+            path: Vec::new(),
+        }
+    }
+
+    /// Records that the error happened inside `segment` of the enclosing value
+    pub(crate) fn within(mut self, segment: PathSegment<'static>) -> Self {
+        self.path.push(segment);
+        self
+    }
+
+    pub(crate) fn locate(self, input: &str) -> TomlError {
+        let path = format_path(self.path.iter().rev());
+        TomlError::new(self.kind, input, self.span).with_path(path)
     }
 }
@@ -2,227 +2,625 @@
 #![doc = include_str!("../README.md")]
 
 pub mod error;
+mod serialize;
 mod to_scalar;
 
+pub use error::{TomlError, TomlErrorKind, TomlSerializeError};
+pub use serialize::*;
+/// The format-preserving document [`update_document`] edits
+pub use toml_edit::DocumentMut;
+
 use std::{
     borrow::Cow,
     net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
     num::NonZero,
+    ops::Range,
 };
 
-use error::AnyErr;
-use facet_core::{Def, Facet};
-use facet_reflect::Wip;
-use toml_edit::{DocumentMut, Item, TomlError};
+use error::DeError;
+use facet_core::{Def, EnumTagging, Facet, Field, ScalarAffinity, StructKind, TaggedFields};
+use facet_reflect::{PathSegment, ReflectError, Wip};
+use toml_edit::{ArrayOfTables, ImDocument, Item, Key, Table, TableLike, Value};
 
 /// Deserializes a TOML string into a value of type `T` that implements `Facet`.
-pub fn from_str<T: Facet>(toml: &str) -> Result<T, AnyErr> {
+///
+/// Table keys that aren't fields are skipped, unless the struct has
+/// `#[facet(deny_unknown_fields)]`.
+pub fn from_str<'a, T: Facet<'a>>(toml: &str) -> Result<T, TomlError> {
+    let located = |e: DeError| e.locate(toml);
+    let root = 0..0;
     let wip = Wip::alloc::<T>();
-    let wip = from_str_value(wip, toml)?;
-    let heap_value = wip.build().map_err(|e| AnyErr(e.to_string()))?;
+    let wip = from_str_value(wip, toml).map_err(located)?;
+    let heap_value = wip.build().map_err(|e| located(reflect_err(&root)(e)))?;
     heap_value
         .materialize::<T>()
-        .map_err(|e| AnyErr(e.to_string()))
+        .map_err(|e| located(reflect_err(&root)(e)))
+}
+
+fn from_str_value<'a>(wip: Wip<'a>, toml: &str) -> Result<Wip<'a>, DeError> {
+    let document = ImDocument::parse(toml).map_err(|e| {
+        DeError::new(
+            // the message is an error and what was expected, on separate lines
+            TomlErrorKind::Syntax(e.message().trim_end().replace('\n', ", ")),
+            e.span().unwrap_or(0..0),
+        )
+    })?;
+    deserialize_node(wip, Node::Table(document.as_table()), &(0..0))
+}
+
+/// Wraps an error from building the value deserialized from `span`
+fn reflect_err(span: &Range<usize>) -> impl FnOnce(ReflectError) -> DeError + use<> {
+    let span = span.clone();
+    move |e| DeError::new(TomlErrorKind::ReflectError(e), span)
 }
 
-fn from_str_value<'a>(wip: Wip<'a>, toml: &str) -> Result<Wip<'a>, AnyErr> {
-    let docs: DocumentMut = toml.parse().map_err(|e| TomlError::to_string(&e))?;
-    deserialize_item(wip, docs.as_item())
+/// Something to deserialize a value from: array elements are [`Value`]s and
+/// arrays of tables hold [`Table`]s, so this doesn't go through [`Item`]
+#[derive(Clone, Copy)]
+enum Node<'i> {
+    /// A value, including inline tables and arrays
+    Value(&'i Value),
+    /// A `[table]`, or the document itself
+    Table(&'i Table),
+    /// An array of `[[tables]]`
+    ArrayOfTables(&'i ArrayOfTables),
+}
+
+impl<'i> Node<'i> {
+    /// Returns `None` for an empty item
+    fn from_item(item: &'i Item) -> Option<Self> {
+        match item {
+            Item::None => None,
+            Item::Value(value) => Some(Node::Value(value)),
+            Item::Table(table) => Some(Node::Table(table)),
+            Item::ArrayOfTables(tables) => Some(Node::ArrayOfTables(tables)),
+        }
+    }
+
+    fn span(self) -> Option<Range<usize>> {
+        match self {
+            Node::Value(value) => value.span(),
+            Node::Table(table) => table.span(),
+            Node::ArrayOfTables(tables) => tables.span(),
+        }
+    }
+
+    fn type_name(self) -> &'static str {
+        match self {
+            Node::Value(value) => value.type_name(),
+            Node::Table(_) => "table",
+            Node::ArrayOfTables(_) => "array of tables",
+        }
+    }
+
+    fn as_value(self) -> Option<&'i Value> {
+        match self {
+            Node::Value(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> Option<&'i str> {
+        self.as_value().and_then(Value::as_str)
+    }
+
+    fn as_table_like(self) -> Option<&'i dyn TableLike> {
+        match self {
+            Node::Value(Value::InlineTable(table)) => Some(table),
+            Node::Table(table) => Some(table),
+            _ => None,
+        }
+    }
 }
 
-fn deserialize_item<'a>(wip: Wip<'a>, item: &Item) -> Result<Wip<'a>, AnyErr> {
+fn deserialize_node<'a>(
+    wip: Wip<'a>,
+    node: Node<'_>,
+    span: &Range<usize>,
+) -> Result<Wip<'a>, DeError> {
     let shape = wip.shape();
     match shape.def {
-        Def::Scalar(_) => deserialize_as_scalar(wip, item),
-        Def::List(_) => todo!(),
-        Def::Map(_) => todo!(),
-        Def::Struct(_) => deserialize_as_struct(wip, item),
-        Def::Enum(_) => deserialize_as_enum(wip, item),
-        _ => Err(AnyErr(format!("Unsupported type: {:?}", shape))),
+        Def::Scalar(_) => deserialize_as_scalar(wip, node, span),
+        Def::List(_) => deserialize_as_list(wip, node, span),
+        Def::Map(_) => deserialize_as_map(wip, node, span),
+        Def::Array(ad) => deserialize_as_array(wip, node, span, ad.n),
+        // absent keys are `None`, see `deserialize_as_struct`
+        Def::Option(_) => {
+            let wip = wip.push_some().map_err(reflect_err(span))?;
+            let wip = deserialize_node(wip, node, span)?;
+            wip.pop().map_err(reflect_err(span))
+        }
+        Def::Struct(_) => deserialize_as_struct(wip, node, span),
+        Def::Enum(_) => deserialize_as_enum(wip, node, span),
+        _ => Err(DeError::new(
+            TomlErrorKind::UnsupportedShape(shape),
+            span.clone(),
+        )),
     }
 }
 
-fn deserialize_as_struct<'a>(mut wip: Wip<'a>, item: &Item) -> Result<Wip<'a>, AnyErr> {
+/// Deserializes the value of `key` in a table spanning `parent`
+fn deserialize_entry<'a>(
+    wip: Wip<'a>,
+    key: Option<&Key>,
+    item: &Item,
+    parent: &Range<usize>,
+) -> Result<Wip<'a>, DeError> {
+    let key_span = key.and_then(Key::span);
+    let Some(node) = Node::from_item(item) else {
+        return Err(to_scalar::mismatch_of(
+            "none",
+            &key_span.unwrap_or_else(|| parent.clone()),
+            wip.shape(),
+        ));
+    };
+    // implicit tables, like `a` in `[a.b]`, have no span of their own
+    let span = node.span().or(key_span).unwrap_or_else(|| parent.clone());
+    deserialize_node(wip, node, &span)
+}
+
+fn deserialize_as_struct<'a>(
+    mut wip: Wip<'a>,
+    node: Node<'_>,
+    span: &Range<usize>,
+) -> Result<Wip<'a>, DeError> {
+    let shape = wip.shape();
+    let Def::Struct(def) = shape.def else {
+        unreachable!()
+    };
+
     // Parse as the inner struct type if item is a single value and the struct is a unit struct
-    if item.is_value() {
+    let Some(table) = node.as_table_like() else {
         // Only allow unit structs
-        let shape = wip.shape();
-        if let Def::Struct(def) = shape.def {
-            if def.fields.len() > 1 {
-                return Err(AnyErr(
-                    "Failed trying to parse a single value as a struct with multiple fields".into(),
-                ));
-            }
+        if node.as_value().is_none() || def.fields.len() != 1 {
+            return Err(to_scalar::mismatch(node, span, shape));
         }
 
-        let field_index = 0;
-        wip = wip
-            .field(field_index)
-            .map_err(|e| AnyErr(format!("Unit struct is missing value: {}", e)))?;
-        wip = deserialize_item(wip, item)?;
-        wip = wip.pop().map_err(|e| AnyErr(e.to_string()))?;
+        wip = wip.field(0).map_err(reflect_err(span))?;
+        wip = deserialize_node(wip, node, span)?;
+        wip = wip.pop().map_err(reflect_err(span))?;
         return Ok(wip);
-    }
+    };
 
-    // Otherwise we expect a table
-    let table = item.as_table_like().ok_or_else(|| {
-        AnyErr(format!(
-            "Expected table like structure, got {}",
-            item.type_name()
-        ))
-    })?;
+    deserialize_fields(wip, def.fields, table, span, None)
+}
 
+/// Fills the `fields` of a struct or struct variant from a table, ignoring
+/// the `skip` key (the tag of an internally tagged enum)
+fn deserialize_fields<'a>(
+    mut wip: Wip<'a>,
+    fields: &'static [Field],
+    table: &dyn TableLike,
+    span: &Range<usize>,
+    skip: Option<&str>,
+) -> Result<Wip<'a>, DeError> {
     for (k, v) in table.iter() {
-        let field_index = wip
-            .field_index(k)
-            .ok_or_else(|| AnyErr(format!("Field '{}' not found", k)))?;
+        if skip == Some(k) {
+            continue;
+        }
+        let key = table.key(k);
+        let Some(index) = wip.field_index(k) else {
+            if wip.shape().denies_unknown_fields() {
+                return Err(DeError::new(
+                    TomlErrorKind::UnknownField(k.to_string()),
+                    key.and_then(Key::span).unwrap_or_else(|| span.clone()),
+                ));
+            }
+            continue;
+        };
+        wip = wip.field(index).map_err(reflect_err(span))?;
+        wip = deserialize_entry(wip, key, v, span)
+            .map_err(|e| e.within(PathSegment::Field(fields[index].name)))?;
+        wip = wip.pop().map_err(reflect_err(span))?;
+    }
+
+    // absent optional fields are `None`, everything else is required
+    for (index, field) in fields.iter().enumerate() {
+        if wip.is_field_set(index).map_err(reflect_err(span))? {
+            continue;
+        }
+        if !matches!(field.shape().def, Def::Option(_)) {
+            return Err(DeError::new(
+                TomlErrorKind::MissingField(field.name),
+                span.clone(),
+            ));
+        }
         wip = wip
-            .field(field_index)
-            .map_err(|e| AnyErr(format!("Field '{}' error: {}", k, e)))?;
-        wip = deserialize_item(wip, v)
-            .map_err(|e| AnyErr(format!("Error deserializing field '{}': {}", k, e)))?;
-        wip = wip.pop().map_err(|e| AnyErr(e.to_string()))?;
+            .field(index)
+            .and_then(|wip| wip.put_default())
+            .and_then(|wip| wip.pop())
+            .map_err(reflect_err(span))?;
     }
 
     Ok(wip)
 }
 
-fn deserialize_as_enum<'a>(wip: Wip<'a>, item: &Item) -> Result<Wip<'a>, AnyErr> {
-    if item.is_value() {
-        let variant_name = item
-            .as_str()
-            .ok_or_else(|| AnyErr(format!("Expected string, got: {}", item.type_name())))?;
+/// Deserializes a list from an array, or from an array of tables
+fn deserialize_as_list<'a>(
+    wip: Wip<'a>,
+    node: Node<'_>,
+    span: &Range<usize>,
+) -> Result<Wip<'a>, DeError> {
+    let elements: Vec<Node<'_>> = match node {
+        Node::Value(Value::Array(array)) => array.iter().map(Node::Value).collect(),
+        Node::ArrayOfTables(tables) => tables.iter().map(Node::Table).collect(),
+        _ => return Err(to_scalar::mismatch(node, span, wip.shape())),
+    };
 
-        // Use variant_named to select the variant by name
-        wip.variant_named(variant_name).map_err(|e| {
-            AnyErr(format!(
-                "Error selecting enum variant '{}': {}",
-                variant_name, e
+    let mut wip = wip.begin_pushback().map_err(reflect_err(span))?;
+    for (index, element) in elements.into_iter().enumerate() {
+        let element_span = element.span().unwrap_or_else(|| span.clone());
+        wip = wip.push().map_err(reflect_err(&element_span))?;
+        wip = deserialize_node(wip, element, &element_span)
+            .map_err(|e| e.within(PathSegment::Index(index)))?;
+        wip = wip.pop().map_err(reflect_err(&element_span))?;
+    }
+    Ok(wip)
+}
+
+/// Deserializes a fixed-size array from a TOML array of exactly `len` elements
+fn deserialize_as_array<'a>(
+    mut wip: Wip<'a>,
+    node: Node<'_>,
+    span: &Range<usize>,
+    len: usize,
+) -> Result<Wip<'a>, DeError> {
+    let Node::Value(Value::Array(array)) = node else {
+        return Err(to_scalar::mismatch(node, span, wip.shape()));
+    };
+    if array.len() != len {
+        return Err(DeError::new(
+            TomlErrorKind::WrongLength {
+                expected: len,
+                got: array.len(),
+            },
+            span.clone(),
+        ));
+    }
+
+    for (index, element) in array.iter().enumerate() {
+        let element_span = element.span().unwrap_or_else(|| span.clone());
+        wip = wip.field(index).map_err(reflect_err(&element_span))?;
+        wip = deserialize_node(wip, Node::Value(element), &element_span)
+            .map_err(|e| e.within(PathSegment::Index(index)))?;
+        wip = wip.pop().map_err(reflect_err(&element_span))?;
+    }
+    Ok(wip)
+}
+
+/// Deserializes a map from a table or an inline table. Keys are always
+/// strings in TOML; other key types are parsed from them.
+fn deserialize_as_map<'a>(
+    wip: Wip<'a>,
+    node: Node<'_>,
+    span: &Range<usize>,
+) -> Result<Wip<'a>, DeError> {
+    let Some(table) = node.as_table_like() else {
+        return Err(to_scalar::mismatch(node, span, wip.shape()));
+    };
+
+    let mut wip = wip.begin_map_insert().map_err(reflect_err(span))?;
+    for (k, v) in table.iter() {
+        let key = table.key(k);
+        let key_span = key.and_then(Key::span).unwrap_or_else(|| span.clone());
+        wip = wip.push_map_key().map_err(reflect_err(&key_span))?;
+        let key_shape = wip.shape();
+        wip = if key_shape.is_type::<String>() {
+            wip.put(k.to_string())
+        } else if key_shape.is_type::<Cow<'_, str>>() {
+            wip.put(Cow::<'static, str>::Owned(k.to_string()))
+        } else {
+            wip.parse(k)
+        }
+        .map_err(|_| to_scalar::invalid(&key_span, key_shape))?;
+        wip = wip.push_map_value().map_err(reflect_err(&key_span))?;
+        wip = deserialize_entry(wip, key, v, span)
+            .map_err(|e| e.within(PathSegment::Key(k.to_string().into())))?;
+        wip = wip.pop().map_err(reflect_err(&key_span))?;
+    }
+    Ok(wip)
+}
+
+/// Deserializes an enum according to its tagging: by default, a unit variant
+/// is its name as a string and any other variant a table with a single key,
+/// the variant name, holding its data (`[auth.Token]`)
+fn deserialize_as_enum<'a>(
+    wip: Wip<'a>,
+    node: Node<'_>,
+    span: &Range<usize>,
+) -> Result<Wip<'a>, DeError> {
+    let shape = wip.shape();
+    match shape.enum_tagging() {
+        EnumTagging::Internal { tag } => {
+            let table = node
+                .as_table_like()
+                .ok_or_else(|| to_scalar::mismatch(node, span, shape))?;
+            let wip = select_variant(wip, tagged_variant_name(table, span, tag)?, span)?;
+            let variant = wip.selected_variant().unwrap();
+            match variant.internally_tagged_fields() {
+                Some(TaggedFields::Unit) => Ok(wip),
+                Some(TaggedFields::Struct(fields)) => {
+                    deserialize_fields(wip, fields, table, span, Some(tag))
+                }
+                Some(TaggedFields::Newtype(fields)) => {
+                    let wip = wip.field(0).map_err(reflect_err(span))?;
+                    let wip = deserialize_fields(wip, fields, table, span, Some(tag))?;
+                    wip.pop().map_err(reflect_err(span))
+                }
+                _ => Err(DeError::new(
+                    TomlErrorKind::UnsupportedShape(shape),
+                    span.clone(),
+                )),
+            }
+        }
+        EnumTagging::Adjacent { tag, content } => {
+            let table = node
+                .as_table_like()
+                .ok_or_else(|| to_scalar::mismatch(node, span, shape))?;
+            let wip = select_variant(wip, tagged_variant_name(table, span, tag)?, span)?;
+            let variant = wip.selected_variant().unwrap();
+            match (table.get(content), variant.data.kind) {
+                (Some(item), _) => {
+                    let payload = Node::from_item(item)
+                        .ok_or_else(|| to_scalar::mismatch_of("none", span, shape))?;
+                    let payload_span = payload.span().unwrap_or_else(|| span.clone());
+                    deserialize_variant_payload(wip, payload, &payload_span)
+                        .map_err(|e| e.within(PathSegment::Field(content)))
+                }
+                (None, StructKind::Unit) => Ok(wip),
+                (None, _) => Err(DeError::new(
+                    TomlErrorKind::MissingField(content),
+                    span.clone(),
+                )),
+            }
+        }
+        EnumTagging::Untagged => {
+            let variant = Wip::find_untagged_variant(shape, |scratch| {
+                deserialize_variant_payload(scratch, node, span)
+            });
+            if let Some(variant) = variant {
+                let wip = wip.variant_named(variant.name).map_err(reflect_err(span))?;
+                return deserialize_variant_payload(wip, node, span);
+            }
+            Err(DeError::new(
+                TomlErrorKind::NoMatchingVariant(shape),
+                span.clone(),
             ))
-        })
-    } else {
-        // TODO: Handle non-unit enum variants
-        Err(AnyErr("Non-unit enum variants not yet supported".into()))
+        }
+        _ => {
+            // `value = "Variant"`
+            if node.as_value().is_some_and(|value| value.is_str()) {
+                let wip = select_variant(wip, node, span)?;
+                let variant = wip.selected_variant().unwrap();
+                if variant.data.kind != StructKind::Unit {
+                    return Err(to_scalar::mismatch(node, span, shape));
+                }
+                return Ok(wip);
+            }
+
+            // `value = { Variant = payload }`, or `[value.Variant]`
+            let table = node
+                .as_table_like()
+                .filter(|table| table.len() == 1)
+                .ok_or_else(|| to_scalar::mismatch(node, span, shape))?;
+            let (name, item) = table.iter().next().unwrap();
+            let key_span = table
+                .key(name)
+                .and_then(Key::span)
+                .unwrap_or_else(|| span.clone());
+            if wip.find_variant(name).is_none() {
+                return Err(DeError::new(
+                    TomlErrorKind::UnknownVariant(name.to_string()),
+                    key_span,
+                ));
+            }
+            let wip = wip.variant_named(name).map_err(reflect_err(&key_span))?;
+            let variant = wip.selected_variant().unwrap();
+            let payload = Node::from_item(item)
+                .ok_or_else(|| to_scalar::mismatch_of("none", &key_span, shape))?;
+            // implicit tables, like `Variant` in `[value.Variant.inner]`, have no span
+            let payload_span = payload.span().unwrap_or(key_span);
+            deserialize_variant_payload(wip, payload, &payload_span)
+                .map_err(|e| e.within(PathSegment::Field(variant.name)))
+        }
     }
 }
 
-fn deserialize_as_scalar<'a>(mut wip: Wip<'a>, item: &Item) -> Result<Wip<'a>, AnyErr> {
+/// Deserializes the data of the selected variant: newtype variants hold their
+/// value directly, tuple variants are arrays and struct variants tables
+fn deserialize_variant_payload<'a>(
+    wip: Wip<'a>,
+    node: Node<'_>,
+    span: &Range<usize>,
+) -> Result<Wip<'a>, DeError> {
+    let variant = wip.selected_variant().unwrap();
+    match variant.data.kind {
+        // TOML has no null, so a unit variant's data is an empty table
+        StructKind::Unit => match node.as_table_like() {
+            Some(table) if table.is_empty() => Ok(wip),
+            _ => Err(to_scalar::mismatch(node, span, wip.shape())),
+        },
+        StructKind::Tuple | StructKind::TupleStruct if variant.data.fields.len() == 1 => {
+            let wip = wip.field(0).map_err(reflect_err(span))?;
+            let wip = deserialize_node(wip, node, span)?;
+            wip.pop().map_err(reflect_err(span))
+        }
+        StructKind::Tuple | StructKind::TupleStruct => {
+            deserialize_as_array(wip, node, span, variant.data.fields.len())
+        }
+        _ => {
+            let table = node
+                .as_table_like()
+                .ok_or_else(|| to_scalar::mismatch(node, span, wip.shape()))?;
+            deserialize_fields(wip, variant.data.fields, table, span, None)
+        }
+    }
+}
+
+/// Finds the node holding the variant name stored under `tag`
+fn tagged_variant_name<'t>(
+    table: &'t dyn TableLike,
+    span: &Range<usize>,
+    tag: &'static str,
+) -> Result<Node<'t>, DeError> {
+    table
+        .get(tag)
+        .and_then(Node::from_item)
+        .ok_or_else(|| DeError::new(TomlErrorKind::MissingField(tag), span.clone()))
+}
+
+/// Selects the variant named by the string `name`, found in a table spanning `span`
+fn select_variant<'a>(
+    wip: Wip<'a>,
+    name: Node<'_>,
+    span: &Range<usize>,
+) -> Result<Wip<'a>, DeError> {
+    let name_span = name.span().unwrap_or_else(|| span.clone());
+    let variant_name = name
+        .as_str()
+        .ok_or_else(|| to_scalar::mismatch(name, &name_span, wip.shape()))?;
+    if wip.find_variant(variant_name).is_none() {
+        return Err(DeError::new(
+            TomlErrorKind::UnknownVariant(variant_name.to_string()),
+            name_span,
+        ));
+    }
+    wip.variant_named(variant_name)
+        .map_err(reflect_err(&name_span))
+}
+
+fn deserialize_as_scalar<'a>(
+    mut wip: Wip<'a>,
+    node: Node<'_>,
+    span: &Range<usize>,
+) -> Result<Wip<'a>, DeError> {
     let shape = wip.shape();
 
     if shape.is_type::<String>() {
-        let val = to_scalar::string(item)?;
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = to_scalar::string(node, span, shape)?;
+        wip = wip.put(val).map_err(reflect_err(span))?;
     } else if shape.is_type::<Cow<'_, str>>() {
-        let val = Cow::Owned(to_scalar::string(item)?);
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = Cow::Owned(to_scalar::string(node, span, shape)?);
+        wip = wip.put(val).map_err(reflect_err(span))?;
     } else if shape.is_type::<bool>() {
-        let val = to_scalar::boolean(item)?;
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = to_scalar::boolean(node, span, shape)?;
+        wip = wip.put(val).map_err(reflect_err(span))?;
     } else if shape.is_type::<f64>() {
-        let val = to_scalar::number::<f64>(item)?;
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = to_scalar::number::<f64>(node, span, shape)?;
+        wip = wip.put(val).map_err(reflect_err(span))?;
     } else if shape.is_type::<f32>() {
-        let val = to_scalar::number::<f32>(item)?;
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = to_scalar::number::<f32>(node, span, shape)?;
+        wip = wip.put(val).map_err(reflect_err(span))?;
     } else if shape.is_type::<usize>() {
-        let val = to_scalar::number::<usize>(item)?;
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = to_scalar::number::<usize>(node, span, shape)?;
+        wip = wip.put(val).map_err(reflect_err(span))?;
     } else if shape.is_type::<u128>() {
-        let val = to_scalar::number::<u128>(item)?;
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = to_scalar::number::<u128>(node, span, shape)?;
+        wip = wip.put(val).map_err(reflect_err(span))?;
     } else if shape.is_type::<u64>() {
-        let val = to_scalar::number::<u64>(item)?;
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = to_scalar::number::<u64>(node, span, shape)?;
+        wip = wip.put(val).map_err(reflect_err(span))?;
     } else if shape.is_type::<u32>() {
-        let val = to_scalar::number::<u32>(item)?;
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = to_scalar::number::<u32>(node, span, shape)?;
+        wip = wip.put(val).map_err(reflect_err(span))?;
     } else if shape.is_type::<u16>() {
-        let val = to_scalar::number::<u16>(item)?;
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = to_scalar::number::<u16>(node, span, shape)?;
+        wip = wip.put(val).map_err(reflect_err(span))?;
     } else if shape.is_type::<u8>() {
-        let val = to_scalar::number::<u8>(item)?;
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = to_scalar::number::<u8>(node, span, shape)?;
+        wip = wip.put(val).map_err(reflect_err(span))?;
     } else if shape.is_type::<isize>() {
-        let val = to_scalar::number::<isize>(item)?;
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = to_scalar::number::<isize>(node, span, shape)?;
+        wip = wip.put(val).map_err(reflect_err(span))?;
     } else if shape.is_type::<i128>() {
-        let val = to_scalar::number::<i128>(item)?;
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = to_scalar::number::<i128>(node, span, shape)?;
+        wip = wip.put(val).map_err(reflect_err(span))?;
     } else if shape.is_type::<i64>() {
-        let val = to_scalar::number::<i64>(item)?;
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = to_scalar::number::<i64>(node, span, shape)?;
+        wip = wip.put(val).map_err(reflect_err(span))?;
     } else if shape.is_type::<i32>() {
-        let val = to_scalar::number::<i32>(item)?;
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = to_scalar::number::<i32>(node, span, shape)?;
+        wip = wip.put(val).map_err(reflect_err(span))?;
     } else if shape.is_type::<i16>() {
-        let val = to_scalar::number::<i16>(item)?;
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = to_scalar::number::<i16>(node, span, shape)?;
+        wip = wip.put(val).map_err(reflect_err(span))?;
     } else if shape.is_type::<i8>() {
-        let val = to_scalar::number::<i8>(item)?;
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = to_scalar::number::<i8>(node, span, shape)?;
+        wip = wip.put(val).map_err(reflect_err(span))?;
     } else if shape.is_type::<NonZero<usize>>() {
         // TODO: create a to_scalar::nonzero_number method when we can use a trait to do so
-        let val = NonZero::new(to_scalar::number::<usize>(item)?)
-            .ok_or_else(|| AnyErr("Could not convert number to non-zero variant".into()))?;
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = NonZero::new(to_scalar::number::<usize>(node, span, shape)?)
+            .ok_or_else(|| to_scalar::invalid(span, shape))?;
+        wip = wip.put(val).map_err(reflect_err(span))?;
     } else if shape.is_type::<NonZero<u128>>() {
-        let val = NonZero::new(to_scalar::number::<u128>(item)?)
-            .ok_or_else(|| AnyErr("Could not convert number to non-zero variant".into()))?;
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = NonZero::new(to_scalar::number::<u128>(node, span, shape)?)
+            .ok_or_else(|| to_scalar::invalid(span, shape))?;
+        wip = wip.put(val).map_err(reflect_err(span))?;
     } else if shape.is_type::<NonZero<u64>>() {
-        let val = NonZero::new(to_scalar::number::<u64>(item)?)
-            .ok_or_else(|| AnyErr("Could not convert number to non-zero variant".into()))?;
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = NonZero::new(to_scalar::number::<u64>(node, span, shape)?)
+            .ok_or_else(|| to_scalar::invalid(span, shape))?;
+        wip = wip.put(val).map_err(reflect_err(span))?;
     } else if shape.is_type::<NonZero<u32>>() {
-        let val = NonZero::new(to_scalar::number::<u32>(item)?)
-            .ok_or_else(|| AnyErr("Could not convert number to non-zero variant".into()))?;
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = NonZero::new(to_scalar::number::<u32>(node, span, shape)?)
+            .ok_or_else(|| to_scalar::invalid(span, shape))?;
+        wip = wip.put(val).map_err(reflect_err(span))?;
     } else if shape.is_type::<NonZero<u16>>() {
-        let val = NonZero::new(to_scalar::number::<u16>(item)?)
-            .ok_or_else(|| AnyErr("Could not convert number to non-zero variant".into()))?;
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = NonZero::new(to_scalar::number::<u16>(node, span, shape)?)
+            .ok_or_else(|| to_scalar::invalid(span, shape))?;
+        wip = wip.put(val).map_err(reflect_err(span))?;
     } else if shape.is_type::<NonZero<u8>>() {
-        let val = NonZero::new(to_scalar::number::<u8>(item)?)
-            .ok_or_else(|| AnyErr("Could not convert number to non-zero variant".into()))?;
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = NonZero::new(to_scalar::number::<u8>(node, span, shape)?)
+            .ok_or_else(|| to_scalar::invalid(span, shape))?;
+        wip = wip.put(val).map_err(reflect_err(span))?;
     } else if shape.is_type::<NonZero<isize>>() {
-        let val = NonZero::new(to_scalar::number::<isize>(item)?)
-            .ok_or_else(|| AnyErr("Could not convert number to non-zero variant".into()))?;
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = NonZero::new(to_scalar::number::<isize>(node, span, shape)?)
+            .ok_or_else(|| to_scalar::invalid(span, shape))?;
+        wip = wip.put(val).map_err(reflect_err(span))?;
     } else if shape.is_type::<NonZero<i128>>() {
-        let val = NonZero::new(to_scalar::number::<i128>(item)?)
-            .ok_or_else(|| AnyErr("Could not convert number to non-zero variant".into()))?;
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = NonZero::new(to_scalar::number::<i128>(node, span, shape)?)
+            .ok_or_else(|| to_scalar::invalid(span, shape))?;
+        wip = wip.put(val).map_err(reflect_err(span))?;
     } else if shape.is_type::<NonZero<i64>>() {
-        let val = NonZero::new(to_scalar::number::<i64>(item)?)
-            .ok_or_else(|| AnyErr("Could not convert number to non-zero variant".into()))?;
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = NonZero::new(to_scalar::number::<i64>(node, span, shape)?)
+            .ok_or_else(|| to_scalar::invalid(span, shape))?;
+        wip = wip.put(val).map_err(reflect_err(span))?;
     } else if shape.is_type::<NonZero<i32>>() {
-        let val = NonZero::new(to_scalar::number::<i32>(item)?)
-            .ok_or_else(|| AnyErr("Could not convert number to non-zero variant".into()))?;
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = NonZero::new(to_scalar::number::<i32>(node, span, shape)?)
+            .ok_or_else(|| to_scalar::invalid(span, shape))?;
+        wip = wip.put(val).map_err(reflect_err(span))?;
     } else if shape.is_type::<NonZero<i16>>() {
-        let val = NonZero::new(to_scalar::number::<i16>(item)?)
-            .ok_or_else(|| AnyErr("Could not convert number to non-zero variant".into()))?;
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = NonZero::new(to_scalar::number::<i16>(node, span, shape)?)
+            .ok_or_else(|| to_scalar::invalid(span, shape))?;
+        wip = wip.put(val).map_err(reflect_err(span))?;
     } else if shape.is_type::<NonZero<i8>>() {
-        let val = NonZero::new(to_scalar::number::<i8>(item)?)
-            .ok_or_else(|| AnyErr("Could not convert number to non-zero variant".into()))?;
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = NonZero::new(to_scalar::number::<i8>(node, span, shape)?)
+            .ok_or_else(|| to_scalar::invalid(span, shape))?;
+        wip = wip.put(val).map_err(reflect_err(span))?;
     } else if shape.is_type::<SocketAddr>() {
-        let val = to_scalar::from_str::<SocketAddr>(item, "socket address")?;
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = to_scalar::from_str::<SocketAddr>(node, span, shape)?;
+        wip = wip.put(val).map_err(reflect_err(span))?;
     } else if shape.is_type::<IpAddr>() {
-        let val = to_scalar::from_str::<IpAddr>(item, "ip address")?;
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = to_scalar::from_str::<IpAddr>(node, span, shape)?;
+        wip = wip.put(val).map_err(reflect_err(span))?;
     } else if shape.is_type::<Ipv4Addr>() {
-        let val = to_scalar::from_str::<Ipv4Addr>(item, "ipv4 address")?;
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = to_scalar::from_str::<Ipv4Addr>(node, span, shape)?;
+        wip = wip.put(val).map_err(reflect_err(span))?;
     } else if shape.is_type::<Ipv6Addr>() {
-        let val = to_scalar::from_str::<Ipv6Addr>(item, "ipv6 address")?;
-        wip = wip.put(val).map_err(|e| AnyErr(e.to_string()))?;
+        let val = to_scalar::from_str::<Ipv6Addr>(node, span, shape)?;
+        wip = wip.put(val).map_err(reflect_err(span))?;
+    } else if matches!(shape.def, Def::Scalar(sd) if matches!(sd.affinity, ScalarAffinity::Time(_)))
+    {
+        // datetime types parse the RFC 3339 text of a TOML datetime (or a
+        // string, for the ones TOML can't represent)
+        let val = to_scalar::string(node, span, shape)?;
+        wip = wip
+            .parse(&val)
+            .map_err(|_| to_scalar::invalid(span, shape))?;
     } else {
-        return Err(AnyErr(format!("Unsupported scalar type: {}", wip.shape())));
+        return Err(DeError::new(
+            TomlErrorKind::UnsupportedShape(shape),
+            span.clone(),
+        ));
     }
     Ok(wip)
 }
@@ -0,0 +1,481 @@
+use std::{
+    borrow::Cow,
+    collections::HashSet,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    num::NonZero,
+};
+
+use facet_core::{Def, EnumTagging, Facet, ScalarAffinity, StructKind};
+use facet_reflect::{Peek, PeekEnum};
+use toml_edit::{
+    Array, ArrayOfTables, Datetime, DocumentMut, InlineTable, Item, Table, TableLike, Value,
+};
+
+use crate::TomlSerializeError;
+
+/// Serializes a value to a TOML document. The value must serialize to a
+/// table, like a struct or a map.
+pub fn to_string<'a, T: Facet<'a>>(value: &T) -> Result<String, TomlSerializeError> {
+    peek_to_string(&Peek::new(value))
+}
+
+/// Serializes a Peek instance to a TOML document
+pub fn peek_to_string(peek: &Peek<'_>) -> Result<String, TomlSerializeError> {
+    let entries = root_entries(peek)?;
+    let mut document = DocumentMut::new();
+    for (key, node) in entries {
+        document.insert(&key, node.into_item());
+    }
+    Ok(document.to_string())
+}
+
+/// Writes `value` into an existing document, keeping its comments, key order
+/// and whitespace.
+///
+/// Values that didn't change are left as they are written (so `0x10` stays
+/// `0x10`), changed values are replaced in place, new keys are appended to
+/// their table and keys the value no longer has (like `None` fields) are
+/// removed.
+pub fn update_document<'a, T: Facet<'a>>(
+    document: &mut DocumentMut,
+    value: &T,
+) -> Result<(), TomlSerializeError> {
+    let entries = root_entries(&Peek::new(value))?;
+    merge_table(document.as_table_mut(), entries, false);
+    Ok(())
+}
+
+fn root_entries(peek: &Peek<'_>) -> Result<Vec<(String, Node)>, TomlSerializeError> {
+    match Node::from_peek(peek)? {
+        Node::Table(entries) => Ok(entries),
+        _ => Err(TomlSerializeError::Unsupported(format!(
+            "A TOML document is a table, {} can't be one",
+            peek.shape()
+        ))),
+    }
+}
+
+/// A value ready to be written out. Whether tables and arrays of tables are
+/// written inline depends on where they end up, see [`Node::into_item`].
+enum Node {
+    Value(Value),
+    Table(Vec<(String, Node)>),
+    Array(Vec<Node>),
+}
+
+impl Node {
+    fn from_peek(peek: &Peek<'_>) -> Result<Self, TomlSerializeError> {
+        let shape = peek.shape();
+        let node = match shape.def {
+            Def::Scalar(_) => Node::Value(scalar_to_value(peek)?),
+            Def::Struct(_) => {
+                let struct_peek = peek
+                    .into_struct()
+                    .map_err(|e| TomlSerializeError::Unsupported(format!("Not a struct: {}", e)))?;
+                match struct_peek.def().kind {
+                    StructKind::Unit => Node::Table(Vec::new()),
+                    StructKind::Tuple | StructKind::TupleStruct => {
+                        Node::array(struct_peek.fields().map(|(_, field_peek)| field_peek))?
+                    }
+                    _ => Node::table(
+                        struct_peek
+                            .fields()
+                            .map(|(field, field_peek)| (field.name.to_string(), field_peek)),
+                    )?,
+                }
+            }
+            Def::List(_) => {
+                let list_peek = peek
+                    .into_list()
+                    .map_err(|e| TomlSerializeError::Unsupported(format!("Not a list: {}", e)))?;
+                Node::array(list_peek.iter())?
+            }
+            Def::Array(_) => {
+                let array_peek = peek
+                    .into_array()
+                    .map_err(|e| TomlSerializeError::Unsupported(format!("Not an array: {}", e)))?;
+                Node::array(array_peek.iter())?
+            }
+            Def::Slice(_) => {
+                let slice_peek = peek
+                    .into_slice()
+                    .map_err(|e| TomlSerializeError::Unsupported(format!("Not a slice: {}", e)))?;
+                Node::array(slice_peek.iter())?
+            }
+            Def::Map(_) => {
+                let map_peek = peek
+                    .into_map()
+                    .map_err(|e| TomlSerializeError::Unsupported(format!("Not a map: {}", e)))?;
+                let entries = map_peek
+                    .iter()
+                    .map(|(key, value)| Ok((key_to_string(&key)?, value)))
+                    .collect::<Result<Vec<_>, TomlSerializeError>>()?;
+                Node::table(entries.into_iter())?
+            }
+            Def::Option(_) => {
+                let option_peek = peek.into_option().map_err(|e| {
+                    TomlSerializeError::Unsupported(format!("Not an option: {}", e))
+                })?;
+                match option_peek.value() {
+                    Some(value) => Node::from_peek(&value)?,
+                    // tables leave out `None` values, see `Node::table`
+                    None => {
+                        return Err(TomlSerializeError::Unsupported(format!(
+                            "TOML has no null, so a {} in an array must be Some",
+                            shape
+                        )));
+                    }
+                }
+            }
+            Def::SmartPointer(_) => {
+                let smart_pointer_peek = peek.into_smart_pointer().map_err(|e| {
+                    TomlSerializeError::Unsupported(format!("Not a smart pointer: {}", e))
+                })?;
+                let inner = smart_pointer_peek.borrow_inner().ok_or_else(|| {
+                    TomlSerializeError::Unsupported(format!(
+                        "Cannot borrow from smart pointer: {}",
+                        shape
+                    ))
+                })?;
+                Node::from_peek(&inner)?
+            }
+            Def::Enum(_) => {
+                let enum_peek = peek
+                    .into_enum()
+                    .map_err(|e| TomlSerializeError::Unsupported(format!("Not an enum: {}", e)))?;
+                Node::from_enum(peek, enum_peek)?
+            }
+            _ => {
+                return Err(TomlSerializeError::Unsupported(format!(
+                    "Unsupported type: {}",
+                    shape
+                )));
+            }
+        };
+        Ok(node)
+    }
+
+    /// Enums are written in the representation selected by their tagging
+    /// attributes, the way facet-toml reads them
+    fn from_enum(peek: &Peek<'_>, enum_peek: PeekEnum<'_>) -> Result<Self, TomlSerializeError> {
+        let variant = enum_peek.active_variant();
+        let is_unit = variant.data.kind == StructKind::Unit;
+        let name = || Node::Value(Value::from(variant.name));
+
+        let node = match peek.shape().enum_tagging() {
+            EnumTagging::Untagged => Node::from_variant_payload(enum_peek)?,
+            EnumTagging::External if is_unit => name(),
+            EnumTagging::External => Node::Table(vec![(
+                variant.name.to_string(),
+                Node::from_variant_payload(enum_peek)?,
+            )]),
+            EnumTagging::Adjacent { tag, content } => {
+                let mut entries = vec![(tag.to_string(), name())];
+                if !is_unit {
+                    entries.push((content.to_string(), Node::from_variant_payload(enum_peek)?));
+                }
+                Node::Table(entries)
+            }
+            EnumTagging::Internal { tag } => {
+                let Some(fields) = enum_peek.internally_tagged_fields() else {
+                    return Err(TomlSerializeError::Unsupported(format!(
+                        "Internally tagged variant {}::{} must be a unit, struct or newtype struct variant",
+                        peek.shape(),
+                        variant.name
+                    )));
+                };
+                let Node::Table(mut entries) = Node::table(
+                    fields.map(|(field, field_peek)| (field.name.to_string(), field_peek)),
+                )?
+                else {
+                    unreachable!()
+                };
+                entries.insert(0, (tag.to_string(), name()));
+                Node::Table(entries)
+            }
+            _ => {
+                return Err(TomlSerializeError::Unsupported(format!(
+                    "Unsupported enum tagging for {}",
+                    peek.shape()
+                )));
+            }
+        };
+        Ok(node)
+    }
+
+    /// The data of the active variant: newtype variants are their value, tuple
+    /// variants arrays and struct variants tables. TOML has no null, so the
+    /// data of a unit variant is an empty table.
+    fn from_variant_payload(enum_peek: PeekEnum<'_>) -> Result<Self, TomlSerializeError> {
+        let variant = enum_peek.active_variant();
+        match variant.data.kind {
+            StructKind::Unit => Ok(Node::Table(Vec::new())),
+            StructKind::Tuple | StructKind::TupleStruct if variant.data.fields.len() == 1 => {
+                let field = enum_peek.field(0).ok_or_else(|| {
+                    TomlSerializeError::Unsupported("Failed to access enum field".to_string())
+                })?;
+                Node::from_peek(&field)
+            }
+            StructKind::Tuple | StructKind::TupleStruct => {
+                Node::array(enum_peek.fields().map(|(_, field_peek)| field_peek))
+            }
+            _ => Node::table(
+                enum_peek
+                    .fields()
+                    .map(|(field, field_peek)| (field.name.to_string(), field_peek)),
+            ),
+        }
+    }
+
+    fn array<'mem>(items: impl Iterator<Item = Peek<'mem>>) -> Result<Self, TomlSerializeError> {
+        let items = items
+            .map(|item| Node::from_peek(&item))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Node::Array(items))
+    }
+
+    /// Builds a table, leaving out keys whose value is `None`
+    fn table<'mem>(
+        entries: impl Iterator<Item = (String, Peek<'mem>)>,
+    ) -> Result<Self, TomlSerializeError> {
+        let mut table = Vec::new();
+        for (key, value) in entries {
+            if !is_none(&value) {
+                table.push((key, Node::from_peek(&value)?));
+            }
+        }
+        Ok(Node::Table(table))
+    }
+
+    fn is_table(&self) -> bool {
+        matches!(self, Node::Table(_))
+    }
+
+    /// Tables become `[tables]` and arrays of tables `[[tables]]`, unless
+    /// they're inside a value
+    fn into_item(self) -> Item {
+        match self {
+            Node::Table(entries) => Item::Table(Node::into_table(entries)),
+            Node::Array(items) if !items.is_empty() && items.iter().all(Node::is_table) => {
+                let mut tables = ArrayOfTables::new();
+                for item in items {
+                    let Node::Table(entries) = item else {
+                        unreachable!()
+                    };
+                    tables.push(Node::into_table(entries));
+                }
+                Item::ArrayOfTables(tables)
+            }
+            node => Item::Value(node.into_value()),
+        }
+    }
+
+    fn into_table(entries: Vec<(String, Node)>) -> Table {
+        let mut table = Table::new();
+        // a table holding nothing but tables doesn't need its own header
+        table.set_implicit(!entries.is_empty() && entries.iter().all(|(_, node)| node.is_table()));
+        for (key, node) in entries {
+            table.insert(&key, node.into_item());
+        }
+        table
+    }
+
+    fn into_value(self) -> Value {
+        match self {
+            Node::Value(value) => value,
+            Node::Table(entries) => Value::InlineTable(
+                entries
+                    .into_iter()
+                    .map(|(key, node)| (key, node.into_value()))
+                    .collect::<InlineTable>(),
+            ),
+            Node::Array(items) => {
+                Value::Array(items.into_iter().map(Node::into_value).collect::<Array>())
+            }
+        }
+    }
+}
+
+/// Whether `peek` is a `None`, possibly behind other options
+fn is_none(peek: &Peek<'_>) -> bool {
+    match peek.into_option() {
+        Ok(option_peek) => option_peek.value().is_none_or(|inner| is_none(&inner)),
+        Err(_) => false,
+    }
+}
+
+/// TOML keys are strings: other scalar keys are written as they display
+fn key_to_string(peek: &Peek<'_>) -> Result<String, TomlSerializeError> {
+    match Node::from_peek(peek)? {
+        Node::Value(Value::String(s)) => Ok(s.into_value()),
+        Node::Value(value) => Ok(value.to_string().trim().to_string()),
+        _ => Err(TomlSerializeError::Unsupported(format!(
+            "Map keys must be scalar types, got: {}",
+            peek.shape()
+        ))),
+    }
+}
+
+/// Writes the `entries` into an existing table. `inline` is true for inline
+/// tables, where new tables must be written inline too.
+fn merge_table(table: &mut dyn TableLike, entries: Vec<(String, Node)>, inline: bool) {
+    let keys: HashSet<&str> = entries.iter().map(|(key, _)| key.as_str()).collect();
+    let removed: Vec<String> = table
+        .iter()
+        .map(|(key, _)| key)
+        .filter(|key| !keys.contains(key))
+        .map(str::to_string)
+        .collect();
+    for key in removed {
+        table.remove(&key);
+    }
+
+    for (key, node) in entries {
+        match table.get_mut(&key) {
+            Some(item) => merge_item(item, node),
+            None if inline => {
+                table.insert(&key, Item::Value(node.into_value()));
+            }
+            None => {
+                table.insert(&key, node.into_item());
+            }
+        }
+    }
+}
+
+fn merge_item(item: &mut Item, node: Node) {
+    match (item, node) {
+        (Item::Table(table), Node::Table(entries)) => merge_table(table, entries, false),
+        (Item::ArrayOfTables(tables), Node::Array(items))
+            if tables.len() == items.len() && items.iter().all(Node::is_table) =>
+        {
+            for (table, item) in tables.iter_mut().zip(items) {
+                let Node::Table(entries) = item else {
+                    unreachable!()
+                };
+                merge_table(table, entries, false);
+            }
+        }
+        (Item::Value(value), node) => merge_value(value, node),
+        (item, node) => *item = node.into_item(),
+    }
+}
+
+fn merge_value(value: &mut Value, node: Node) {
+    match (value, node) {
+        (Value::InlineTable(table), Node::Table(entries)) => merge_table(table, entries, true),
+        (Value::Array(array), Node::Array(items)) if array.len() == items.len() => {
+            for (element, item) in array.iter_mut().zip(items) {
+                merge_value(element, item);
+            }
+        }
+        (value, node) => {
+            let new = node.into_value();
+            if !same_value(value, &new) {
+                // keep the whitespace and comments around the old value
+                let decor = value.decor().clone();
+                *value = new;
+                *value.decor_mut() = decor;
+            }
+        }
+    }
+}
+
+/// Whether two values are equal, however they're written
+fn same_value(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::String(a), Value::String(b)) => a.value() == b.value(),
+        (Value::Integer(a), Value::Integer(b)) => a.value() == b.value(),
+        (Value::Float(a), Value::Float(b)) => a.value().to_bits() == b.value().to_bits(),
+        (Value::Boolean(a), Value::Boolean(b)) => a.value() == b.value(),
+        (Value::Datetime(a), Value::Datetime(b)) => a.value() == b.value(),
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| same_value(a, b))
+        }
+        (Value::InlineTable(a), Value::InlineTable(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(key, a)| b.get(key).is_some_and(|b| same_value(a, b)))
+        }
+        _ => false,
+    }
+}
+
+/// TOML integers are 64-bit signed integers
+fn integer_to_value(peek: &Peek<'_>) -> Result<Value, TomlSerializeError> {
+    let display = peek.to_string();
+    display.parse::<i64>().map(Value::from).map_err(|_| {
+        TomlSerializeError::Unsupported(format!(
+            "{} doesn't fit in a TOML integer (a {})",
+            display,
+            peek.shape()
+        ))
+    })
+}
+
+fn scalar_to_value(peek: &Peek<'_>) -> Result<Value, TomlSerializeError> {
+    let shape = peek.shape();
+    let value = if shape.is_type::<bool>() {
+        Value::from(*peek.get::<bool>().unwrap())
+    } else if shape.is_type::<String>() {
+        Value::from(peek.get::<String>().unwrap().as_str())
+    } else if shape.is_type::<&str>() {
+        Value::from(*peek.get::<&str>().unwrap())
+    } else if shape.is_type::<Cow<'_, str>>() {
+        Value::from(peek.get::<Cow<'_, str>>().unwrap().as_ref())
+    } else if shape.is_type::<char>() {
+        Value::from(peek.get::<char>().unwrap().to_string())
+    } else if shape.is_type::<f64>() {
+        Value::from(*peek.get::<f64>().unwrap())
+    } else if shape.is_type::<f32>() {
+        let value = *peek.get::<f32>().unwrap();
+        Value::from(value.to_string().parse::<f64>().unwrap_or(value as f64))
+    } else if shape.is_type::<u8>()
+        || shape.is_type::<u16>()
+        || shape.is_type::<u32>()
+        || shape.is_type::<u64>()
+        || shape.is_type::<u128>()
+        || shape.is_type::<usize>()
+        || shape.is_type::<i8>()
+        || shape.is_type::<i16>()
+        || shape.is_type::<i32>()
+        || shape.is_type::<i64>()
+        || shape.is_type::<i128>()
+        || shape.is_type::<isize>()
+        || shape.is_type::<NonZero<u8>>()
+        || shape.is_type::<NonZero<u16>>()
+        || shape.is_type::<NonZero<u32>>()
+        || shape.is_type::<NonZero<u64>>()
+        || shape.is_type::<NonZero<u128>>()
+        || shape.is_type::<NonZero<usize>>()
+        || shape.is_type::<NonZero<i8>>()
+        || shape.is_type::<NonZero<i16>>()
+        || shape.is_type::<NonZero<i32>>()
+        || shape.is_type::<NonZero<i64>>()
+        || shape.is_type::<NonZero<i128>>()
+        || shape.is_type::<NonZero<isize>>()
+    {
+        integer_to_value(peek)?
+    } else if shape.is_type::<SocketAddr>()
+        || shape.is_type::<IpAddr>()
+        || shape.is_type::<Ipv4Addr>()
+        || shape.is_type::<Ipv6Addr>()
+    {
+        Value::from(peek.to_string())
+    } else if matches!(shape.def, Def::Scalar(sd) if matches!(sd.affinity, ScalarAffinity::Time(_)))
+    {
+        // datetime types are written as TOML datetimes when they display as
+        // RFC 3339, and as strings otherwise
+        let display = peek.to_string();
+        match display.parse::<Datetime>() {
+            Ok(datetime) => Value::from(datetime),
+            Err(_) => Value::from(display),
+        }
+    } else {
+        return Err(TomlSerializeError::Unsupported(format!(
+            "Unsupported scalar type: {}",
+            shape
+        )));
+    };
+    Ok(value)
+}
@@ -1,60 +1,103 @@
 //! Convert TOML values to it's scalar counterpart.
 
-use std::{fmt::Display, str::FromStr};
+use std::{ops::Range, str::FromStr};
 
+use facet_core::{Def, NumberBits, ScalarAffinity, Shape};
 use num_traits::cast::NumCast;
-use toml_edit::{Item, Value};
+use toml_edit::Value;
 
-use crate::AnyErr;
+use crate::{
+    Node,
+    error::{DeError, TomlErrorKind},
+};
 
 /// Try to convert a TOML integer or float to a Rust number.
 ///
 /// Applies to all Rust scalars supported by the `num` crate.
-pub(crate) fn number<T: NumCast>(item: &Item) -> Result<T, AnyErr> {
-    let v = item
-        .as_value()
-        .ok_or_else(|| format!("Expected value, got: {}", item.type_name()))?;
-
-    match v {
-        Value::Float(r) => Ok(T::from(*r.value())
-            .ok_or_else(|| format!("Cannot convert float to {}", std::any::type_name::<T>()))?),
-        Value::Integer(i) => Ok(T::from(*i.value())
-            .ok_or_else(|| format!("Cannot convert integer to {}", std::any::type_name::<T>()))?),
-        _ => Err(format!("Cannot convert {} to a number", v.type_name()).into()),
+pub(crate) fn number<T: NumCast>(
+    node: Node<'_>,
+    span: &Range<usize>,
+    shape: &'static Shape,
+) -> Result<T, DeError> {
+    match node.as_value() {
+        // never drop a fraction to fit an integer
+        Some(Value::Float(r)) => Some(*r.value())
+            .filter(|f| !is_integer(shape) || f.fract() == 0.0)
+            .and_then(T::from)
+            .ok_or_else(|| invalid(span, shape)),
+        Some(Value::Integer(i)) => T::from(*i.value()).ok_or_else(|| invalid(span, shape)),
+        _ => Err(mismatch(node, span, shape)),
     }
 }
 
+/// Whether `shape` is an integer type, which can't hold a fraction
+fn is_integer(shape: &Shape) -> bool {
+    matches!(shape.def, Def::Scalar(sd) if matches!(
+        sd.affinity,
+        ScalarAffinity::Number(na) if matches!(na.bits, NumberBits::Integer { .. })
+    ))
+}
+
 /// Try to convert a TOML boolean to a Rust boolean.
-pub(crate) fn boolean(item: &Item) -> Result<bool, AnyErr> {
-    let v = item
-        .as_value()
-        .ok_or_else(|| format!("Expected value, got: {}", item.type_name()))?;
-
-    match v {
-        Value::Boolean(boolean) => Ok(*boolean.value()),
-        _ => Err(format!("Cannot convert {} to a bool", v.type_name()).into()),
+pub(crate) fn boolean(
+    node: Node<'_>,
+    span: &Range<usize>,
+    shape: &'static Shape,
+) -> Result<bool, DeError> {
+    match node.as_value() {
+        Some(Value::Boolean(boolean)) => Ok(*boolean.value()),
+        _ => Err(mismatch(node, span, shape)),
     }
 }
 
 /// Try to convert a TOML string to a Rust string.
-pub(crate) fn string(item: &Item) -> Result<String, AnyErr> {
-    Ok(item
-        .as_str()
-        .ok_or_else(|| format!("Expected string, got: {}", item.type_name()))?
-        .to_string())
+///
+/// Datetimes are converted to their RFC 3339 text.
+pub(crate) fn string(
+    node: Node<'_>,
+    span: &Range<usize>,
+    shape: &'static Shape,
+) -> Result<String, DeError> {
+    match node.as_value() {
+        Some(Value::String(s)) => Ok(s.value().to_string()),
+        Some(Value::Datetime(datetime)) => Ok(datetime.value().to_string()),
+        _ => Err(mismatch(node, span, shape)),
+    }
 }
 
 /// Try to convert a TOML string to a Rust type that implements `FromStr`.
-pub(crate) fn from_str<T>(item: &Item, type_name: &'static str) -> Result<T, AnyErr>
-where
-    T: FromStr,
-    T::Err: Display,
-{
-    let string = item
-        .as_str()
-        .ok_or_else(|| format!("Expected string, got: {}", item.type_name()))?;
-
-    Ok(string
+pub(crate) fn from_str<T: FromStr>(
+    node: Node<'_>,
+    span: &Range<usize>,
+    shape: &'static Shape,
+) -> Result<T, DeError> {
+    node.as_str()
+        .ok_or_else(|| mismatch(node, span, shape))?
         .parse()
-        .map_err(|e: T::Err| format!("Cannot convert string to {}: {e}", type_name))?)
+        .map_err(|_| invalid(span, shape))
+}
+
+/// The node has the wrong type for `shape`
+pub(crate) fn mismatch(node: Node<'_>, span: &Range<usize>, shape: &'static Shape) -> DeError {
+    mismatch_of(node.type_name(), span, shape)
+}
+
+/// A value of type `got` was found where a `shape` was expected
+pub(crate) fn mismatch_of(
+    got: &'static str,
+    span: &Range<usize>,
+    shape: &'static Shape,
+) -> DeError {
+    DeError::new(
+        TomlErrorKind::TypeMismatch {
+            expected: shape,
+            got,
+        },
+        span.clone(),
+    )
+}
+
+/// The node has the right type, but a value `shape` can't hold
+pub(crate) fn invalid(span: &Range<usize>, shape: &'static Shape) -> DeError {
+    DeError::new(TomlErrorKind::InvalidValue(shape), span.clone())
 }
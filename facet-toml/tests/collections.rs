@@ -0,0 +1,223 @@
+//! Tests for arrays, arrays of tables, maps and options.
+
+use std::collections::{BTreeMap, HashMap};
+
+use facet::Facet;
+use facet_toml::TomlErrorKind;
+
+#[test]
+fn test_array_to_vec() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        numbers: Vec<u32>,
+        names: Vec<String>,
+        nested: Vec<Vec<i8>>,
+        empty: Vec<bool>,
+    }
+
+    assert_eq!(
+        facet_toml::from_str::<Root>(
+            "numbers = [1, 2, 3]\nnames = ['a', \"b\"]\nnested = [[1], [-2, 3]]\nempty = []"
+        )
+        .unwrap(),
+        Root {
+            numbers: vec![1, 2, 3],
+            names: vec!["a".to_string(), "b".to_string()],
+            nested: vec![vec![1], vec![-2, 3]],
+            empty: vec![],
+        }
+    );
+
+    let toml = "numbers = [1, 'two']\nnames = []\nnested = []\nempty = []";
+    let err = facet_toml::from_str::<Root>(toml).unwrap_err();
+    assert_eq!(err.path(), "numbers[1]");
+    assert_eq!(&toml[err.span()], "'two'");
+
+    assert!(
+        facet_toml::from_str::<Root>("numbers = 1\nnames = []\nnested = []\nempty = []").is_err()
+    );
+}
+
+#[test]
+fn test_array_of_tables() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Dependency {
+        name: String,
+        version: String,
+        optional: Option<bool>,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Manifest {
+        dependency: Vec<Dependency>,
+    }
+
+    let toml = r#"
+[[dependency]]
+name = "serde"
+version = "1.0"
+
+[[dependency]]
+name = "log"
+version = "0.4"
+optional = true
+"#;
+    assert_eq!(
+        facet_toml::from_str::<Manifest>(toml).unwrap(),
+        Manifest {
+            dependency: vec![
+                Dependency {
+                    name: "serde".to_string(),
+                    version: "1.0".to_string(),
+                    optional: None,
+                },
+                Dependency {
+                    name: "log".to_string(),
+                    version: "0.4".to_string(),
+                    optional: Some(true),
+                },
+            ]
+        }
+    );
+
+    // an array of inline tables is the same thing
+    assert_eq!(
+        facet_toml::from_str::<Manifest>("dependency = [{ name = 'serde', version = '1.0' }]")
+            .unwrap()
+            .dependency
+            .len(),
+        1
+    );
+
+    let err = facet_toml::from_str::<Manifest>("[[dependency]]\nname = 'serde'\n").unwrap_err();
+    assert!(matches!(err.kind(), TomlErrorKind::MissingField("version")));
+    assert_eq!(err.path(), "dependency[0]");
+}
+
+#[test]
+fn test_nested_array_of_tables() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Target {
+        name: String,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Workspace {
+        bin: Vec<Target>,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        workspace: Workspace,
+    }
+
+    let toml = "[[workspace.bin]]\nname = 'a'\n[[workspace.bin]]\nname = 'b'\n";
+    assert_eq!(
+        facet_toml::from_str::<Root>(toml).unwrap(),
+        Root {
+            workspace: Workspace {
+                bin: vec![
+                    Target {
+                        name: "a".to_string()
+                    },
+                    Target {
+                        name: "b".to_string()
+                    },
+                ]
+            }
+        }
+    );
+}
+
+#[test]
+fn test_table_to_map() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        features: BTreeMap<String, Vec<String>>,
+        ports: HashMap<String, u16>,
+        ids: BTreeMap<u32, String>,
+    }
+
+    let toml = r#"
+ids = { 1 = "one", 20 = "twenty" }
+
+[features]
+default = ["std"]
+std = []
+
+[ports]
+http = 80
+https = 443
+"#;
+    assert_eq!(
+        facet_toml::from_str::<Root>(toml).unwrap(),
+        Root {
+            features: BTreeMap::from([
+                ("default".to_string(), vec!["std".to_string()]),
+                ("std".to_string(), vec![]),
+            ]),
+            ports: HashMap::from([("http".to_string(), 80), ("https".to_string(), 443)]),
+            ids: BTreeMap::from([(1, "one".to_string()), (20, "twenty".to_string())]),
+        }
+    );
+
+    let toml = "ids = { one = 'one' }\nfeatures = {}\nports = {}\n";
+    let err = facet_toml::from_str::<Root>(toml).unwrap_err();
+    assert!(matches!(err.kind(), TomlErrorKind::InvalidValue(_)));
+    assert_eq!(&toml[err.span()], "one");
+
+    let toml = "ids = {}\nfeatures = {}\nports = { http = 80000 }\n";
+    let err = facet_toml::from_str::<Root>(toml).unwrap_err();
+    assert_eq!(err.path(), "ports.http");
+}
+
+#[test]
+fn test_option() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        set: Option<u32>,
+        unset: Option<u32>,
+        list: Option<Vec<String>>,
+    }
+
+    assert_eq!(
+        facet_toml::from_str::<Root>("set = 1\nlist = ['a']").unwrap(),
+        Root {
+            set: Some(1),
+            unset: None,
+            list: Some(vec!["a".to_string()]),
+        }
+    );
+    assert_eq!(
+        facet_toml::from_str::<Root>("").unwrap(),
+        Root {
+            set: None,
+            unset: None,
+            list: None,
+        }
+    );
+}
+
+#[test]
+fn test_fixed_array() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        rgb: [u8; 3],
+    }
+
+    assert_eq!(
+        facet_toml::from_str::<Root>("rgb = [255, 128, 0]").unwrap(),
+        Root { rgb: [255, 128, 0] }
+    );
+
+    let err = facet_toml::from_str::<Root>("rgb = [255, 128]").unwrap_err();
+    assert!(matches!(
+        err.kind(),
+        TomlErrorKind::WrongLength {
+            expected: 3,
+            got: 2
+        }
+    ));
+    let err = facet_toml::from_str::<Root>("rgb = [255, 128, 256]").unwrap_err();
+    assert_eq!(err.path(), "rgb[2]");
+}
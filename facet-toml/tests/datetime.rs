@@ -0,0 +1,112 @@
+//! Tests for TOML datetimes.
+
+use std::{alloc::Layout, fmt, str::FromStr};
+
+use facet::{ConstTypeId, Def, Facet, ScalarAffinity, ScalarDef, Shape, value_vtable};
+use facet_toml::TomlErrorKind;
+
+/// A datetime scalar, like the ones of datetime crates: it parses and displays
+/// RFC 3339 text and has a time affinity
+#[derive(Debug, Clone, PartialEq)]
+struct Timestamp(String);
+
+impl FromStr for Timestamp {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with(|c: char| c.is_ascii_digit()) {
+            Ok(Timestamp(s.to_string()))
+        } else {
+            Err("not a timestamp")
+        }
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+unsafe impl Facet<'_> for Timestamp {
+    const SHAPE: &'static Shape = &const {
+        Shape::builder()
+            .id(ConstTypeId::of::<Self>())
+            .layout(Layout::new::<Self>())
+            .def(Def::Scalar(
+                ScalarDef::builder()
+                    .affinity(ScalarAffinity::time().build())
+                    .build(),
+            ))
+            .vtable(value_vtable!(Timestamp, |f, _opts| write!(f, "Timestamp")))
+            .build()
+    };
+}
+
+#[test]
+fn test_deserialize() {
+    #[derive(Debug, Facet)]
+    struct Window {
+        starts: Timestamp,
+        day: Timestamp,
+        at: Timestamp,
+        local: Timestamp,
+        note: String,
+    }
+
+    let toml = "starts = 2025-03-01T02:00:00Z
+day = 2025-03-01
+at = 02:30:00
+local = 2025-03-01T02:00:00.5
+note = 1979-05-27T07:32:00-08:00
+";
+    let window = facet_toml::from_str::<Window>(toml).unwrap();
+    assert_eq!(window.starts.0, "2025-03-01T02:00:00Z");
+    assert_eq!(window.day.0, "2025-03-01");
+    assert_eq!(window.at.0, "02:30:00");
+    assert_eq!(window.local.0, "2025-03-01T02:00:00.5");
+    assert_eq!(window.note, "1979-05-27T07:32:00-08:00");
+
+    let toml = toml.replace("day = 2025-03-01", "day = 'tomorrow'");
+    let err = facet_toml::from_str::<Window>(&toml).unwrap_err();
+    assert!(matches!(err.kind(), TomlErrorKind::InvalidValue(_)));
+    assert_eq!(err.path(), "day");
+
+    let toml = toml.replace("day = 'tomorrow'", "day = 20250301");
+    let err = facet_toml::from_str::<Window>(&toml).unwrap_err();
+    assert!(matches!(
+        err.kind(),
+        TomlErrorKind::TypeMismatch { got: "integer", .. }
+    ));
+}
+
+#[test]
+fn test_serialize() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Window {
+        starts: Timestamp,
+        day: Timestamp,
+        at: Timestamp,
+        local: Timestamp,
+        note: String,
+    }
+
+    let window = Window {
+        starts: Timestamp("2025-03-01T02:00:00Z".to_string()),
+        day: Timestamp("2025-03-01".to_string()),
+        at: Timestamp("02:30:00".to_string()),
+        local: Timestamp("2025-03-01T02:00:00.5".to_string()),
+        note: "1979-05-27T07:32:00-08:00".to_string(),
+    };
+    let toml = facet_toml::to_string(&window).unwrap();
+    assert_eq!(
+        toml,
+        "starts = 2025-03-01T02:00:00Z
+day = 2025-03-01
+at = 02:30:00
+local = 2025-03-01T02:00:00.5
+note = \"1979-05-27T07:32:00-08:00\"
+"
+    );
+    assert_eq!(facet_toml::from_str::<Window>(&toml).unwrap(), window);
+}
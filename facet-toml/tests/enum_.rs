@@ -1,6 +1,7 @@
 //! Tests for TOML values to structs.
 
 use facet::Facet;
+use facet_toml::TomlErrorKind;
 
 #[test]
 fn test_unit_only_enum() {
@@ -53,3 +54,196 @@ fn test_single_value_on_non_unit_enum() {
     );
     assert!(facet_toml::from_str::<Root>("value = 'VariantB'").is_err());
 }
+
+#[derive(Debug, Facet, PartialEq)]
+struct Bucket {
+    name: String,
+    region: Option<String>,
+}
+
+#[test]
+fn test_externally_tagged() {
+    #[derive(Debug, Facet, PartialEq)]
+    #[repr(u8)]
+    enum Storage {
+        Memory,
+        Disk(String),
+        Mirror(String, String),
+        S3 {
+            bucket: String,
+            prefix: Option<String>,
+        },
+        Gcs(Bucket),
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        primary: Storage,
+        backups: Vec<Storage>,
+    }
+
+    let toml = r#"
+backups = [
+    "Memory",
+    { Disk = "/var/backup" },
+    { Mirror = ["/a", "/b"] },
+    { Gcs = { name = "archive" } },
+]
+
+[primary.S3]
+bucket = "data"
+"#;
+    assert_eq!(
+        facet_toml::from_str::<Root>(toml).expect("Failed to parse TOML"),
+        Root {
+            primary: Storage::S3 {
+                bucket: "data".to_string(),
+                prefix: None,
+            },
+            backups: vec![
+                Storage::Memory,
+                Storage::Disk("/var/backup".to_string()),
+                Storage::Mirror("/a".to_string(), "/b".to_string()),
+                Storage::Gcs(Bucket {
+                    name: "archive".to_string(),
+                    region: None,
+                }),
+            ],
+        },
+    );
+
+    let toml = "backups = []\n[primary.S3]\nbukcet = 'data'\n";
+    let err = facet_toml::from_str::<Root>(toml).unwrap_err();
+    assert!(matches!(err.kind(), TomlErrorKind::MissingField("bucket")));
+    assert_eq!(err.path(), "primary.S3");
+
+    let toml = "backups = []\n[primary.Tape]\nbucket = 'data'\n";
+    let err = facet_toml::from_str::<Root>(toml).unwrap_err();
+    assert!(matches!(err.kind(), TomlErrorKind::UnknownVariant(name) if name == "Tape"));
+    assert_eq!(&toml[err.span()], "Tape");
+
+    // a single key only
+    let toml = "backups = []\nprimary = { Memory = {}, Disk = '/' }\n";
+    assert!(facet_toml::from_str::<Root>(toml).is_err());
+}
+
+#[test]
+fn test_internally_tagged() {
+    #[derive(Debug, Facet, PartialEq)]
+    #[repr(u8)]
+    #[facet(tag = "type")]
+    enum Storage {
+        Memory,
+        S3 { bucket: String },
+        Gcs(Bucket),
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        storage: Vec<Storage>,
+    }
+
+    let toml = r#"
+[[storage]]
+type = "Memory"
+
+[[storage]]
+type = "S3"
+bucket = "data"
+
+[[storage]]
+name = "archive"
+type = "Gcs"
+region = "eu"
+"#;
+    assert_eq!(
+        facet_toml::from_str::<Root>(toml).expect("Failed to parse TOML"),
+        Root {
+            storage: vec![
+                Storage::Memory,
+                Storage::S3 {
+                    bucket: "data".to_string()
+                },
+                Storage::Gcs(Bucket {
+                    name: "archive".to_string(),
+                    region: Some("eu".to_string()),
+                }),
+            ],
+        },
+    );
+
+    let err = facet_toml::from_str::<Root>("[[storage]]\nbucket = 'data'\n").unwrap_err();
+    assert!(matches!(err.kind(), TomlErrorKind::MissingField("type")));
+    assert_eq!(err.path(), "storage[0]");
+}
+
+#[test]
+fn test_adjacently_tagged() {
+    #[derive(Debug, Facet, PartialEq)]
+    #[repr(u8)]
+    #[facet(tag = "t", content = "c")]
+    enum Message {
+        Ping,
+        Say(String),
+        Move(i32, i32),
+    }
+
+    let toml = r#"
+messages = [
+    { t = "Ping" },
+    { c = "hello", t = "Say" },
+    { t = "Move", c = [1, 2] },
+]
+"#;
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        messages: Vec<Message>,
+    }
+
+    assert_eq!(
+        facet_toml::from_str::<Root>(toml).expect("Failed to parse TOML"),
+        Root {
+            messages: vec![
+                Message::Ping,
+                Message::Say("hello".to_string()),
+                Message::Move(1, 2),
+            ],
+        },
+    );
+    let err = facet_toml::from_str::<Root>("messages = [{ t = 'Say' }]").unwrap_err();
+    assert!(matches!(err.kind(), TomlErrorKind::MissingField("c")));
+}
+
+#[test]
+fn test_untagged() {
+    #[derive(Debug, Facet, PartialEq)]
+    #[repr(u8)]
+    #[facet(untagged)]
+    enum Value {
+        Flag(bool),
+        Number(i64),
+        Pair(u8, u8),
+        Point { x: i32, y: i32 },
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        values: Vec<Value>,
+    }
+
+    assert_eq!(
+        facet_toml::from_str::<Root>("values = [true, 5, [1, 2], { x = 1, y = 2 }]")
+            .expect("Failed to parse TOML"),
+        Root {
+            values: vec![
+                Value::Flag(true),
+                Value::Number(5),
+                Value::Pair(1, 2),
+                Value::Point { x: 1, y: 2 },
+            ],
+        },
+    );
+    let err = facet_toml::from_str::<Root>("values = ['text']").unwrap_err();
+    assert!(matches!(err.kind(), TomlErrorKind::NoMatchingVariant(_)));
+}
@@ -0,0 +1,123 @@
+//! Tests for the location, path and kind of deserialization errors.
+
+use facet::Facet;
+use facet_toml::TomlErrorKind;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Server {
+    host: String,
+    port: u16,
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Config {
+    name: String,
+    server: Server,
+}
+
+#[test]
+fn test_invalid_value() {
+    let toml = "name = 'web'\n\n[server]\nhost = 'localhost'\nport = 80000\n";
+    let err = facet_toml::from_str::<Config>(toml).unwrap_err();
+    assert!(matches!(err.kind(), TomlErrorKind::InvalidValue(_)));
+    assert_eq!(err.path(), "server.port");
+    assert_eq!((err.line(), err.column()), (5, 8));
+    assert_eq!(&toml[err.span()], "80000");
+    assert_eq!(
+        err.to_string(),
+        "TOML error: Invalid value for u16 (at server.port)
+ --> line 5, column 8 (byte 49)
+  |
+5 | port = 80000
+  |        ^^^^^"
+    );
+}
+
+#[test]
+fn test_missing_field() {
+    let toml = "name = 'web'\n\n[server]\nhost = 'localhost'\n";
+    let err = facet_toml::from_str::<Config>(toml).unwrap_err();
+    assert!(matches!(err.kind(), TomlErrorKind::MissingField("port")));
+    assert_eq!(err.path(), "server");
+    assert_eq!(err.line(), 3);
+
+    let err = facet_toml::from_str::<Config>("name = 'web'\n").unwrap_err();
+    assert!(matches!(err.kind(), TomlErrorKind::MissingField("server")));
+    assert_eq!(err.path(), "");
+}
+
+#[test]
+fn test_unknown_field() {
+    let toml = "name = 'web'\nserver = { host = 'localhost', port = 80, prot = 8080 }\n";
+    let config = facet_toml::from_str::<Config>(toml).unwrap();
+    assert_eq!(config.server.port, 80);
+
+    #[derive(Debug, Facet)]
+    #[facet(deny_unknown_fields)]
+    struct StrictServer {
+        host: String,
+        port: u16,
+    }
+
+    #[derive(Debug, Facet)]
+    struct StrictConfig {
+        name: String,
+        server: StrictServer,
+    }
+
+    let err = facet_toml::from_str::<StrictConfig>(toml).unwrap_err();
+    assert!(matches!(err.kind(), TomlErrorKind::UnknownField(name) if name == "prot"));
+    assert_eq!(err.path(), "server");
+    assert_eq!(&toml[err.span()], "prot");
+}
+
+#[test]
+fn test_type_mismatch() {
+    let toml = "name = 'web'\nserver.host = 'localhost'\nserver.port = 'eighty'\n";
+    let err = facet_toml::from_str::<Config>(toml).unwrap_err();
+    assert!(matches!(
+        err.kind(),
+        TomlErrorKind::TypeMismatch { got: "string", .. }
+    ));
+    assert_eq!(err.path(), "server.port");
+    assert_eq!(&toml[err.span()], "'eighty'");
+}
+
+#[test]
+fn test_unknown_variant() {
+    #[derive(Debug, Facet, PartialEq)]
+    #[repr(u8)]
+    enum Level {
+        Debug,
+        Info,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        level: Level,
+    }
+
+    let toml = "level = 'Trace'\n";
+    let err = facet_toml::from_str::<Root>(toml).unwrap_err();
+    assert!(matches!(err.kind(), TomlErrorKind::UnknownVariant(name) if name == "Trace"));
+    assert_eq!(err.path(), "level");
+    assert_eq!(
+        facet_toml::from_str::<Root>("level = 'Info'").unwrap(),
+        Root { level: Level::Info }
+    );
+    assert_eq!(
+        facet_toml::from_str::<Root>("level = 'Debug'").unwrap(),
+        Root {
+            level: Level::Debug
+        }
+    );
+}
+
+#[test]
+fn test_syntax_error() {
+    let toml = "name = 'web'\n[server\n";
+    let err = facet_toml::from_str::<Config>(toml).unwrap_err();
+    assert!(matches!(err.kind(), TomlErrorKind::Syntax(_)));
+    assert_eq!(err.line(), 2);
+    assert!(!err.kind().to_string().contains('\n'), "{err}");
+}
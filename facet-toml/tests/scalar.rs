@@ -207,6 +207,18 @@ fn test_u32() {
         Root { value: 1 },
     );
     assert!(facet_toml::from_str::<Root>("value = -1").is_err());
+
+    // floats are read into integers only when they have no fraction
+    assert_eq!(
+        facet_toml::from_str::<Root>("value = 2.0").expect("Failed to parse TOML"),
+        Root { value: 2 },
+    );
+    assert!(matches!(
+        facet_toml::from_str::<Root>("value = 1.7")
+            .unwrap_err()
+            .kind(),
+        facet_toml::TomlErrorKind::InvalidValue(_)
+    ));
 }
 
 #[test]
@@ -0,0 +1,210 @@
+//! Tests for serializing to TOML.
+
+use std::collections::BTreeMap;
+
+use facet::Facet;
+
+#[test]
+fn test_to_string() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Manifest {
+        name: String,
+        version: String,
+        edition: Option<u16>,
+        keywords: Vec<String>,
+        package: Package,
+        dependencies: BTreeMap<String, Dependency>,
+        bin: Vec<Target>,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Package {
+        publish: bool,
+        rating: f64,
+        metadata: Metadata,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Metadata {
+        docs: Docs,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Docs {
+        all_features: bool,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Dependency {
+        version: String,
+        features: Vec<String>,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Target {
+        name: String,
+        path: Option<String>,
+    }
+
+    let manifest = Manifest {
+        name: "demo".to_string(),
+        version: "0.1.0".to_string(),
+        edition: None,
+        keywords: vec!["cli".to_string(), "config".to_string()],
+        package: Package {
+            publish: false,
+            rating: 4.5,
+            metadata: Metadata {
+                docs: Docs { all_features: true },
+            },
+        },
+        dependencies: BTreeMap::from([(
+            "serde".to_string(),
+            Dependency {
+                version: "1".to_string(),
+                features: vec!["derive".to_string()],
+            },
+        )]),
+        bin: vec![
+            Target {
+                name: "demo".to_string(),
+                path: None,
+            },
+            Target {
+                name: "helper".to_string(),
+                path: Some("src/helper.rs".to_string()),
+            },
+        ],
+    };
+    let toml = facet_toml::to_string(&manifest).unwrap();
+    assert_eq!(
+        toml,
+        r#"name = "demo"
+version = "0.1.0"
+keywords = ["cli", "config"]
+
+[package]
+publish = false
+rating = 4.5
+
+[package.metadata.docs]
+all_features = true
+
+[dependencies.serde]
+version = "1"
+features = ["derive"]
+
+[[bin]]
+name = "demo"
+
+[[bin]]
+name = "helper"
+path = "src/helper.rs"
+"#
+    );
+    assert_eq!(facet_toml::from_str::<Manifest>(&toml).unwrap(), manifest);
+}
+
+#[test]
+fn test_scalars_and_arrays_of_tables_inside_values() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        ratio: f32,
+        ip: std::net::IpAddr,
+        rgb: [u8; 3],
+        path: Vec<Point>,
+        grid: Vec<Vec<Point>>,
+    }
+
+    let root = Root {
+        ratio: 0.1,
+        ip: "127.0.0.1".parse().unwrap(),
+        rgb: [255, 0, 16],
+        path: vec![Point { x: 1, y: 2 }],
+        grid: vec![vec![Point { x: 0, y: 0 }]],
+    };
+    let toml = facet_toml::to_string(&root).unwrap();
+    assert_eq!(
+        toml,
+        r#"ratio = 0.1
+ip = "127.0.0.1"
+rgb = [255, 0, 16]
+grid = [[{ x = 0, y = 0 }]]
+
+[[path]]
+x = 1
+y = 2
+"#
+    );
+    assert_eq!(facet_toml::from_str::<Root>(&toml).unwrap(), root);
+}
+
+#[test]
+fn test_enums() {
+    #[derive(Debug, Facet, PartialEq)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Storage {
+        Memory,
+        Disk(String),
+        S3 { bucket: String },
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    #[repr(u8)]
+    #[facet(tag = "type")]
+    #[allow(dead_code)]
+    enum Auth {
+        Anonymous,
+        Token { value: String },
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        cache: Storage,
+        backups: Vec<Storage>,
+        primary: Storage,
+        auth: Auth,
+    }
+
+    let root = Root {
+        cache: Storage::Memory,
+        backups: vec![Storage::Disk("/var/backup".to_string())],
+        primary: Storage::S3 {
+            bucket: "data".to_string(),
+        },
+        auth: Auth::Token {
+            value: "secret".to_string(),
+        },
+    };
+    let toml = facet_toml::to_string(&root).unwrap();
+    assert_eq!(
+        toml,
+        r#"cache = "Memory"
+
+[[backups]]
+Disk = "/var/backup"
+
+[primary.S3]
+bucket = "data"
+
+[auth]
+type = "Token"
+value = "secret"
+"#
+    );
+    assert_eq!(facet_toml::from_str::<Root>(&toml).unwrap(), root);
+}
+
+#[test]
+fn test_unsupported() {
+    assert!(facet_toml::to_string(&5u32).is_err());
+    assert!(facet_toml::to_string(&BTreeMap::from([("big", u64::MAX)])).is_err());
+    assert!(facet_toml::to_string(&BTreeMap::from([("list", vec![Some(1), None])])).is_err());
+}
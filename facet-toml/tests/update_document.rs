@@ -0,0 +1,149 @@
+//! Tests for writing values back into existing documents.
+
+use std::collections::BTreeMap;
+
+use facet::Facet;
+use facet_toml::DocumentMut;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Config {
+    name: String,
+    port: u16,
+    mask: u32,
+    proxy: Option<String>,
+    server: Server,
+    aliases: BTreeMap<String, String>,
+    mirror: Vec<Mirror>,
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Server {
+    host: String,
+    tags: Vec<String>,
+    limits: Limits,
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Limits {
+    requests: u32,
+    burst: u32,
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Mirror {
+    url: String,
+}
+
+const ORIGINAL: &str = r#"# Service configuration
+name = 'web'   # shown in the dashboard
+port = 8080
+mask = 0xff
+proxy = "http://proxy:3128"
+
+# where we listen
+[server]
+host = "localhost"
+tags = [ "a", "b" ]  # keep these sorted
+limits = { requests = 100, burst = 10 }
+
+[aliases]
+ls = "list"
+
+[[mirror]]
+url = "https://one.example"
+"#;
+
+fn edit(config: impl FnOnce(&mut Config)) -> String {
+    let mut document: DocumentMut = ORIGINAL.parse().unwrap();
+    let mut value = facet_toml::from_str::<Config>(ORIGINAL).unwrap();
+    config(&mut value);
+    facet_toml::update_document(&mut document, &value).unwrap();
+    let updated = document.to_string();
+    assert_eq!(facet_toml::from_str::<Config>(&updated).unwrap(), value);
+    updated
+}
+
+#[test]
+fn test_unchanged_value_keeps_formatting() {
+    assert_eq!(edit(|_| ()), ORIGINAL);
+}
+
+#[test]
+fn test_changed_values_keep_comments() {
+    let updated = edit(|config| {
+        config.name = "api".to_string();
+        config.server.limits.burst = 20;
+        config.server.tags[1] = "c".to_string();
+    });
+    assert_eq!(
+        updated,
+        ORIGINAL
+            .replace("name = 'web'", "name = \"api\"")
+            .replace("burst = 10", "burst = 20")
+            .replace("\"b\" ]", "\"c\" ]")
+    );
+}
+
+#[test]
+fn test_added_and_removed_keys() {
+    let updated = edit(|config| {
+        config.proxy = None;
+        config.aliases.remove("ls");
+        config
+            .aliases
+            .insert("rm".to_string(), "remove".to_string());
+        config.mirror.push(Mirror {
+            url: "https://two.example".to_string(),
+        });
+    });
+    assert_eq!(
+        updated,
+        r#"# Service configuration
+name = 'web'   # shown in the dashboard
+port = 8080
+mask = 0xff
+
+# where we listen
+[server]
+host = "localhost"
+tags = [ "a", "b" ]  # keep these sorted
+limits = { requests = 100, burst = 10 }
+
+[aliases]
+rm = "remove"
+
+[[mirror]]
+url = "https://one.example"
+
+[[mirror]]
+url = "https://two.example"
+"#
+    );
+}
+
+#[test]
+fn test_added_table() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Tls {
+        cert: String,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        name: String,
+        tls: Option<Tls>,
+    }
+
+    let mut document: DocumentMut = "name = 'web' # the name\n".parse().unwrap();
+    let root = Root {
+        name: "web".to_string(),
+        tls: Some(Tls {
+            cert: "cert.pem".to_string(),
+        }),
+    };
+    facet_toml::update_document(&mut document, &root).unwrap();
+    assert_eq!(
+        document.to_string(),
+        "name = 'web' # the name\n\n[tls]\ncert = \"cert.pem\"\n"
+    );
+}
@@ -2,8 +2,8 @@
 #![forbid(unsafe_code)]
 #![doc = include_str!("../README.md")]
 
-use facet_core::{Def, Facet};
-use facet_reflect::{HeapValue, Wip};
+use facet_core::{Def, Facet, Field, ScalarAffinity, Shape, StructKind};
+use facet_reflect::{HeapValue, Peek, Wip};
 use log::*;
 
 #[cfg(test)]
@@ -15,6 +15,10 @@ mod tests;
 /// bracket notation. For example, a form field like `user[name]` will be deserialized into
 /// a struct with a field named `user` that contains a field named `name`.
 ///
+/// `Option` fields are `Some` when their key is present, and `None` when it's absent.
+/// A nested struct with only `Option` fields may be absent too, when they're all `None`.
+/// Keys that aren't fields are ignored, unless the struct has `#[facet(deny_unknown_fields)]`.
+///
 /// # Nested Structure Format
 ///
 /// For nested structures, the library supports the standard bracket notation used in most web frameworks:
@@ -68,7 +72,7 @@ mod tests;
 ///     },
 /// });
 /// ```
-pub fn from_str<T: Facet>(urlencoded: &str) -> Result<T, UrlEncodedError> {
+pub fn from_str<'a, T: Facet<'a>>(urlencoded: &str) -> Result<T, UrlEncodedError> {
     let val = from_str_value(Wip::alloc::<T>(), urlencoded)?;
     Ok(val.materialize::<T>()?)
 }
@@ -180,7 +184,7 @@ fn deserialize_value<'mem>(
     values: &NestedValues,
 ) -> Result<HeapValue<'mem>, UrlEncodedError> {
     match wip.shape().def {
-        Def::Struct(_sd) => {
+        Def::Struct(sd) => {
             trace!("Deserializing struct");
 
             let mut wip = wip;
@@ -191,6 +195,8 @@ fn deserialize_value<'mem>(
                     let value = values.get(key).unwrap(); // Safe because we're iterating over keys
                     let field = wip.field(index)?;
                     wip = deserialize_scalar_field(key, value, field)?;
+                } else if wip.shape().denies_unknown_fields() {
+                    return Err(UrlEncodedError::UnknownField(key.clone()));
                 } else {
                     trace!("Unknown field: {}", key);
                 }
@@ -202,11 +208,15 @@ fn deserialize_value<'mem>(
                     let nested_values = values.nested.get(key).unwrap(); // Safe because we're iterating over keys
                     let field = wip.field(index)?;
                     wip = deserialize_nested_field(key, nested_values, field)?;
+                } else if wip.shape().denies_unknown_fields() {
+                    return Err(UrlEncodedError::UnknownField(key.clone()));
                 } else {
                     trace!("Unknown nested field: {}", key);
                 }
             }
 
+            let wip = default_missing_fields(wip, sd.fields)?;
+
             trace!("Finished deserializing struct");
             Ok(wip.build()?)
         }
@@ -219,14 +229,15 @@ fn deserialize_value<'mem>(
     }
 }
 
-/// Helper function to deserialize a scalar field
+/// Helper function to deserialize a scalar field, or an optional one: a field
+/// that is present holds `Some`
 fn deserialize_scalar_field<'mem>(
     key: &str,
     value: &str,
     wip: Wip<'mem>,
 ) -> Result<Wip<'mem>, UrlEncodedError> {
     match wip.shape().def {
-        Def::Scalar(_sd) => {
+        Def::Scalar(sd) => {
             let wip = if wip.shape().is_type::<String>() {
                 let s = value.to_string();
                 wip.put(s)?
@@ -240,12 +251,29 @@ fn deserialize_scalar_field<'mem>(
                         ));
                     }
                 }
+            } else if wip.shape().vtable.parse.is_some() {
+                let is_number = matches!(sd.affinity, ScalarAffinity::Number(_));
+                match wip.parse(value) {
+                    Ok(wip) => wip,
+                    Err(_) if is_number => {
+                        return Err(UrlEncodedError::InvalidNumber(
+                            key.to_string(),
+                            value.to_string(),
+                        ));
+                    }
+                    Err(err) => return Err(err.into()),
+                }
             } else {
                 warn!("Unsupported scalar type: {}", wip.shape());
                 return Err(UrlEncodedError::UnsupportedType(format!("{}", wip.shape())));
             };
             Ok(wip.pop()?)
         }
+        Def::Option(_) => {
+            let some = wip.push_some()?;
+            let wip = deserialize_scalar_field(key, value, some)?;
+            Ok(wip.pop()?)
+        }
         _ => {
             error!("Expected scalar field");
             Err(UrlEncodedError::UnsupportedShape(format!(
@@ -263,7 +291,7 @@ fn deserialize_nested_field<'mem>(
     wip: Wip<'mem>,
 ) -> Result<Wip<'mem>, UrlEncodedError> {
     match wip.shape().def {
-        Def::Struct(_sd) => {
+        Def::Struct(sd) => {
             trace!("Deserializing nested struct field: {}", key);
 
             let mut current_wip = wip;
@@ -286,9 +314,16 @@ fn deserialize_nested_field<'mem>(
                 }
             }
 
+            let current_wip = default_missing_fields(current_wip, sd.fields)?;
+
             // Return to parent level
             Ok(current_wip.pop()?)
         }
+        Def::Option(_) => {
+            let some = wip.push_some()?;
+            let wip = deserialize_nested_field(key, nested_values, some)?;
+            Ok(wip.pop()?)
+        }
         _ => {
             error!("Expected struct field for nested value");
             Err(UrlEncodedError::UnsupportedShape(format!(
@@ -299,16 +334,170 @@ fn deserialize_nested_field<'mem>(
     }
 }
 
-/// Errors that can occur during URL encoded form data deserialization.
+/// Fills in the fields of a struct that were absent from the input: options
+/// become `None`, and nested structs with only optional fields are built from
+/// their absent fields, like [`to_string`] leaves them out
+fn default_missing_fields<'mem>(
+    mut wip: Wip<'mem>,
+    fields: &'static [Field],
+) -> Result<Wip<'mem>, UrlEncodedError> {
+    for (index, field) in fields.iter().enumerate() {
+        if wip.is_field_set(index)? || !can_be_absent(field.shape()) {
+            continue;
+        }
+        wip = match field.shape().def {
+            Def::Struct(sd) => default_missing_fields(wip.field(index)?, sd.fields)?.pop()?,
+            _ => wip.field(index)?.put_default()?.pop()?,
+        };
+    }
+    Ok(wip)
+}
+
+/// Whether a value of this shape may have no keys at all: options, and structs
+/// with only such fields
+fn can_be_absent(shape: &Shape) -> bool {
+    match shape.def {
+        Def::Option(_) => true,
+        Def::Struct(sd) => {
+            sd.kind == StructKind::Struct && sd.fields.iter().all(|f| can_be_absent(f.shape()))
+        }
+        _ => false,
+    }
+}
+
+/// Serializes a value of type `T` that implements `Facet` into a URL encoded form data string.
+///
+/// Field names and values are percent-encoded as `application/x-www-form-urlencoded`
+/// (spaces become `+`). Nested structs use the same bracket notation [`from_str`] reads,
+/// like `user[address][city]=Anytown`, and `None` fields are left out, so a nested
+/// struct whose fields are all `None` has no keys at all.
+///
+/// Scalars are written as they display. The root value must be a struct.
+///
+/// # Example
+///
+/// ```
+/// use facet::Facet;
+/// use facet_urlencoded::to_string;
+///
+/// #[derive(Debug, Facet, PartialEq)]
+/// struct Address {
+///     street: String,
+///     city: String,
+/// }
+///
+/// #[derive(Debug, Facet, PartialEq)]
+/// struct User {
+///     name: String,
+///     nickname: Option<String>,
+///     address: Address,
+/// }
+///
+/// let user = User {
+///     name: "John Doe".to_string(),
+///     nickname: None,
+///     address: Address {
+///         street: "123 Main St".to_string(),
+///         city: "Anytown".to_string(),
+///     },
+/// };
+///
+/// let query_string = to_string(&user).expect("Failed to serialize URL encoded data");
+/// assert_eq!(
+///     query_string,
+///     "name=John+Doe&address[street]=123+Main+St&address[city]=Anytown"
+/// );
+/// ```
+pub fn to_string<'a, T: Facet<'a>>(value: &T) -> Result<String, UrlEncodedError> {
+    trace!("Starting URL encoded form data serialization");
+
+    let peek = Peek::new(value);
+    if !matches!(peek.shape().def, Def::Struct(_)) {
+        error!("Unsupported root type");
+        return Err(UrlEncodedError::UnsupportedShape(
+            "Unsupported root type".to_string(),
+        ));
+    }
+
+    let mut output = String::new();
+    serialize_struct(peek, None, &mut output)?;
+    Ok(output)
+}
+
+/// Writes the fields of a struct, with their keys nested under `prefix` if any
+fn serialize_struct(
+    peek: Peek<'_>,
+    prefix: Option<&str>,
+    output: &mut String,
+) -> Result<(), UrlEncodedError> {
+    let struct_peek = peek.into_struct()?;
+    for (field, field_peek) in struct_peek.fields() {
+        let name = encode(field.name);
+        let key = match prefix {
+            Some(prefix) => format!("{}[{}]", prefix, name),
+            None => name,
+        };
+        serialize_field(&key, field_peek, output)?;
+    }
+    Ok(())
+}
+
+/// Writes a field under its (already encoded) key: scalars as a pair, structs
+/// as their fields with nested keys, and options as their value, if any
+fn serialize_field(key: &str, peek: Peek<'_>, output: &mut String) -> Result<(), UrlEncodedError> {
+    match peek.shape().def {
+        Def::Scalar(_) => {
+            if peek.shape().vtable.display.is_none() {
+                warn!("Unsupported scalar type: {}", peek.shape());
+                return Err(UrlEncodedError::UnsupportedType(format!(
+                    "{}",
+                    peek.shape()
+                )));
+            }
+            if !output.is_empty() {
+                output.push('&');
+            }
+            output.push_str(key);
+            output.push('=');
+            output.push_str(&encode(&peek.to_string()));
+            Ok(())
+        }
+        Def::Struct(_) => {
+            trace!("Serializing nested struct field: {}", key);
+            serialize_struct(peek, Some(key), output)
+        }
+        Def::Option(_) => match peek.into_option()?.value() {
+            Some(value) => serialize_field(key, value, output),
+            None => Ok(()),
+        },
+        _ => {
+            error!("Unsupported field shape");
+            Err(UrlEncodedError::UnsupportedShape(format!(
+                "Unsupported shape for field '{}': {}",
+                key,
+                peek.shape()
+            )))
+        }
+    }
+}
+
+/// Percent-encodes a field name or value
+fn encode(s: &str) -> String {
+    form_urlencoded::byte_serialize(s.as_bytes()).collect()
+}
+
+/// Errors that can occur during URL encoded form data serialization and deserialization.
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum UrlEncodedError {
     /// The field value couldn't be parsed as a number.
     InvalidNumber(String, String),
-    /// The shape is not supported for deserialization.
+    /// The shape is not supported for serialization or deserialization.
     UnsupportedShape(String),
-    /// The type is not supported for deserialization.
+    /// The type is not supported for serialization or deserialization.
     UnsupportedType(String),
+    /// The key isn't a field of a struct with `#[facet(deny_unknown_fields)]`.
+    UnknownField(String),
     /// Reflection error
     ReflectError(facet_reflect::ReflectError),
 }
@@ -331,6 +520,9 @@ impl core::fmt::Display for UrlEncodedError {
             UrlEncodedError::UnsupportedType(ty) => {
                 write!(f, "Unsupported type: {}", ty)
             }
+            UrlEncodedError::UnknownField(key) => {
+                write!(f, "Unknown field: {}", key)
+            }
             UrlEncodedError::ReflectError(err) => {
                 write!(f, "Reflection error: {}", err)
             }
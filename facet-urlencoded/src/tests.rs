@@ -1,4 +1,4 @@
-use crate::from_str;
+use crate::{UrlEncodedError, from_str, to_string};
 use facet::Facet;
 
 #[derive(Debug, Facet, PartialEq)]
@@ -106,6 +106,16 @@ fn test_unknown_field() {
             page: 2
         }
     );
+
+    #[derive(Debug, Facet)]
+    #[facet(deny_unknown_fields)]
+    struct StrictParams {
+        query: String,
+        page: u64,
+    }
+
+    let result = from_str::<StrictParams>(query_string);
+    assert!(matches!(result, Err(UrlEncodedError::UnknownField(key)) if key == "unknown"));
 }
 
 #[test]
@@ -229,3 +239,167 @@ fn test_deep_nesting() {
         }
     );
 }
+
+#[test]
+fn test_to_string_basic() {
+    facet_testhelpers::setup();
+
+    let params = SearchParams {
+        query: "rust programming!".to_string(),
+        page: 2,
+    };
+
+    let query_string = to_string(&params).expect("Failed to serialize URL encoded data");
+    assert_eq!(query_string, "query=rust+programming%21&page=2");
+    assert_eq!(from_str::<SearchParams>(&query_string).unwrap(), params);
+}
+
+#[test]
+fn test_to_string_percent_encoding() {
+    facet_testhelpers::setup();
+
+    let params = SearchParams {
+        query: "a&b=c/d?e#f+g%h é".to_string(),
+        page: 0,
+    };
+
+    let query_string = to_string(&params).expect("Failed to serialize URL encoded data");
+    assert_eq!(
+        query_string,
+        "query=a%26b%3Dc%2Fd%3Fe%23f%2Bg%25h+%C3%A9&page=0"
+    );
+    assert_eq!(from_str::<SearchParams>(&query_string).unwrap(), params);
+}
+
+#[test]
+fn test_to_string_nested() {
+    facet_testhelpers::setup();
+
+    let order = OrderForm {
+        product_id: "ABC123".to_string(),
+        quantity: 5,
+        user: User {
+            name: "Jane Smith".to_string(),
+            age: 28,
+            address: Address {
+                street: "456 Oak Ave".to_string(),
+                city: "Somewhere".to_string(),
+                zip: "54321".to_string(),
+            },
+        },
+    };
+
+    let query_string = to_string(&order).expect("Failed to serialize URL encoded data");
+    assert_eq!(
+        query_string,
+        "product_id=ABC123&quantity=5&user[name]=Jane+Smith&user[age]=28\
+         &user[address][street]=456+Oak+Ave&user[address][city]=Somewhere\
+         &user[address][zip]=54321"
+    );
+    assert_eq!(from_str::<OrderForm>(&query_string).unwrap(), order);
+}
+
+#[test]
+fn test_to_string_options() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Redirect {
+        to: String,
+        state: Option<String>,
+        attempt: Option<u32>,
+        ok: bool,
+        origin: Option<Address>,
+    }
+
+    let redirect = Redirect {
+        to: "https://example.com/cb?x=1".to_string(),
+        state: None,
+        attempt: Some(3),
+        ok: true,
+        origin: None,
+    };
+    assert_eq!(
+        to_string(&redirect).unwrap(),
+        "to=https%3A%2F%2Fexample.com%2Fcb%3Fx%3D1&attempt=3&ok=true"
+    );
+    assert_eq!(
+        from_str::<Redirect>(&to_string(&redirect).unwrap()).unwrap(),
+        redirect
+    );
+
+    let redirect = Redirect {
+        state: Some("s".to_string()),
+        origin: Some(Address {
+            street: "1 Elm St".to_string(),
+            city: "Town".to_string(),
+            zip: "00001".to_string(),
+        }),
+        ..redirect
+    };
+    assert_eq!(
+        to_string(&redirect).unwrap(),
+        "to=https%3A%2F%2Fexample.com%2Fcb%3Fx%3D1&state=s&attempt=3&ok=true\
+         &origin[street]=1+Elm+St&origin[city]=Town&origin[zip]=00001"
+    );
+    assert_eq!(
+        from_str::<Redirect>(&to_string(&redirect).unwrap()).unwrap(),
+        redirect
+    );
+}
+
+#[test]
+fn test_to_string_all_none_nested_struct() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Addr {
+        city: Option<String>,
+        zip: Option<u32>,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct U {
+        name: String,
+        addr: Addr,
+    }
+
+    // a struct with nothing but `None` fields has no keys at all
+    let u = U {
+        name: "Ann".to_string(),
+        addr: Addr {
+            city: None,
+            zip: None,
+        },
+    };
+    assert_eq!(to_string(&u).unwrap(), "name=Ann");
+    assert_eq!(from_str::<U>(&to_string(&u).unwrap()).unwrap(), u);
+
+    let u = U {
+        addr: Addr {
+            city: None,
+            zip: Some(12345),
+        },
+        ..u
+    };
+    assert_eq!(to_string(&u).unwrap(), "name=Ann&addr[zip]=12345");
+    assert_eq!(from_str::<U>(&to_string(&u).unwrap()).unwrap(), u);
+}
+
+#[test]
+fn test_to_string_unsupported() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet)]
+    struct Tags {
+        tags: Vec<String>,
+    }
+
+    let result = to_string(&Tags {
+        tags: vec!["a".to_string()],
+    });
+    assert!(matches!(result, Err(UrlEncodedError::UnsupportedShape(_))));
+
+    let result = to_string(&42u64);
+    assert!(matches!(result, Err(UrlEncodedError::UnsupportedShape(_))));
+}
@@ -0,0 +1,234 @@
+//! Errors from reading and writing YAML.
+
+use std::{
+    fmt::{self, Display, Formatter},
+    io,
+    ops::Range,
+};
+
+use facet_ansi::{ColorStyle as _, Style, Stylize as _};
+use facet_core::Shape;
+use facet_reflect::{PathSegment, ReflectError, Snippet, format_path};
+
+/// A YAML deserialization error, located in the input.
+///
+/// Besides the [kind](YamlErrorKind) of error, this records where in the input
+/// it happened (byte span, line and column), the path of the value being
+/// deserialized (e.g. `spec.containers[0].image`) and, for streams, which
+/// document it was in.
+///
+/// The `Display` implementation renders the offending line of input with a
+/// caret underline. Call [`with_colors`](Self::with_colors) to style it
+/// for a terminal.
+#[derive(Debug)]
+pub struct YamlError {
+    kind: YamlErrorKind,
+    span: Range<usize>,
+    snippet: Snippet,
+    path: String,
+    document: Option<usize>,
+    colors: bool,
+}
+
+impl YamlError {
+    /// Creates a new error of the given kind, for the given byte span of the input
+    pub(crate) fn new(kind: YamlErrorKind, input: &str, span: Range<usize>) -> Self {
+        let start = span.start.min(input.len());
+        Self {
+            kind,
+            snippet: Snippet::new(input.as_bytes(), span.clone()),
+            span: start..span.end.max(start),
+            path: String::new(),
+            document: None,
+            colors: false,
+        }
+    }
+
+    /// Records the path of the value that was being deserialized
+    pub(crate) fn with_path(mut self, path: String) -> Self {
+        self.path = path;
+        self
+    }
+
+    /// Records the 1-based number of the document the error is in
+    pub(crate) fn with_document(mut self, document: usize) -> Self {
+        self.document = Some(document);
+        self
+    }
+
+    /// Enable or disable ANSI colors when displaying this error
+    pub fn with_colors(mut self, colors: bool) -> Self {
+        self.colors = colors;
+        self
+    }
+
+    /// Returns the kind of error that occurred
+    pub fn kind(&self) -> &YamlErrorKind {
+        &self.kind
+    }
+
+    /// Returns the byte span of the input the error is about
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+
+    /// Returns the 1-based line at which the error occurred
+    pub fn line(&self) -> usize {
+        self.snippet.line()
+    }
+
+    /// Returns the 1-based column (in characters) at which the error occurred
+    pub fn column(&self) -> usize {
+        self.snippet.column()
+    }
+
+    /// Returns the path of the value that was being deserialized, like
+    /// `spec.containers[0].image`. Empty for the top-level value.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the 1-based number of the document the error is in, when
+    /// reading a stream of documents
+    pub fn document(&self) -> Option<usize> {
+        self.document
+    }
+
+    fn style(&self, style: Style) -> Style {
+        if self.colors { style } else { Style::new() }
+    }
+}
+
+impl Display for YamlError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let error = self.style(Style::new().fg_red().fg_bold());
+        let path = self.style(Style::new().fg_yellow());
+
+        write!(f, "{}", "YAML error".style(error))?;
+        if let Some(document) = self.document {
+            write!(f, " in document {}", document)?;
+        }
+        write!(f, ": {}", self.kind)?;
+        if !self.path.is_empty() {
+            write!(f, " (at {})", self.path.as_str().style(path))?;
+        }
+        writeln!(f)?;
+        self.snippet.write(f, None, self.colors)
+    }
+}
+
+impl std::error::Error for YamlError {}
+
+/// The different kinds of errors that can occur while deserializing YAML
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum YamlErrorKind {
+    /// The input isn't well-formed YAML, or uses an alias or merge key wrongly
+    Syntax(String),
+    /// The input doesn't hold exactly one document
+    NotOneDocument,
+    /// The YAML value doesn't have the right type for the shape being deserialized
+    TypeMismatch {
+        /// The shape we were deserializing
+        expected: &'static Shape,
+        /// The kind of YAML value we got instead (e.g. "string", "mapping")
+        got: &'static str,
+    },
+    /// The value could not be converted into the shape being deserialized
+    /// (e.g. a number out of range, or a malformed IP address)
+    InvalidValue(&'static Shape),
+    /// A sequence doesn't have as many elements as the tuple or array it's read into
+    WrongLength {
+        /// The number of elements of the tuple or array
+        expected: usize,
+        /// The number of elements in the sequence
+        got: usize,
+    },
+    /// A field required by a struct was not present in the mapping
+    MissingField(&'static str),
+    /// The mapping has a key that isn't a field of the struct
+    UnknownField(String),
+    /// The enum has no variant with this name
+    UnknownVariant(String),
+    /// None of the variants of an untagged enum could be deserialized from the value
+    NoMatchingVariant(&'static Shape),
+    /// The shape is not supported by the YAML deserializer
+    UnsupportedShape(&'static Shape),
+    /// An error occurred while building the value
+    ReflectError(ReflectError),
+}
+
+impl Display for YamlErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            YamlErrorKind::Syntax(message) => write!(f, "{}", message),
+            YamlErrorKind::NotOneDocument => write!(f, "Expected exactly one YAML document"),
+            YamlErrorKind::TypeMismatch { expected, got } => {
+                write!(f, "Expected {}, got {}", expected, got)
+            }
+            YamlErrorKind::InvalidValue(shape) => write!(f, "Invalid value for {}", shape),
+            YamlErrorKind::WrongLength { expected, got } => {
+                write!(f, "Expected {} elements, got {}", expected, got)
+            }
+            YamlErrorKind::MissingField(name) => write!(f, "Missing field: {}", name),
+            YamlErrorKind::UnknownField(name) => write!(f, "Unknown field: {}", name),
+            YamlErrorKind::UnknownVariant(name) => write!(f, "Unknown variant: {}", name),
+            YamlErrorKind::NoMatchingVariant(shape) => {
+                write!(f, "Data did not match any variant of {}", shape)
+            }
+            YamlErrorKind::UnsupportedShape(shape) => write!(f, "Unsupported shape: {}", shape),
+            YamlErrorKind::ReflectError(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// An error serializing a value to YAML
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum YamlSerializeError {
+    /// The writer returned an error
+    Io(io::Error),
+    /// The value (or part of it) can't be represented as YAML
+    Unsupported(String),
+}
+
+impl Display for YamlSerializeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            YamlSerializeError::Io(e) => write!(f, "Failed to write YAML: {}", e),
+            YamlSerializeError::Unsupported(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for YamlSerializeError {}
+
+/// A deserialization error that hasn't been located in the input yet: the
+/// path is filled in as the error bubbles up through the values it's in
+pub(crate) struct DeError {
+    kind: YamlErrorKind,
+    span: Range<usize>,
+    /// innermost segment first
+    path: Vec<PathSegment<'static>>,
+}
+
+impl DeError {
+    pub(crate) fn new(kind: YamlErrorKind, span: Range<usize>) -> Self {
+        Self {
+            kind,
+            span,
+            path: Vec::new(),
+        }
+    }
+
+    /// Records that the error happened inside `segment` of the enclosing value
+    pub(crate) fn within(mut self, segment: PathSegment<'static>) -> Self {
+        self.path.push(segment);
+        self
+    }
+
+    pub(crate) fn locate(self, input: &str) -> YamlError {
+        let path = format_path(self.path.iter().rev());
+        YamlError::new(self.kind, input, self.span).with_path(path)
+    }
+}
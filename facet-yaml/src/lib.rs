@@ -1,120 +1,605 @@
 #![warn(missing_docs)]
 #![doc = include_str!("../README.md")]
 
-use facet_core::{Def, Facet};
-use facet_reflect::Wip;
-use yaml_rust2::{Yaml, YamlLoader};
+mod error;
+mod loader;
+mod serialize;
+mod to_scalar;
+
+pub use error::{YamlError, YamlErrorKind, YamlSerializeError};
+pub use serialize::*;
+
+use std::{
+    borrow::Cow,
+    marker::PhantomData,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    num::NonZero,
+};
+
+use error::DeError;
+use facet_core::{Def, EnumTagging, Facet, Field, Shape, StructKind, TaggedFields};
+use facet_reflect::{PathSegment, ReflectError, Wip};
+use loader::{Content, Loader, Node};
+use yaml_rust2::Yaml;
+
+/// Options controlling how [`from_str_with_options`] and friends read YAML.
+#[derive(Debug, Clone)]
+pub struct YamlReaderOptions {
+    max_alias_expansion: usize,
+}
+
+impl Default for YamlReaderOptions {
+    fn default() -> Self {
+        Self {
+            max_alias_expansion: 100_000,
+        }
+    }
+}
+
+impl YamlReaderOptions {
+    /// Creates the default options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how many nodes aliases (`*name`) may copy in total, per document,
+    /// before the document is rejected. Aliases to aliases grow exponentially,
+    /// so this is what keeps a few hundred bytes of YAML from expanding into
+    /// gigabytes ("billion laughs"). Defaults to 100 000.
+    pub fn with_max_alias_expansion(mut self, max_alias_expansion: usize) -> Self {
+        self.max_alias_expansion = max_alias_expansion;
+        self
+    }
+}
 
 /// Deserializes a YAML string into a value of type `T` that implements `Facet`.
-pub fn from_str<T: Facet>(yaml: &str) -> Result<T, AnyErr> {
+///
+/// The string must hold exactly one document; see [`from_str_multi`] for streams.
+/// Aliases are replaced by the node they refer to, and merge keys
+/// (`<<: *base`) are applied before fields are matched. Keys that aren't
+/// fields are skipped, unless the struct has `#[facet(deny_unknown_fields)]`.
+pub fn from_str<'a, T: Facet<'a>>(yaml: &str) -> Result<T, YamlError> {
+    from_str_with_options(yaml, &YamlReaderOptions::default())
+}
+
+/// Deserializes a single-document YAML string, with the given options
+pub fn from_str_with_options<'a, T: Facet<'a>>(
+    yaml: &str,
+    options: &YamlReaderOptions,
+) -> Result<T, YamlError> {
+    let mut loader = Loader::new(yaml, options);
+    let document = loader
+        .next_document()
+        .map_err(|e| e.locate(yaml))?
+        .ok_or_else(|| YamlError::new(YamlErrorKind::NotOneDocument, yaml, 0..0))?;
+    if let Some(extra) = loader.next_document().map_err(|e| e.locate(yaml))? {
+        return Err(YamlError::new(
+            YamlErrorKind::NotOneDocument,
+            yaml,
+            extra.span,
+        ));
+    }
+    from_node(yaml, &document)
+}
+
+/// Deserializes every document of a YAML stream (documents separated by `---`)
+/// into a `T`. An empty stream gives an empty `Vec`.
+pub fn from_str_multi<'a, T: Facet<'a>>(yaml: &str) -> Result<Vec<T>, YamlError> {
+    from_str_multi_with_options(yaml, &YamlReaderOptions::default())
+}
+
+/// Deserializes every document of a YAML stream, with the given options
+pub fn from_str_multi_with_options<'a, T: Facet<'a>>(
+    yaml: &str,
+    options: &YamlReaderOptions,
+) -> Result<Vec<T>, YamlError> {
+    let mut loader = Loader::new(yaml, options);
+    let mut values = Vec::new();
+    while let Some(document) = loader
+        .next_document()
+        .map_err(|e| e.locate(yaml).with_document(values.len() + 1))?
+    {
+        values.push(from_node(yaml, &document).map_err(|e| e.with_document(values.len() + 1))?);
+    }
+    Ok(values)
+}
+
+/// Deserializes the documents of a YAML stream lazily, as the returned
+/// iterator is advanced. Iteration stops after a syntax error, as the rest
+/// of the stream can't be trusted.
+pub fn from_str_documents<T>(yaml: &str) -> YamlDocuments<'_, T>
+where
+    T: for<'a> Facet<'a>,
+{
+    from_str_documents_with_options(yaml, &YamlReaderOptions::default())
+}
+
+/// Deserializes the documents of a YAML stream lazily, with the given options
+pub fn from_str_documents_with_options<'input, T>(
+    yaml: &'input str,
+    options: &YamlReaderOptions,
+) -> YamlDocuments<'input, T>
+where
+    T: for<'a> Facet<'a>,
+{
+    YamlDocuments {
+        input: yaml,
+        loader: Loader::new(yaml, options),
+        document: 0,
+        done: false,
+        phantom: PhantomData,
+    }
+}
+
+/// Iterator over the documents of a YAML stream, returned by [`from_str_documents`]
+pub struct YamlDocuments<'input, T> {
+    input: &'input str,
+    loader: Loader<'input>,
+    /// 1-based number of the last document read
+    document: usize,
+    /// set after a syntax error, or at the end of the stream
+    done: bool,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<T> YamlDocuments<'_, T> {
+    /// Returns the 1-based number of the last document read (0 before the first one)
+    pub fn document(&self) -> usize {
+        self.document
+    }
+}
+
+impl<T> Iterator for YamlDocuments<'_, T>
+where
+    T: for<'a> Facet<'a>,
+{
+    type Item = Result<T, YamlError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.loader.next_document() {
+            Ok(Some(document)) => {
+                self.document += 1;
+                Some(from_node(self.input, &document).map_err(|e| e.with_document(self.document)))
+            }
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e.locate(self.input).with_document(self.document + 1)))
+            }
+        }
+    }
+}
+
+fn from_node<'a, T: Facet<'a>>(input: &str, node: &Node) -> Result<T, YamlError> {
     let wip = Wip::alloc::<T>();
-    let wip = from_str_value(wip, yaml)?;
-    let heap_value = wip.build().map_err(|e| AnyErr(e.to_string()))?;
+    let wip = deserialize_value(wip, node).map_err(|e| e.locate(input))?;
+    let heap_value = wip
+        .build()
+        .map_err(|e| reflect_err(node)(e).locate(input))?;
     heap_value
         .materialize::<T>()
-        .map_err(|e| AnyErr(e.to_string()))
+        .map_err(|e| reflect_err(node)(e).locate(input))
 }
 
-/// Any error
-#[derive(Debug, Clone)]
-pub struct AnyErr(String);
+/// Wraps an error from building the value deserialized from `node`
+fn reflect_err(node: &Node) -> impl FnOnce(ReflectError) -> DeError + use<> {
+    let span = node.span.clone();
+    move |e| DeError::new(YamlErrorKind::ReflectError(e), span)
+}
 
-impl core::fmt::Display for AnyErr {
-    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "{}", self.0)
+fn deserialize_value<'a>(wip: Wip<'a>, node: &Node) -> Result<Wip<'a>, DeError> {
+    let shape = wip.shape();
+    match shape.def {
+        Def::Scalar(_) => deserialize_as_scalar(wip, node),
+        Def::List(_) => deserialize_as_list(wip, node),
+        Def::Map(_) => deserialize_as_map(wip, node),
+        Def::Array(ad) => deserialize_fields_array(wip, ad.n, node),
+        Def::Option(_) => {
+            if let Content::Scalar(Yaml::Null) = node.content {
+                wip.put_default().map_err(reflect_err(node))
+            } else {
+                let wip = wip.push_some().map_err(reflect_err(node))?;
+                let wip = deserialize_value(wip, node)?;
+                wip.pop().map_err(reflect_err(node))
+            }
+        }
+        Def::Struct(sd) => match sd.kind {
+            StructKind::Struct => deserialize_fields_hash(wip, sd.fields, node, None),
+            StructKind::TupleStruct | StructKind::Tuple => {
+                deserialize_fields_array(wip, sd.fields.len(), node)
+            }
+            StructKind::Unit => deserialize_unit(wip, node),
+            _ => Err(DeError::new(
+                YamlErrorKind::UnsupportedShape(shape),
+                node.span.clone(),
+            )),
+        },
+        Def::Enum(_) => deserialize_as_enum(wip, node),
+        _ => Err(DeError::new(
+            YamlErrorKind::UnsupportedShape(shape),
+            node.span.clone(),
+        )),
     }
 }
 
-impl std::error::Error for AnyErr {}
+/// Fills the fields of a struct (or struct variant) from a YAML mapping.
+/// Absent optional fields are `None`. `skip` is a key that isn't a field,
+/// like the tag of an internally tagged enum.
+fn deserialize_fields_hash<'a>(
+    mut wip: Wip<'a>,
+    fields: &'static [Field],
+    node: &Node,
+    skip: Option<&str>,
+) -> Result<Wip<'a>, DeError> {
+    let Content::Mapping(entries) = &node.content else {
+        return Err(to_scalar::mismatch(node, wip.shape()));
+    };
+
+    let deny_unknown = wip.shape().denies_unknown_fields();
+    for (k, v) in entries {
+        let name = k.as_str();
+        if name.is_some() && skip == name {
+            continue;
+        }
+        let Some(index) = name.and_then(|name| wip.field_index(name)) else {
+            if deny_unknown {
+                return Err(DeError::new(
+                    YamlErrorKind::UnknownField(key_text(k)),
+                    k.span.clone(),
+                ));
+            }
+            continue;
+        };
+        wip = wip.field(index).map_err(reflect_err(k))?;
+        wip = deserialize_value(wip, v)
+            .map_err(|e| e.within(PathSegment::Field(fields[index].name)))?;
+        wip = wip.pop().map_err(reflect_err(v))?;
+    }
+
+    // absent optional fields are `None`, everything else is required
+    for (index, field) in fields.iter().enumerate() {
+        if wip.is_field_set(index).map_err(reflect_err(node))? {
+            continue;
+        }
+        if !matches!(field.shape().def, Def::Option(_)) {
+            return Err(DeError::new(
+                YamlErrorKind::MissingField(field.name),
+                node.span.clone(),
+            ));
+        }
+        wip = wip
+            .field(index)
+            .and_then(|wip| wip.put_default())
+            .and_then(|wip| wip.pop())
+            .map_err(reflect_err(node))?;
+    }
+
+    Ok(wip)
+}
 
-impl From<String> for AnyErr {
-    fn from(s: String) -> Self {
-        Self(s)
+/// Fills the `len` fields of a tuple, tuple struct, tuple variant or
+/// fixed-size array from a YAML sequence
+fn deserialize_fields_array<'a>(
+    mut wip: Wip<'a>,
+    len: usize,
+    node: &Node,
+) -> Result<Wip<'a>, DeError> {
+    let items = match &node.content {
+        Content::Sequence(items) if items.len() == len => items,
+        Content::Sequence(items) => {
+            return Err(DeError::new(
+                YamlErrorKind::WrongLength {
+                    expected: len,
+                    got: items.len(),
+                },
+                node.span.clone(),
+            ));
+        }
+        _ => return Err(to_scalar::mismatch(node, wip.shape())),
+    };
+    for (index, item) in items.iter().enumerate() {
+        wip = wip.field(index).map_err(reflect_err(item))?;
+        wip = deserialize_value(wip, item).map_err(|e| e.within(PathSegment::Index(index)))?;
+        wip = wip.pop().map_err(reflect_err(item))?;
     }
+    Ok(wip)
 }
 
-impl From<&str> for AnyErr {
-    fn from(s: &str) -> Self {
-        Self(s.to_string())
+/// Unit structs and variants are written as `~` (null)
+fn deserialize_unit<'a>(wip: Wip<'a>, node: &Node) -> Result<Wip<'a>, DeError> {
+    match node.content {
+        Content::Scalar(Yaml::Null) => Ok(wip),
+        _ => Err(to_scalar::mismatch(node, wip.shape())),
     }
 }
 
-fn yaml_type(ty: &Yaml) -> &'static str {
-    match ty {
-        Yaml::Real(_) => "real number",
-        Yaml::Integer(_) => "integer",
-        Yaml::String(_) => "string",
-        Yaml::Boolean(_) => "boolean",
-        Yaml::Array(_) => "array",
-        Yaml::Hash(_) => "hash/map",
-        Yaml::Alias(_) => "alias",
-        Yaml::Null => "null",
-        Yaml::BadValue => "bad value",
+fn deserialize_as_list<'a>(wip: Wip<'a>, node: &Node) -> Result<Wip<'a>, DeError> {
+    let Content::Sequence(items) = &node.content else {
+        return Err(to_scalar::mismatch(node, wip.shape()));
+    };
+
+    let mut wip = wip.begin_pushback().map_err(reflect_err(node))?;
+    for (index, item) in items.iter().enumerate() {
+        wip = wip.push().map_err(reflect_err(item))?;
+        wip = deserialize_value(wip, item).map_err(|e| e.within(PathSegment::Index(index)))?;
+        wip = wip.pop().map_err(reflect_err(item))?;
     }
+    Ok(wip)
 }
 
-fn yaml_to_u64(ty: &Yaml) -> Result<u64, AnyErr> {
-    match ty {
-        Yaml::Real(r) => r
-            .parse::<u64>()
-            .map_err(|_| AnyErr("Failed to parse real as u64".into())),
-        Yaml::Integer(i) => Ok(*i as u64),
-        Yaml::String(s) => s
-            .parse::<u64>()
-            .map_err(|_| AnyErr("Failed to parse string as u64".into())),
-        Yaml::Boolean(b) => Ok(if *b { 1 } else { 0 }),
-        _ => Err(AnyErr(format!("Cannot convert {} to u64", yaml_type(ty)))),
+fn deserialize_as_map<'a>(wip: Wip<'a>, node: &Node) -> Result<Wip<'a>, DeError> {
+    let Content::Mapping(entries) = &node.content else {
+        return Err(to_scalar::mismatch(node, wip.shape()));
+    };
+
+    let mut wip = wip.begin_map_insert().map_err(reflect_err(node))?;
+    for (k, v) in entries {
+        wip = wip.push_map_key().map_err(reflect_err(k))?;
+        wip = deserialize_value(wip, k)?;
+        wip = wip.push_map_value().map_err(reflect_err(v))?;
+        wip = deserialize_value(wip, v)
+            .map_err(|e| e.within(PathSegment::Key(key_text(k).into())))?;
+        wip = wip.pop().map_err(reflect_err(v))?;
     }
+    Ok(wip)
 }
 
-fn from_str_value<'a>(wip: Wip<'a>, yaml: &str) -> Result<Wip<'a>, AnyErr> {
-    let docs = YamlLoader::load_from_str(yaml).map_err(|e| e.to_string())?;
-    if docs.len() != 1 {
-        return Err("Expected exactly one YAML document".into());
+/// A mapping key as written, for paths and error messages
+fn key_text(key: &Node) -> String {
+    match &key.content {
+        Content::Scalar(Yaml::String(s) | Yaml::Real(s)) => s.clone(),
+        Content::Scalar(Yaml::Integer(i)) => i.to_string(),
+        Content::Scalar(Yaml::Boolean(b)) => b.to_string(),
+        _ => format!("<{}>", key.type_name()),
     }
-    deserialize_value(wip, &docs[0])
 }
 
-fn deserialize_value<'a>(mut wip: Wip<'a>, value: &Yaml) -> Result<Wip<'a>, AnyErr> {
+/// Deserializes an enum, in the representation selected by its tagging attributes
+fn deserialize_as_enum<'a>(wip: Wip<'a>, node: &Node) -> Result<Wip<'a>, DeError> {
     let shape = wip.shape();
-    match shape.def {
-        Def::Scalar(_) => {
-            if shape.is_type::<u64>() {
-                let u = yaml_to_u64(value)?;
-                wip = wip.put(u).map_err(|e| AnyErr(e.to_string()))?;
-            } else if shape.is_type::<String>() {
-                let s = value
-                    .as_str()
-                    .ok_or_else(|| AnyErr(format!("Expected string, got: {}", yaml_type(value))))?
-                    .to_string();
-                wip = wip.put(s).map_err(|e| AnyErr(e.to_string()))?;
-            } else {
-                return Err(AnyErr(format!("Unsupported scalar type: {}", shape)));
+    match shape.enum_tagging() {
+        EnumTagging::Internal { tag } => {
+            let wip = select_variant(wip, tagged_variant_name(shape, node, tag)?)?;
+            let variant = wip.selected_variant().unwrap();
+            match variant.internally_tagged_fields() {
+                Some(TaggedFields::Unit) => Ok(wip),
+                Some(TaggedFields::Struct(fields)) => {
+                    deserialize_fields_hash(wip, fields, node, Some(tag))
+                }
+                Some(TaggedFields::Newtype(fields)) => {
+                    let wip = wip.field(0).map_err(reflect_err(node))?;
+                    let wip = deserialize_fields_hash(wip, fields, node, Some(tag))?;
+                    wip.pop().map_err(reflect_err(node))
+                }
+                _ => Err(DeError::new(
+                    YamlErrorKind::UnsupportedShape(shape),
+                    node.span.clone(),
+                )),
             }
         }
-        Def::List(_) => todo!(),
-        Def::Map(_) => todo!(),
-        Def::Struct(_) => {
-            if let Yaml::Hash(hash) = value {
-                for (k, v) in hash {
-                    let k = k.as_str().ok_or_else(|| {
-                        AnyErr(format!("Expected string key, got: {}", yaml_type(k)))
-                    })?;
-                    let field_index = wip
-                        .field_index(k)
-                        .ok_or_else(|| AnyErr(format!("Field '{}' not found", k)))?;
-                    wip = wip
-                        .field(field_index)
-                        .map_err(|e| AnyErr(format!("Field '{}' error: {}", k, e)))?;
-                    wip = deserialize_value(wip, v)?;
-                    wip = wip.pop().map_err(|e| AnyErr(e.to_string()))?;
-                }
-            } else {
-                return Err(AnyErr(format!("Expected a YAML hash, got: {:?}", value)));
+        EnumTagging::Adjacent { tag, content } => {
+            let wip = select_variant(wip, tagged_variant_name(shape, node, tag)?)?;
+            let variant = wip.selected_variant().unwrap();
+            match (node.get(content), variant.data.kind) {
+                (Some(payload), _) => deserialize_variant_payload(wip, payload)
+                    .map_err(|e| e.within(PathSegment::Field(content))),
+                (None, StructKind::Unit) => Ok(wip),
+                (None, _) => Err(DeError::new(
+                    YamlErrorKind::MissingField(content),
+                    node.span.clone(),
+                )),
+            }
+        }
+        EnumTagging::Untagged => {
+            let variant = Wip::find_untagged_variant(shape, |scratch| {
+                deserialize_variant_payload(scratch, node)
+            });
+            if let Some(variant) = variant {
+                let wip = wip.variant_named(variant.name).map_err(reflect_err(node))?;
+                return deserialize_variant_payload(wip, node);
+            }
+            Err(DeError::new(
+                YamlErrorKind::NoMatchingVariant(shape),
+                node.span.clone(),
+            ))
+        }
+        _ => match &node.content {
+            // `Variant`
+            Content::Scalar(Yaml::String(_)) => {
+                let wip = select_variant(wip, node)?;
+                let null = Node {
+                    content: Content::Scalar(Yaml::Null),
+                    span: node.span.clone(),
+                };
+                deserialize_variant_payload(wip, &null)
+            }
+            // `Variant: payload`
+            Content::Mapping(entries) if entries.len() == 1 => {
+                let (name, payload) = &entries[0];
+                let wip = select_variant(wip, name)?;
+                let variant = wip.selected_variant().unwrap();
+                deserialize_variant_payload(wip, payload)
+                    .map_err(|e| e.within(PathSegment::Field(variant.name)))
             }
+            _ => Err(to_scalar::mismatch(node, shape)),
+        },
+    }
+}
+
+/// Deserializes the data of the selected variant: newtype variants hold their
+/// value directly, tuple variants are sequences and struct variants mappings
+fn deserialize_variant_payload<'a>(wip: Wip<'a>, node: &Node) -> Result<Wip<'a>, DeError> {
+    let variant = wip.selected_variant().unwrap();
+    match variant.data.kind {
+        StructKind::Unit => deserialize_unit(wip, node),
+        StructKind::Tuple | StructKind::TupleStruct if variant.data.fields.len() == 1 => {
+            let wip = wip.field(0).map_err(reflect_err(node))?;
+            let wip = deserialize_value(wip, node)?;
+            wip.pop().map_err(reflect_err(node))
+        }
+        StructKind::Tuple | StructKind::TupleStruct => {
+            deserialize_fields_array(wip, variant.data.fields.len(), node)
         }
-        Def::Enum(_) => todo!(),
-        _ => return Err(AnyErr(format!("Unsupported type: {:?}", shape))),
+        _ => deserialize_fields_hash(wip, variant.data.fields, node, None),
+    }
+}
+
+/// Finds the node holding the variant name stored under `tag`
+fn tagged_variant_name<'y>(
+    shape: &'static Shape,
+    node: &'y Node,
+    tag: &'static str,
+) -> Result<&'y Node, DeError> {
+    if !matches!(node.content, Content::Mapping(_)) {
+        return Err(to_scalar::mismatch(node, shape));
+    }
+    node.get(tag)
+        .ok_or_else(|| DeError::new(YamlErrorKind::MissingField(tag), node.span.clone()))
+}
+
+/// Selects the variant named by the string scalar `name`
+fn select_variant<'a>(wip: Wip<'a>, name: &Node) -> Result<Wip<'a>, DeError> {
+    let variant_name = name
+        .as_str()
+        .ok_or_else(|| to_scalar::mismatch(name, wip.shape()))?;
+    if wip.find_variant(variant_name).is_none() {
+        return Err(DeError::new(
+            YamlErrorKind::UnknownVariant(variant_name.to_string()),
+            name.span.clone(),
+        ));
+    }
+    wip.variant_named(variant_name).map_err(reflect_err(name))
+}
+
+fn deserialize_as_scalar<'a>(mut wip: Wip<'a>, node: &Node) -> Result<Wip<'a>, DeError> {
+    let shape = wip.shape();
+
+    if shape.is_type::<String>() {
+        let val = to_scalar::string(node, shape)?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<Cow<'_, str>>() {
+        let val = Cow::Owned(to_scalar::string(node, shape)?);
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<bool>() {
+        let val = to_scalar::boolean(node, shape)?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<char>() {
+        let val = to_scalar::character(node, shape)?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<f64>() {
+        let val = to_scalar::number::<f64>(node, shape)?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<f32>() {
+        let val = to_scalar::number::<f32>(node, shape)?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<usize>() {
+        let val = to_scalar::number::<usize>(node, shape)?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<u128>() {
+        let val = to_scalar::number::<u128>(node, shape)?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<u64>() {
+        let val = to_scalar::number::<u64>(node, shape)?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<u32>() {
+        let val = to_scalar::number::<u32>(node, shape)?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<u16>() {
+        let val = to_scalar::number::<u16>(node, shape)?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<u8>() {
+        let val = to_scalar::number::<u8>(node, shape)?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<isize>() {
+        let val = to_scalar::number::<isize>(node, shape)?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<i128>() {
+        let val = to_scalar::number::<i128>(node, shape)?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<i64>() {
+        let val = to_scalar::number::<i64>(node, shape)?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<i32>() {
+        let val = to_scalar::number::<i32>(node, shape)?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<i16>() {
+        let val = to_scalar::number::<i16>(node, shape)?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<i8>() {
+        let val = to_scalar::number::<i8>(node, shape)?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<NonZero<usize>>() {
+        // TODO: create a to_scalar::nonzero_number method when we can use a trait to do so
+        let val = NonZero::new(to_scalar::number::<usize>(node, shape)?)
+            .ok_or_else(|| to_scalar::invalid(node, shape))?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<NonZero<u128>>() {
+        let val = NonZero::new(to_scalar::number::<u128>(node, shape)?)
+            .ok_or_else(|| to_scalar::invalid(node, shape))?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<NonZero<u64>>() {
+        let val = NonZero::new(to_scalar::number::<u64>(node, shape)?)
+            .ok_or_else(|| to_scalar::invalid(node, shape))?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<NonZero<u32>>() {
+        let val = NonZero::new(to_scalar::number::<u32>(node, shape)?)
+            .ok_or_else(|| to_scalar::invalid(node, shape))?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<NonZero<u16>>() {
+        let val = NonZero::new(to_scalar::number::<u16>(node, shape)?)
+            .ok_or_else(|| to_scalar::invalid(node, shape))?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<NonZero<u8>>() {
+        let val = NonZero::new(to_scalar::number::<u8>(node, shape)?)
+            .ok_or_else(|| to_scalar::invalid(node, shape))?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<NonZero<isize>>() {
+        let val = NonZero::new(to_scalar::number::<isize>(node, shape)?)
+            .ok_or_else(|| to_scalar::invalid(node, shape))?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<NonZero<i128>>() {
+        let val = NonZero::new(to_scalar::number::<i128>(node, shape)?)
+            .ok_or_else(|| to_scalar::invalid(node, shape))?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<NonZero<i64>>() {
+        let val = NonZero::new(to_scalar::number::<i64>(node, shape)?)
+            .ok_or_else(|| to_scalar::invalid(node, shape))?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<NonZero<i32>>() {
+        let val = NonZero::new(to_scalar::number::<i32>(node, shape)?)
+            .ok_or_else(|| to_scalar::invalid(node, shape))?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<NonZero<i16>>() {
+        let val = NonZero::new(to_scalar::number::<i16>(node, shape)?)
+            .ok_or_else(|| to_scalar::invalid(node, shape))?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<NonZero<i8>>() {
+        let val = NonZero::new(to_scalar::number::<i8>(node, shape)?)
+            .ok_or_else(|| to_scalar::invalid(node, shape))?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<SocketAddr>() {
+        let val = to_scalar::from_str::<SocketAddr>(node, shape)?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<IpAddr>() {
+        let val = to_scalar::from_str::<IpAddr>(node, shape)?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<Ipv4Addr>() {
+        let val = to_scalar::from_str::<Ipv4Addr>(node, shape)?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else if shape.is_type::<Ipv6Addr>() {
+        let val = to_scalar::from_str::<Ipv6Addr>(node, shape)?;
+        wip = wip.put(val).map_err(reflect_err(node))?;
+    } else {
+        return Err(DeError::new(
+            YamlErrorKind::UnsupportedShape(shape),
+            node.span.clone(),
+        ));
     }
     Ok(wip)
 }
@@ -0,0 +1,431 @@
+//! Builds YAML nodes from parser events, one document at a time.
+//!
+//! This is what `YamlLoader` does, except that documents can be pulled lazily
+//! off a stream without losing the loader's errors (like duplicate keys), the
+//! expansion of aliases is bounded, merge keys (`<<: *base`) are applied, and
+//! every node remembers where in the input it was read from.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    ops::Range,
+    str::Chars,
+};
+
+use yaml_rust2::{
+    Event, Yaml,
+    parser::{MarkedEventReceiver, Parser, Tag},
+    scanner::{Marker, TScalarStyle},
+};
+
+use crate::{
+    YamlReaderOptions,
+    error::{DeError, YamlErrorKind},
+};
+
+/// A YAML node, with the byte span of the input it was read from
+#[derive(Debug, Clone)]
+pub(crate) struct Node {
+    pub(crate) content: Content,
+    pub(crate) span: Range<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Content {
+    /// A resolved scalar (never a `Yaml::Array` or `Yaml::Hash`)
+    Scalar(Yaml),
+    Sequence(Vec<Node>),
+    /// Entries in document order, merged entries last
+    Mapping(Vec<(Node, Node)>),
+}
+
+impl Node {
+    /// Returns the string of a string scalar
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match &self.content {
+            Content::Scalar(scalar) => scalar.as_str(),
+            _ => None,
+        }
+    }
+
+    /// Looks up the value of a string key in a mapping
+    pub(crate) fn get(&self, key: &str) -> Option<&Node> {
+        match &self.content {
+            Content::Mapping(entries) => entries
+                .iter()
+                .find(|(k, _)| k.as_str() == Some(key))
+                .map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Describes the type of the node, for error messages
+    pub(crate) fn type_name(&self) -> &'static str {
+        match &self.content {
+            Content::Scalar(Yaml::Real(_)) => "float",
+            Content::Scalar(Yaml::Integer(_)) => "integer",
+            Content::Scalar(Yaml::String(_)) => "string",
+            Content::Scalar(Yaml::Boolean(_)) => "boolean",
+            Content::Scalar(Yaml::Null) => "null",
+            Content::Scalar(_) => "invalid scalar",
+            Content::Sequence(_) => "sequence",
+            Content::Mapping(_) => "mapping",
+        }
+    }
+
+    /// The node without its location, to compare mapping keys
+    fn to_yaml(&self) -> Yaml {
+        match &self.content {
+            Content::Scalar(scalar) => scalar.clone(),
+            Content::Sequence(items) => Yaml::Array(items.iter().map(Node::to_yaml).collect()),
+            Content::Mapping(entries) => Yaml::Hash(
+                entries
+                    .iter()
+                    .map(|(k, v)| (k.to_yaml(), v.to_yaml()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Reads the documents of a YAML stream
+pub(crate) struct Loader<'input> {
+    input: &'input str,
+    parser: Parser<Chars<'input>>,
+    max_alias_expansion: usize,
+    /// byte offset of the start of each line
+    line_starts: Vec<usize>,
+}
+
+impl<'input> Loader<'input> {
+    pub(crate) fn new(yaml: &'input str, options: &YamlReaderOptions) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(yaml.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self {
+            input: yaml,
+            parser: Parser::new_from_str(yaml),
+            max_alias_expansion: options.max_alias_expansion,
+            line_starts,
+        }
+    }
+
+    /// Parses the next document, or returns `None` at the end of the stream.
+    /// Empty documents are null.
+    pub(crate) fn next_document(&mut self) -> Result<Option<Node>, DeError> {
+        let mut builder = DocumentBuilder {
+            input: self.input,
+            line_starts: &self.line_starts,
+            max_alias_expansion: self.max_alias_expansion,
+            document: None,
+            stack: Vec::new(),
+            keys: Vec::new(),
+            anchors: BTreeMap::new(),
+            alias_expansion: 0,
+            error: None,
+        };
+        if let Err(e) = self.parser.load(&mut builder, false) {
+            let start = builder.offset(*e.marker());
+            return Err(DeError::new(
+                YamlErrorKind::Syntax(e.info().to_string()),
+                start..start,
+            ));
+        }
+        match builder.error {
+            Some(e) => Err(e),
+            None => Ok(builder.document),
+        }
+    }
+}
+
+/// A collection being built
+struct Frame {
+    node: Node,
+    anchor: usize,
+    /// number of nodes in the collection, itself included
+    size: usize,
+    /// keys of the mapping so far
+    keys: HashSet<Yaml>,
+}
+
+struct DocumentBuilder<'l> {
+    input: &'l str,
+    line_starts: &'l [usize],
+    document: Option<Node>,
+    stack: Vec<Frame>,
+    /// for each mapping on the stack, the key waiting for its value
+    keys: Vec<Option<Node>>,
+    /// anchored nodes, with their size
+    anchors: BTreeMap<usize, (Node, usize)>,
+    /// nodes copied by aliases so far
+    alias_expansion: usize,
+    max_alias_expansion: usize,
+    error: Option<DeError>,
+}
+
+impl MarkedEventReceiver for DocumentBuilder<'_> {
+    fn on_event(&mut self, ev: Event, mark: Marker) {
+        if self.error.is_some() {
+            return;
+        }
+        if let Err(e) = self.handle(ev, mark) {
+            self.error = Some(e);
+        }
+    }
+}
+
+impl DocumentBuilder<'_> {
+    fn handle(&mut self, ev: Event, mark: Marker) -> Result<(), DeError> {
+        let start = self.offset(mark);
+        match ev {
+            Event::DocumentEnd => {
+                self.document = Some(self.stack.pop().map_or(
+                    Node {
+                        content: Content::Scalar(Yaml::Null),
+                        span: start..start,
+                    },
+                    |frame| frame.node,
+                ));
+            }
+            Event::SequenceStart(anchor, _) => {
+                self.push_frame(Content::Sequence(Vec::new()), anchor, start)
+            }
+            Event::MappingStart(anchor, _) => {
+                self.push_frame(Content::Mapping(Vec::new()), anchor, start);
+                self.keys.push(None);
+            }
+            Event::SequenceEnd => {
+                let frame = self.pop_frame(start);
+                self.insert(frame.node, frame.anchor, frame.size)?;
+            }
+            Event::MappingEnd => {
+                self.keys.pop();
+                let mut frame = self.pop_frame(start);
+                if let Content::Mapping(entries) = &mut frame.node.content {
+                    apply_merge_key(entries)?;
+                }
+                self.insert(frame.node, frame.anchor, frame.size)?;
+            }
+            Event::Scalar(value, style, anchor, tag) => {
+                let span = self.scalar_span(start, &value, style);
+                let node = Node {
+                    content: Content::Scalar(resolve_scalar(value, style, tag)),
+                    span,
+                };
+                self.insert(node, anchor, 1)?;
+            }
+            Event::Alias(anchor) => {
+                let (node, size) = self.anchors.get(&anchor).cloned().ok_or_else(|| {
+                    DeError::new(
+                        YamlErrorKind::Syntax("alias refers to an unknown anchor".to_string()),
+                        start..start,
+                    )
+                })?;
+                // every alias copies the anchored node, so nested aliases can
+                // grow a tiny document exponentially ("billion laughs")
+                self.alias_expansion = self.alias_expansion.saturating_add(size);
+                if self.alias_expansion > self.max_alias_expansion {
+                    return Err(DeError::new(
+                        YamlErrorKind::Syntax(format!(
+                            "aliases expand to more than {} nodes",
+                            self.max_alias_expansion
+                        )),
+                        start..start,
+                    ));
+                }
+                self.insert(node, 0, size)?;
+            }
+            Event::StreamStart | Event::StreamEnd | Event::DocumentStart | Event::Nothing => {}
+        }
+        Ok(())
+    }
+
+    fn push_frame(&mut self, content: Content, anchor: usize, start: usize) {
+        self.stack.push(Frame {
+            node: Node {
+                content,
+                span: start..start,
+            },
+            anchor,
+            size: 1,
+            keys: HashSet::new(),
+        });
+    }
+
+    /// Pops the collection that ends at `end`. Block collections are marked
+    /// where the parser noticed them (e.g. at the first `:` of a mapping),
+    /// so they're taken to start at their first element instead.
+    fn pop_frame(&mut self, end: usize) -> Frame {
+        let mut frame = self.stack.pop().unwrap();
+        let first = match &frame.node.content {
+            Content::Sequence(items) => items.first(),
+            Content::Mapping(entries) => entries.first().map(|(key, _)| key),
+            Content::Scalar(_) => None,
+        };
+        if let Some(first) = first {
+            frame.node.span.start = frame.node.span.start.min(first.span.start);
+        }
+        // flow collections end at their closing bracket
+        let end = match self.input.as_bytes().get(end) {
+            Some(b']' | b'}') => end + 1,
+            _ => end,
+        };
+        frame.node.span.end = end.max(frame.node.span.start);
+        frame
+    }
+
+    /// Adds a finished node of `size` nodes to the collection being built
+    /// (anchor ids start at 1)
+    fn insert(&mut self, node: Node, anchor: usize, size: usize) -> Result<(), DeError> {
+        if anchor > 0 {
+            self.anchors.insert(anchor, (node.clone(), size));
+        }
+        let Some(parent) = self.stack.last_mut() else {
+            self.stack.push(Frame {
+                node,
+                anchor: 0,
+                size,
+                keys: HashSet::new(),
+            });
+            return Ok(());
+        };
+        parent.size = parent.size.saturating_add(size);
+        match &mut parent.node.content {
+            Content::Sequence(items) => items.push(node),
+            Content::Mapping(entries) => {
+                let key = self.keys.last_mut().unwrap();
+                match key.take() {
+                    None => {
+                        if !parent.keys.insert(node.to_yaml()) {
+                            return Err(DeError::new(
+                                YamlErrorKind::Syntax("duplicated key in mapping".to_string()),
+                                node.span,
+                            ));
+                        }
+                        *key = Some(node);
+                    }
+                    Some(key) => entries.push((key, node)),
+                }
+            }
+            Content::Scalar(_) => unreachable!(),
+        }
+        Ok(())
+    }
+
+    /// Converts a marker to a byte offset in the input
+    fn offset(&self, mark: Marker) -> usize {
+        let Some(&line_start) = self.line_starts.get(mark.line().wrapping_sub(1)) else {
+            return self.input.len();
+        };
+        self.input[line_start..]
+            .char_indices()
+            .nth(mark.col())
+            .map_or(self.input.len(), |(i, _)| line_start + i)
+    }
+
+    /// The span of a scalar starting at `start`, as far as it can be told
+    /// from the first line it's on
+    fn scalar_span(&self, start: usize, value: &str, style: TScalarStyle) -> Range<usize> {
+        let rest = &self.input[start..];
+        let line = &rest[..rest.find('\n').unwrap_or(rest.len())];
+        let len = match style {
+            // plain scalars have no escapes, so they're written as they read
+            TScalarStyle::Plain if !value.contains('\n') => value.len(),
+            TScalarStyle::SingleQuoted | TScalarStyle::DoubleQuoted => {
+                closing_quote(line).unwrap_or(line.len())
+            }
+            // just the `|` or `>` indicator
+            TScalarStyle::Literal | TScalarStyle::Folded => 1,
+            _ => line.len(),
+        };
+        start..start + len.min(line.len())
+    }
+}
+
+/// Finds the length of the quoted scalar at the start of `line`, quotes included
+fn closing_quote(line: &str) -> Option<usize> {
+    let bytes = line.as_bytes();
+    let quote = *bytes.first()?;
+    let mut i = 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if quote == b'"' => i += 1,
+            // `''` is an escaped quote
+            b'\'' if quote == b'\'' && bytes.get(i + 1) == Some(&b'\'') => i += 1,
+            b if b == quote => return Some(i + 1),
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Applies a merge key: `<<: *base` copies the entries of `base` that the
+/// mapping doesn't set itself, and `<<: [*a, *b]` does the same for each
+/// mapping in turn, the first ones taking precedence.
+fn apply_merge_key(entries: &mut Vec<(Node, Node)>) -> Result<(), DeError> {
+    let Some(index) = entries
+        .iter()
+        .position(|(key, _)| key.as_str() == Some("<<"))
+    else {
+        return Ok(());
+    };
+    let (_, merged) = entries.remove(index);
+    let sources = match merged.content {
+        Content::Mapping(source) => vec![source],
+        Content::Sequence(items) => items
+            .into_iter()
+            .map(|item| match item.content {
+                Content::Mapping(source) => Some(source),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| {
+                DeError::new(
+                    YamlErrorKind::Syntax("merge key sequences may only hold mappings".to_string()),
+                    merged.span.clone(),
+                )
+            })?,
+        Content::Scalar(_) => {
+            return Err(DeError::new(
+                YamlErrorKind::Syntax(
+                    "merge key value must be a mapping or a sequence of mappings".to_string(),
+                ),
+                merged.span,
+            ));
+        }
+    };
+    let mut keys: HashSet<Yaml> = entries.iter().map(|(key, _)| key.to_yaml()).collect();
+    for source in sources {
+        for (key, value) in source {
+            if keys.insert(key.to_yaml()) {
+                entries.push((key, value));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Resolves a scalar the way `YamlLoader` does: quoted scalars are strings,
+/// plain ones are typed by their content or their `!!` tag
+fn resolve_scalar(value: String, style: TScalarStyle, tag: Option<Tag>) -> Yaml {
+    if style != TScalarStyle::Plain {
+        return Yaml::String(value);
+    }
+    match tag {
+        Some(Tag { handle, suffix }) if handle == "tag:yaml.org,2002:" => match suffix.as_str() {
+            "bool" => value.parse().map_or(Yaml::BadValue, Yaml::Boolean),
+            "int" => value.parse().map_or(Yaml::BadValue, Yaml::Integer),
+            "float" => match Yaml::from_str(&value) {
+                Yaml::Real(_) | Yaml::Integer(_) => Yaml::Real(value),
+                _ => Yaml::BadValue,
+            },
+            "null" => match value.as_str() {
+                "~" | "null" => Yaml::Null,
+                _ => Yaml::BadValue,
+            },
+            _ => Yaml::String(value),
+        },
+        Some(_) => Yaml::String(value),
+        None => Yaml::from_str(&value),
+    }
+}
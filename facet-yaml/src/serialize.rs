@@ -0,0 +1,590 @@
+use std::{
+    borrow::Cow,
+    fmt::Write as _,
+    io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    num::NonZero,
+};
+
+use facet_core::{Def, EnumTagging, Facet, StructKind};
+use facet_reflect::{Peek, PeekEnum};
+use yaml_rust2::Yaml;
+
+use crate::YamlSerializeError;
+
+/// Options controlling the YAML output of [`to_string_with_options`] and friends.
+#[derive(Debug, Clone, Default)]
+pub struct YamlWriterOptions {
+    doc_comments: bool,
+}
+
+impl YamlWriterOptions {
+    /// Creates the default options: no comments
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether to write the doc comments of the root type (`Shape::doc`),
+    /// struct fields (`Field::doc`) and enum variants as YAML `#` comments
+    pub fn with_doc_comments(mut self, doc_comments: bool) -> Self {
+        self.doc_comments = doc_comments;
+        self
+    }
+}
+
+/// Serializes a value to a block-style YAML document
+pub fn to_string<'a, T: Facet<'a>>(value: &T) -> Result<String, YamlSerializeError> {
+    to_string_with_options(value, &YamlWriterOptions::default())
+}
+
+/// Serializes a value to a block-style YAML document, with the given options
+pub fn to_string_with_options<'a, T: Facet<'a>>(
+    value: &T,
+    options: &YamlWriterOptions,
+) -> Result<String, YamlSerializeError> {
+    peek_to_string_with_options(&Peek::new(value), options)
+}
+
+/// Serializes a Peek instance to a block-style YAML document
+pub fn peek_to_string(peek: &Peek<'_>) -> Result<String, YamlSerializeError> {
+    peek_to_string_with_options(peek, &YamlWriterOptions::default())
+}
+
+/// Serializes a Peek instance to a block-style YAML document, with the given options
+pub fn peek_to_string_with_options(
+    peek: &Peek<'_>,
+    options: &YamlWriterOptions,
+) -> Result<String, YamlSerializeError> {
+    let node = Node::from_peek(peek)?;
+    let mut output = String::new();
+    if options.doc_comments {
+        write_comment(&mut output, peek.shape().doc, 0);
+    }
+    let mut emitter = Emitter {
+        output,
+        doc_comments: options.doc_comments,
+    };
+    emitter.write_document(&node);
+    Ok(emitter.output)
+}
+
+/// Serializes a value as a YAML document to a writer
+pub fn to_writer<'a, T: Facet<'a>, W: io::Write>(
+    value: &T,
+    writer: &mut W,
+) -> Result<(), YamlSerializeError> {
+    to_writer_with_options(value, writer, &YamlWriterOptions::default())
+}
+
+/// Serializes a value as a YAML document to a writer, with the given options
+pub fn to_writer_with_options<'a, T: Facet<'a>, W: io::Write>(
+    value: &T,
+    writer: &mut W,
+    options: &YamlWriterOptions,
+) -> Result<(), YamlSerializeError> {
+    let yaml = to_string_with_options(value, options)?;
+    writer
+        .write_all(yaml.as_bytes())
+        .map_err(YamlSerializeError::Io)
+}
+
+/// Serializes values as a YAML stream, each document starting with `---`
+pub fn to_string_multi<'a, 'v, T, I>(values: I) -> Result<String, YamlSerializeError>
+where
+    T: Facet<'a> + 'v,
+    I: IntoIterator<Item = &'v T>,
+{
+    to_string_multi_with_options(values, &YamlWriterOptions::default())
+}
+
+/// Serializes values as a YAML stream, each document starting with `---`, with the given options
+pub fn to_string_multi_with_options<'a, 'v, T, I>(
+    values: I,
+    options: &YamlWriterOptions,
+) -> Result<String, YamlSerializeError>
+where
+    T: Facet<'a> + 'v,
+    I: IntoIterator<Item = &'v T>,
+{
+    let mut output = String::new();
+    for value in values {
+        output.push_str("---\n");
+        output.push_str(&to_string_with_options(value, options)?);
+    }
+    Ok(output)
+}
+
+/// Serializes values as a YAML stream to a writer, each document starting with `---`
+pub fn to_writer_multi<'a, 'v, T, I, W>(values: I, writer: &mut W) -> Result<(), YamlSerializeError>
+where
+    T: Facet<'a> + 'v,
+    I: IntoIterator<Item = &'v T>,
+    W: io::Write,
+{
+    to_writer_multi_with_options(values, writer, &YamlWriterOptions::default())
+}
+
+/// Serializes values as a YAML stream to a writer, each document starting
+/// with `---`, with the given options. Documents are written as they're
+/// serialized.
+pub fn to_writer_multi_with_options<'a, 'v, T, I, W>(
+    values: I,
+    writer: &mut W,
+    options: &YamlWriterOptions,
+) -> Result<(), YamlSerializeError>
+where
+    T: Facet<'a> + 'v,
+    I: IntoIterator<Item = &'v T>,
+    W: io::Write,
+{
+    for value in values {
+        writer.write_all(b"---\n").map_err(YamlSerializeError::Io)?;
+        to_writer_with_options(value, writer, options)?;
+    }
+    Ok(())
+}
+
+/// A value ready to be written out. Scalars are already rendered (and quoted
+/// if need be); empty collections are scalars too, as `[]` and `{}` have no
+/// block form.
+enum Node {
+    Scalar(String),
+    Seq(Vec<Node>),
+    Map(Vec<Entry>),
+}
+
+struct Entry {
+    key: String,
+    doc: &'static [&'static str],
+    value: Node,
+}
+
+impl Entry {
+    fn new(key: &str, value: Node) -> Self {
+        Self {
+            key: quote(key),
+            doc: &[],
+            value,
+        }
+    }
+}
+
+impl Node {
+    fn from_peek(peek: &Peek<'_>) -> Result<Self, YamlSerializeError> {
+        let shape = peek.shape();
+        let node = match shape.def {
+            Def::Scalar(_) => Node::Scalar(scalar_to_string(peek)?),
+            Def::Struct(_) => {
+                let struct_peek = peek
+                    .into_struct()
+                    .map_err(|e| YamlSerializeError::Unsupported(format!("Not a struct: {}", e)))?;
+                match struct_peek.def().kind {
+                    StructKind::Unit => Node::null(),
+                    StructKind::Tuple | StructKind::TupleStruct => {
+                        Node::seq(struct_peek.fields().map(|(_, field_peek)| field_peek))?
+                    }
+                    _ => Node::map(struct_peek.fields().map(|(field, field_peek)| {
+                        Ok(Entry {
+                            doc: field.doc,
+                            ..Entry::new(field.name, Node::from_peek(&field_peek)?)
+                        })
+                    }))?,
+                }
+            }
+            Def::List(_) => {
+                let list_peek = peek
+                    .into_list()
+                    .map_err(|e| YamlSerializeError::Unsupported(format!("Not a list: {}", e)))?;
+                Node::seq(list_peek.iter())?
+            }
+            Def::Array(_) => {
+                let array_peek = peek
+                    .into_array()
+                    .map_err(|e| YamlSerializeError::Unsupported(format!("Not an array: {}", e)))?;
+                Node::seq(array_peek.iter())?
+            }
+            Def::Slice(_) => {
+                let slice_peek = peek
+                    .into_slice()
+                    .map_err(|e| YamlSerializeError::Unsupported(format!("Not a slice: {}", e)))?;
+                Node::seq(slice_peek.iter())?
+            }
+            Def::Map(_) => {
+                let map_peek = peek
+                    .into_map()
+                    .map_err(|e| YamlSerializeError::Unsupported(format!("Not a map: {}", e)))?;
+                Node::map(map_peek.iter().map(|(key, value)| {
+                    let Node::Scalar(key) = Node::from_peek(&key)? else {
+                        return Err(YamlSerializeError::Unsupported(format!(
+                            "Map keys must be scalar types, got: {}",
+                            key.shape()
+                        )));
+                    };
+                    Ok(Entry {
+                        key,
+                        doc: &[],
+                        value: Node::from_peek(&value)?,
+                    })
+                }))?
+            }
+            Def::Option(_) => {
+                let option_peek = peek.into_option().map_err(|e| {
+                    YamlSerializeError::Unsupported(format!("Not an option: {}", e))
+                })?;
+                match option_peek.value() {
+                    Some(value) => Node::from_peek(&value)?,
+                    None => Node::null(),
+                }
+            }
+            Def::SmartPointer(_) => {
+                let smart_pointer_peek = peek.into_smart_pointer().map_err(|e| {
+                    YamlSerializeError::Unsupported(format!("Not a smart pointer: {}", e))
+                })?;
+                let inner = smart_pointer_peek.borrow_inner().ok_or_else(|| {
+                    YamlSerializeError::Unsupported(format!(
+                        "Cannot borrow from smart pointer: {}",
+                        shape
+                    ))
+                })?;
+                Node::from_peek(&inner)?
+            }
+            Def::Enum(_) => {
+                let enum_peek = peek
+                    .into_enum()
+                    .map_err(|e| YamlSerializeError::Unsupported(format!("Not an enum: {}", e)))?;
+                Node::from_enum(peek, enum_peek)?
+            }
+            _ => {
+                return Err(YamlSerializeError::Unsupported(format!(
+                    "Unsupported type: {}",
+                    shape
+                )));
+            }
+        };
+        Ok(node)
+    }
+
+    /// Enums are written in the representation selected by their tagging
+    /// attributes, like facet-json does
+    fn from_enum(peek: &Peek<'_>, enum_peek: PeekEnum<'_>) -> Result<Self, YamlSerializeError> {
+        let variant = enum_peek.active_variant();
+        let is_unit = variant.data.kind == StructKind::Unit;
+
+        let node = match peek.shape().enum_tagging() {
+            EnumTagging::Untagged => Node::from_variant_payload(enum_peek)?,
+            EnumTagging::External if is_unit => Node::Scalar(quote(variant.name)),
+            EnumTagging::External => Node::Map(vec![Entry {
+                doc: variant.doc,
+                ..Entry::new(variant.name, Node::from_variant_payload(enum_peek)?)
+            }]),
+            EnumTagging::Adjacent { tag, content } => {
+                let mut entries = vec![Entry::new(tag, Node::Scalar(quote(variant.name)))];
+                if !is_unit {
+                    entries.push(Entry::new(content, Node::from_variant_payload(enum_peek)?));
+                }
+                Node::Map(entries)
+            }
+            EnumTagging::Internal { tag } => {
+                let mut entries = vec![Entry::new(tag, Node::Scalar(quote(variant.name)))];
+                let Some(fields) = enum_peek.internally_tagged_fields() else {
+                    return Err(YamlSerializeError::Unsupported(format!(
+                        "Internally tagged variant {}::{} must be a unit, struct or newtype struct variant",
+                        peek.shape(),
+                        variant.name
+                    )));
+                };
+                for (field, field_peek) in fields {
+                    entries.push(Entry {
+                        doc: field.doc,
+                        ..Entry::new(field.name, Node::from_peek(&field_peek)?)
+                    });
+                }
+                Node::Map(entries)
+            }
+            _ => {
+                return Err(YamlSerializeError::Unsupported(format!(
+                    "Unsupported enum tagging for {}",
+                    peek.shape()
+                )));
+            }
+        };
+        Ok(node)
+    }
+
+    /// The data of the active variant: newtype variants are their value, tuple
+    /// variants sequences and struct variants mappings
+    fn from_variant_payload(enum_peek: PeekEnum<'_>) -> Result<Self, YamlSerializeError> {
+        let variant = enum_peek.active_variant();
+        match variant.data.kind {
+            StructKind::Unit => Ok(Node::null()),
+            StructKind::Tuple | StructKind::TupleStruct if variant.data.fields.len() == 1 => {
+                let field = enum_peek.field(0).ok_or_else(|| {
+                    YamlSerializeError::Unsupported("Failed to access enum field".to_string())
+                })?;
+                Node::from_peek(&field)
+            }
+            StructKind::Tuple | StructKind::TupleStruct => {
+                Node::seq(enum_peek.fields().map(|(_, field_peek)| field_peek))
+            }
+            _ => Node::map(enum_peek.fields().map(|(field, field_peek)| {
+                Ok(Entry {
+                    doc: field.doc,
+                    ..Entry::new(field.name, Node::from_peek(&field_peek)?)
+                })
+            })),
+        }
+    }
+
+    fn null() -> Self {
+        Node::Scalar("null".to_string())
+    }
+
+    fn seq<'mem>(items: impl Iterator<Item = Peek<'mem>>) -> Result<Self, YamlSerializeError> {
+        let items = items
+            .map(|item| Node::from_peek(&item))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(if items.is_empty() {
+            Node::Scalar("[]".to_string())
+        } else {
+            Node::Seq(items)
+        })
+    }
+
+    fn map(
+        entries: impl Iterator<Item = Result<Entry, YamlSerializeError>>,
+    ) -> Result<Self, YamlSerializeError> {
+        let entries = entries.collect::<Result<Vec<_>, _>>()?;
+        Ok(if entries.is_empty() {
+            Node::Scalar("{}".to_string())
+        } else {
+            Node::Map(entries)
+        })
+    }
+}
+
+/// Writes nodes out as block-style YAML, indenting nested collections by two spaces
+struct Emitter {
+    output: String,
+    doc_comments: bool,
+}
+
+impl Emitter {
+    fn write_document(&mut self, node: &Node) {
+        match node {
+            Node::Scalar(scalar) => {
+                self.output.push_str(scalar);
+                self.output.push('\n');
+            }
+            Node::Seq(items) => self.write_seq(items, 0, false),
+            Node::Map(entries) => self.write_map(entries, 0, false),
+        }
+    }
+
+    /// Writes `- item` lines. `inline` means the first dash goes on the current
+    /// line, right after a parent's `- `.
+    fn write_seq(&mut self, items: &[Node], indent: usize, inline: bool) {
+        for (index, item) in items.iter().enumerate() {
+            if index > 0 || !inline {
+                self.indent(indent);
+            }
+            self.output.push_str("- ");
+            match item {
+                Node::Scalar(scalar) => {
+                    self.output.push_str(scalar);
+                    self.output.push('\n');
+                }
+                Node::Seq(items) => self.write_seq(items, indent + 2, true),
+                Node::Map(entries) => {
+                    // a comment can't share the line with the dash
+                    if self.has_comment(&entries[0]) {
+                        self.output.pop();
+                        self.output.push('\n');
+                        self.write_map(entries, indent + 2, false);
+                    } else {
+                        self.write_map(entries, indent + 2, true);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Writes `key: value` lines. `inline` means the first key goes on the
+    /// current line, right after a parent's `- `.
+    fn write_map(&mut self, entries: &[Entry], indent: usize, inline: bool) {
+        for (index, entry) in entries.iter().enumerate() {
+            if self.doc_comments {
+                write_comment(&mut self.output, entry.doc, indent);
+            }
+            if index > 0 || !inline {
+                self.indent(indent);
+            }
+            self.output.push_str(&entry.key);
+            self.output.push(':');
+            match &entry.value {
+                Node::Scalar(scalar) => {
+                    self.output.push(' ');
+                    self.output.push_str(scalar);
+                    self.output.push('\n');
+                }
+                Node::Seq(items) => {
+                    self.output.push('\n');
+                    self.write_seq(items, indent + 2, false);
+                }
+                Node::Map(entries) => {
+                    self.output.push('\n');
+                    self.write_map(entries, indent + 2, false);
+                }
+            }
+        }
+    }
+
+    fn has_comment(&self, entry: &Entry) -> bool {
+        self.doc_comments && !entry.doc.is_empty()
+    }
+
+    fn indent(&mut self, indent: usize) {
+        self.output.extend(std::iter::repeat_n(' ', indent));
+    }
+}
+
+/// Writes doc comment lines as `#` comments. Lines from `///` comments already
+/// start with a space.
+fn write_comment(output: &mut String, doc: &[&str], indent: usize) {
+    for line in doc {
+        output.extend(std::iter::repeat_n(' ', indent));
+        output.push('#');
+        if !line.is_empty() && !line.starts_with(' ') {
+            output.push(' ');
+        }
+        output.push_str(line.trim_end());
+        output.push('\n');
+    }
+}
+
+/// Renders a string as a YAML scalar: plain if it would read back as the
+/// same string, double-quoted otherwise
+fn quote(s: &str) -> String {
+    if is_plain_safe(s) {
+        return s.to_string();
+    }
+
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            '\0' => quoted.push_str("\\0"),
+            c if c.is_control() || c == '\u{feff}' => {
+                write!(quoted, "\\u{:04x}", c as u32).unwrap();
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn is_plain_safe(s: &str) -> bool {
+    let (Some(first), Some(last)) = (s.chars().next(), s.chars().last()) else {
+        return false;
+    };
+    // anything YAML would resolve to something else, including the YAML 1.1
+    // booleans other parsers still honor
+    if !matches!(Yaml::from_str(s), Yaml::String(_))
+        || matches!(
+            s.to_ascii_lowercase().as_str(),
+            "y" | "n" | "yes" | "no" | "on" | "off" | "true" | "false" | "null" | "~"
+        )
+    {
+        return false;
+    }
+    if first.is_whitespace()
+        || last.is_whitespace()
+        || last == ':'
+        || "?:,[]{}#&*!|>'\"%@`".contains(first)
+        || (first == '-' && (s.len() == 1 || s[1..].starts_with(' ')))
+        || s.starts_with("---")
+        || s.starts_with("...")
+    {
+        return false;
+    }
+    !(s.contains(": ") || s.contains(" #") || s.chars().any(|c| c.is_control() || c == '\u{feff}'))
+}
+
+/// Renders floats so they read back as floats, using YAML's spelling of the
+/// non-finite ones
+fn float_to_string(value: f64, display: String) -> String {
+    if value.is_nan() {
+        ".nan".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { ".inf" } else { "-.inf" }.to_string()
+    } else if display.contains(['.', 'e']) {
+        display
+    } else {
+        format!("{display}.0")
+    }
+}
+
+fn scalar_to_string(peek: &Peek<'_>) -> Result<String, YamlSerializeError> {
+    let shape = peek.shape();
+    let s = if shape.is_type::<bool>() {
+        peek.get::<bool>().unwrap().to_string()
+    } else if shape.is_type::<String>() {
+        quote(peek.get::<String>().unwrap())
+    } else if shape.is_type::<&str>() {
+        quote(peek.get::<&str>().unwrap())
+    } else if shape.is_type::<Cow<'_, str>>() {
+        quote(peek.get::<Cow<'_, str>>().unwrap())
+    } else if shape.is_type::<char>() {
+        quote(peek.get::<char>().unwrap().encode_utf8(&mut [0; 4]))
+    } else if shape.is_type::<f64>() {
+        let value = *peek.get::<f64>().unwrap();
+        float_to_string(value, value.to_string())
+    } else if shape.is_type::<f32>() {
+        let value = *peek.get::<f32>().unwrap();
+        float_to_string(value as f64, value.to_string())
+    } else if shape.is_type::<u8>()
+        || shape.is_type::<u16>()
+        || shape.is_type::<u32>()
+        || shape.is_type::<u64>()
+        || shape.is_type::<u128>()
+        || shape.is_type::<usize>()
+        || shape.is_type::<i8>()
+        || shape.is_type::<i16>()
+        || shape.is_type::<i32>()
+        || shape.is_type::<i64>()
+        || shape.is_type::<i128>()
+        || shape.is_type::<isize>()
+        || shape.is_type::<NonZero<u8>>()
+        || shape.is_type::<NonZero<u16>>()
+        || shape.is_type::<NonZero<u32>>()
+        || shape.is_type::<NonZero<u64>>()
+        || shape.is_type::<NonZero<u128>>()
+        || shape.is_type::<NonZero<usize>>()
+        || shape.is_type::<NonZero<i8>>()
+        || shape.is_type::<NonZero<i16>>()
+        || shape.is_type::<NonZero<i32>>()
+        || shape.is_type::<NonZero<i64>>()
+        || shape.is_type::<NonZero<i128>>()
+        || shape.is_type::<NonZero<isize>>()
+    {
+        // integers display as plain YAML integers
+        peek.to_string()
+    } else if shape.is_type::<SocketAddr>()
+        || shape.is_type::<IpAddr>()
+        || shape.is_type::<Ipv4Addr>()
+        || shape.is_type::<Ipv6Addr>()
+    {
+        quote(&peek.to_string())
+    } else {
+        return Err(YamlSerializeError::Unsupported(format!(
+            "Unsupported scalar type: {}",
+            shape
+        )));
+    };
+    Ok(s)
+}
@@ -0,0 +1,101 @@
+//! Convert YAML values to their scalar counterpart.
+
+use std::str::FromStr;
+
+use facet_core::{Def, NumberBits, ScalarAffinity, Shape};
+use num_traits::cast::NumCast;
+use yaml_rust2::Yaml;
+
+use crate::{
+    error::{DeError, YamlErrorKind},
+    loader::{Content, Node},
+};
+
+/// Try to convert a YAML integer or real to a Rust number.
+///
+/// Applies to all Rust scalars supported by the `num` crate.
+pub(crate) fn number<T: NumCast + FromStr>(
+    node: &Node,
+    shape: &'static Shape,
+) -> Result<T, DeError> {
+    match &node.content {
+        Content::Scalar(Yaml::Integer(i)) => T::from(*i).ok_or_else(|| invalid(node, shape)),
+        // integers that don't fit in an `i64` are reals as far as YAML is concerned,
+        // so try those verbatim before going through `f64` (which also covers `.inf`/`.nan`,
+        // and `1e3` for integers), but never drop a fraction to fit an integer
+        Content::Scalar(value @ Yaml::Real(r)) => r
+            .parse::<T>()
+            .ok()
+            .or_else(|| {
+                value
+                    .as_f64()
+                    .filter(|f| !is_integer(shape) || f.fract() == 0.0)
+                    .and_then(T::from)
+            })
+            .ok_or_else(|| invalid(node, shape)),
+        _ => Err(mismatch(node, shape)),
+    }
+}
+
+/// Whether `shape` is an integer type, which can't hold a fraction
+fn is_integer(shape: &Shape) -> bool {
+    matches!(shape.def, Def::Scalar(sd) if matches!(
+        sd.affinity,
+        ScalarAffinity::Number(na) if matches!(na.bits, NumberBits::Integer { .. })
+    ))
+}
+
+/// Try to convert a YAML boolean to a Rust boolean.
+pub(crate) fn boolean(node: &Node, shape: &'static Shape) -> Result<bool, DeError> {
+    match &node.content {
+        Content::Scalar(Yaml::Boolean(boolean)) => Ok(*boolean),
+        _ => Err(mismatch(node, shape)),
+    }
+}
+
+/// Try to convert a YAML scalar to a Rust string.
+///
+/// Plain scalars that YAML resolves to numbers or booleans (`version: 1.0`)
+/// are taken as written.
+pub(crate) fn string(node: &Node, shape: &'static Shape) -> Result<String, DeError> {
+    match &node.content {
+        Content::Scalar(Yaml::String(s) | Yaml::Real(s)) => Ok(s.clone()),
+        Content::Scalar(Yaml::Integer(i)) => Ok(i.to_string()),
+        Content::Scalar(Yaml::Boolean(b)) => Ok(b.to_string()),
+        _ => Err(mismatch(node, shape)),
+    }
+}
+
+/// Try to convert a single-character YAML string to a Rust char.
+pub(crate) fn character(node: &Node, shape: &'static Shape) -> Result<char, DeError> {
+    let s = string(node, shape)?;
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(invalid(node, shape)),
+    }
+}
+
+/// Try to convert a YAML string to a Rust type that implements `FromStr`.
+pub(crate) fn from_str<T: FromStr>(node: &Node, shape: &'static Shape) -> Result<T, DeError> {
+    node.as_str()
+        .ok_or_else(|| mismatch(node, shape))?
+        .parse()
+        .map_err(|_| invalid(node, shape))
+}
+
+/// The node has the wrong type for `shape`
+pub(crate) fn mismatch(node: &Node, shape: &'static Shape) -> DeError {
+    DeError::new(
+        YamlErrorKind::TypeMismatch {
+            expected: shape,
+            got: node.type_name(),
+        },
+        node.span.clone(),
+    )
+}
+
+/// The node has the right type, but a value `shape` can't hold
+pub(crate) fn invalid(node: &Node, shape: &'static Shape) -> DeError {
+    DeError::new(YamlErrorKind::InvalidValue(shape), node.span.clone())
+}
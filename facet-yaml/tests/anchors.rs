@@ -0,0 +1,128 @@
+//! Tests for anchors, aliases and merge keys.
+
+use std::collections::HashMap;
+
+use facet::Facet;
+use facet_yaml::YamlReaderOptions;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Job {
+    image: String,
+    script: Vec<String>,
+    retries: Option<u32>,
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Pipeline {
+    defaults: Job,
+    build: Job,
+    test: Job,
+}
+
+#[test]
+fn test_aliases() {
+    let yaml = r#"
+        defaults: &job
+          image: &image rust:1.86
+          script: &steps
+            - cargo build
+            - cargo test
+        build: *job
+        test:
+          image: *image
+          script: *steps
+          retries: 3
+    "#;
+    let pipeline: Pipeline = facet_yaml::from_str(yaml).unwrap();
+    assert_eq!(pipeline.build, pipeline.defaults);
+    assert_eq!(pipeline.build.image, "rust:1.86");
+    assert_eq!(pipeline.test.script, ["cargo build", "cargo test"]);
+    assert_eq!(pipeline.test.retries, Some(3));
+
+    let scalars: Vec<String> = facet_yaml::from_str("[&a x, *a, *a]").unwrap();
+    assert_eq!(scalars, ["x", "x", "x"]);
+
+    assert!(facet_yaml::from_str::<Vec<String>>("[*nope]").is_err());
+}
+
+#[test]
+fn test_merge_keys() {
+    let yaml = r#"
+        defaults: &defaults
+          image: rust:1.86
+          script: [cargo build]
+          retries: 2
+        build:
+          <<: *defaults
+        test:
+          script: [cargo test]
+          <<: *defaults
+          retries: 0
+    "#;
+    let pipeline: Pipeline = facet_yaml::from_str(yaml).unwrap();
+    assert_eq!(pipeline.build, pipeline.defaults);
+    assert_eq!(
+        pipeline.test,
+        Job {
+            image: "rust:1.86".to_string(),
+            script: vec!["cargo test".to_string()],
+            retries: Some(0),
+        }
+    );
+}
+
+#[test]
+fn test_merge_key_sequence() {
+    let yaml = r#"
+        - &a { x: 1, y: 1 }
+        - &b { y: 2, z: 2 }
+        - <<: [*a, *b]
+          z: 3
+    "#;
+    let maps: Vec<HashMap<String, u32>> = facet_yaml::from_str(yaml).unwrap();
+    assert_eq!(
+        maps[2],
+        HashMap::from([
+            ("x".to_string(), 1),
+            ("y".to_string(), 1),
+            ("z".to_string(), 3)
+        ])
+    );
+
+    assert!(facet_yaml::from_str::<HashMap<String, u32>>("<<: 1").is_err());
+    assert!(facet_yaml::from_str::<HashMap<String, u32>>("<<: [1]").is_err());
+}
+
+#[test]
+fn test_alias_expansion_limit() {
+    let laughs = r#"
+        a: &a [lol, lol, lol, lol, lol, lol, lol, lol, lol, lol]
+        b: &b [*a, *a, *a, *a, *a, *a, *a, *a, *a, *a]
+        c: &c [*b, *b, *b, *b, *b, *b, *b, *b, *b, *b]
+        d: &d [*c, *c, *c, *c, *c, *c, *c, *c, *c, *c]
+        e: &e [*d, *d, *d, *d, *d, *d, *d, *d, *d, *d]
+        f: &f [*e, *e, *e, *e, *e, *e, *e, *e, *e, *e]
+        g: &g [*f, *f, *f, *f, *f, *f, *f, *f, *f, *f]
+        h: &h [*g, *g, *g, *g, *g, *g, *g, *g, *g, *g]
+        i: &i [*h, *h, *h, *h, *h, *h, *h, *h, *h, *h]
+    "#;
+    let err = facet_yaml::from_str::<HashMap<String, Vec<String>>>(laughs).unwrap_err();
+    assert!(err.to_string().contains("aliases expand"), "{err}");
+
+    // three aliases of an 11-node list
+    let yaml = "a: &a [1, 2, 3, 4, 5, 6, 7, 8, 9, 10]\nb: *a\nc: *a\nd: *a\n";
+    let tight = YamlReaderOptions::new().with_max_alias_expansion(32);
+    assert!(facet_yaml::from_str_with_options::<HashMap<String, Vec<u8>>>(yaml, &tight).is_err());
+    let enough = YamlReaderOptions::new().with_max_alias_expansion(33);
+    let maps: HashMap<String, Vec<u8>> = facet_yaml::from_str_with_options(yaml, &enough).unwrap();
+    assert_eq!(maps["d"], maps["a"]);
+
+    // the limit applies per document
+    let stream = format!("{yaml}---\n{yaml}");
+    assert_eq!(
+        facet_yaml::from_str_multi_with_options::<HashMap<String, Vec<u8>>>(&stream, &enough)
+            .unwrap()
+            .len(),
+        2
+    );
+}
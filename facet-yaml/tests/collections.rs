@@ -0,0 +1,127 @@
+//! Tests for options, lists, maps and arrays.
+
+use std::collections::{BTreeMap, HashMap};
+
+use facet::Facet;
+
+#[test]
+fn test_option() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        set: Option<u32>,
+        unset: Option<u32>,
+        missing: Option<String>,
+    }
+
+    assert_eq!(
+        facet_yaml::from_str::<Root>("set: 5\nunset: ~").expect("Failed to parse YAML"),
+        Root {
+            set: Some(5),
+            unset: None,
+            missing: None,
+        },
+    );
+}
+
+#[test]
+fn test_missing_field() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        required: u32,
+    }
+
+    assert!(facet_yaml::from_str::<Root>("{}").is_err());
+}
+
+#[test]
+fn test_vec() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        numbers: Vec<u32>,
+        nested: Vec<Vec<String>>,
+        empty: Vec<u32>,
+    }
+
+    let yaml = r#"
+        numbers: [1, 2, 3]
+        nested:
+          - [a, b]
+          - []
+          - - c
+        empty: []
+    "#;
+    assert_eq!(
+        facet_yaml::from_str::<Root>(yaml).expect("Failed to parse YAML"),
+        Root {
+            numbers: vec![1, 2, 3],
+            nested: vec![
+                vec!["a".to_string(), "b".to_string()],
+                vec![],
+                vec!["c".to_string()]
+            ],
+            empty: vec![],
+        },
+    );
+    assert!(facet_yaml::from_str::<Vec<u32>>("numbers: 1").is_err());
+}
+
+#[test]
+fn test_maps() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        hash: HashMap<String, u32>,
+        btree: BTreeMap<u16, String>,
+    }
+
+    let yaml = r#"
+        hash:
+          one: 1
+          two: 2
+        btree:
+          20: twenty
+          10: ten
+    "#;
+    let root: Root = facet_yaml::from_str(yaml).expect("Failed to parse YAML");
+    assert_eq!(
+        root.hash,
+        HashMap::from([("one".to_string(), 1), ("two".to_string(), 2)])
+    );
+    assert_eq!(
+        root.btree.into_iter().collect::<Vec<_>>(),
+        [(10, "ten".to_string()), (20, "twenty".to_string())]
+    );
+}
+
+#[test]
+fn test_arrays_and_tuples() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Point(i32, i32);
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        rgb: [u8; 3],
+        names: [String; 2],
+        point: Point,
+        pair: (u8, String),
+    }
+
+    let yaml = r#"
+        rgb: [255, 128, 0]
+        names: [a, b]
+        point: [1, -1]
+        pair: [7, seven]
+    "#;
+    assert_eq!(
+        facet_yaml::from_str::<Root>(yaml).expect("Failed to parse YAML"),
+        Root {
+            rgb: [255, 128, 0],
+            names: ["a".to_string(), "b".to_string()],
+            point: Point(1, -1),
+            pair: (7, "seven".to_string()),
+        },
+    );
+
+    assert!(facet_yaml::from_str::<[u8; 3]>("[1, 2]").is_err());
+    assert!(facet_yaml::from_str::<[String; 2]>("[a, b, c]").is_err());
+    assert!(facet_yaml::from_str::<[String; 2]>("[a, 1.5.0, ~]").is_err());
+}
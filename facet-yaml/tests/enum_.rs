@@ -0,0 +1,134 @@
+//! Tests for enums.
+
+use facet::Facet;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[test]
+fn test_externally_tagged() {
+    #[derive(Debug, Facet, PartialEq)]
+    #[repr(u8)]
+    enum Shape {
+        Empty,
+        Circle(f64),
+        Line(Point, Point),
+        Rect { width: u32, height: u32 },
+    }
+
+    let yaml = r#"
+        - Empty
+        - Circle: 1.5
+        - Line:
+            - { x: 0, y: 0 }
+            - { x: 1, y: 2 }
+        - Rect:
+            width: 3
+            height: 4
+        - Empty: ~
+    "#;
+    assert_eq!(
+        facet_yaml::from_str::<Vec<Shape>>(yaml).expect("Failed to parse YAML"),
+        [
+            Shape::Empty,
+            Shape::Circle(1.5),
+            Shape::Line(Point { x: 0, y: 0 }, Point { x: 1, y: 2 }),
+            Shape::Rect {
+                width: 3,
+                height: 4
+            },
+            Shape::Empty,
+        ],
+    );
+
+    assert!(facet_yaml::from_str::<Shape>("Circle").is_err());
+    assert!(facet_yaml::from_str::<Shape>("Square").is_err());
+    assert!(facet_yaml::from_str::<Shape>("{ Circle: 1, Empty: ~ }").is_err());
+    assert!(facet_yaml::from_str::<Shape>("Rect: { width: 3 }").is_err());
+}
+
+#[test]
+fn test_internally_tagged() {
+    #[derive(Debug, Facet, PartialEq)]
+    #[repr(u8)]
+    #[facet(tag = "type")]
+    enum Message {
+        Ping,
+        Move { x: i32, y: i32 },
+        At(Point),
+    }
+
+    let yaml = r#"
+        - type: Ping
+        - { x: 1, type: Move, y: 2 }
+        - type: At
+          x: 3
+          y: 4
+    "#;
+    assert_eq!(
+        facet_yaml::from_str::<Vec<Message>>(yaml).expect("Failed to parse YAML"),
+        [
+            Message::Ping,
+            Message::Move { x: 1, y: 2 },
+            Message::At(Point { x: 3, y: 4 }),
+        ],
+    );
+    assert!(facet_yaml::from_str::<Message>("{ x: 1, y: 2 }").is_err());
+}
+
+#[test]
+fn test_adjacently_tagged() {
+    #[derive(Debug, Facet, PartialEq)]
+    #[repr(u8)]
+    #[facet(tag = "t", content = "c")]
+    enum Message {
+        Ping,
+        Say(String),
+        Move(i32, i32),
+    }
+
+    let yaml = r#"
+        - t: Ping
+        - { c: hello, t: Say }
+        - { t: Move, c: [1, 2] }
+    "#;
+    assert_eq!(
+        facet_yaml::from_str::<Vec<Message>>(yaml).expect("Failed to parse YAML"),
+        [
+            Message::Ping,
+            Message::Say("hello".to_string()),
+            Message::Move(1, 2),
+        ],
+    );
+    assert!(facet_yaml::from_str::<Message>("t: Say").is_err());
+}
+
+#[test]
+fn test_untagged() {
+    #[derive(Debug, Facet, PartialEq)]
+    #[repr(u8)]
+    #[facet(untagged)]
+    enum Value {
+        Nothing,
+        Flag(bool),
+        Number(i64),
+        Pair(u8, u8),
+        Point { x: i32, y: i32 },
+    }
+
+    let yaml = "[~, true, 5, [1, 2], { x: 1, y: 2 }]";
+    assert_eq!(
+        facet_yaml::from_str::<Vec<Value>>(yaml).expect("Failed to parse YAML"),
+        [
+            Value::Nothing,
+            Value::Flag(true),
+            Value::Number(5),
+            Value::Pair(1, 2),
+            Value::Point { x: 1, y: 2 },
+        ],
+    );
+    assert!(facet_yaml::from_str::<Value>("text").is_err());
+}
@@ -0,0 +1,132 @@
+//! Tests for the location, path and kind of deserialization errors.
+
+use facet::Facet;
+use facet_yaml::YamlErrorKind;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Container {
+    name: String,
+    image: String,
+    port: u16,
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Spec {
+    containers: Vec<Container>,
+}
+
+#[test]
+fn test_invalid_value() {
+    let yaml = "containers:\n  - name: web\n    image: nginx\n    port: 80000\n";
+    let err = facet_yaml::from_str::<Spec>(yaml).unwrap_err();
+    assert!(matches!(err.kind(), YamlErrorKind::InvalidValue(_)));
+    assert_eq!(err.path(), "containers[0].port");
+    assert_eq!((err.line(), err.column()), (4, 11));
+    assert_eq!(&yaml[err.span()], "80000");
+    assert_eq!(
+        err.to_string(),
+        "YAML error: Invalid value for u16 (at containers[0].port)
+ --> line 4, column 11 (byte 53)
+  |
+4 |     port: 80000
+  |           ^^^^^"
+    );
+}
+
+#[test]
+fn test_missing_field() {
+    let yaml = "containers:\n  - name: web\n    port: 80\n";
+    let err = facet_yaml::from_str::<Spec>(yaml).unwrap_err();
+    assert!(matches!(err.kind(), YamlErrorKind::MissingField("image")));
+    assert_eq!(err.path(), "containers[0]");
+    assert_eq!((err.line(), err.column()), (2, 5));
+}
+
+#[test]
+fn test_unknown_field() {
+    let yaml = "containers:\n  - name: web\n    image: nginx\n    port: 80\n    imgae: x\n";
+    let spec = facet_yaml::from_str::<Spec>(yaml).unwrap();
+    assert_eq!(spec.containers[0].image, "nginx");
+
+    #[derive(Debug, Facet)]
+    #[facet(deny_unknown_fields)]
+    struct StrictContainer {
+        name: String,
+        image: String,
+        port: u16,
+    }
+
+    #[derive(Debug, Facet)]
+    struct StrictSpec {
+        containers: Vec<StrictContainer>,
+    }
+
+    let err = facet_yaml::from_str::<StrictSpec>(yaml).unwrap_err();
+    assert!(matches!(err.kind(), YamlErrorKind::UnknownField(name) if name == "imgae"));
+    assert_eq!(&yaml[err.span()], "imgae");
+    assert_eq!((err.line(), err.column()), (5, 5));
+}
+
+#[test]
+fn test_type_mismatch() {
+    let yaml = "containers:\n  - name: web\n    image: nginx\n    port: 'eighty'\n";
+    let err = facet_yaml::from_str::<Spec>(yaml).unwrap_err();
+    assert!(matches!(
+        err.kind(),
+        YamlErrorKind::TypeMismatch { got: "string", .. }
+    ));
+    assert_eq!(&yaml[err.span()], "'eighty'");
+
+    let yaml = "containers: {web: nginx}\n";
+    let err = facet_yaml::from_str::<Spec>(yaml).unwrap_err();
+    assert!(matches!(
+        err.kind(),
+        YamlErrorKind::TypeMismatch { got: "mapping", .. }
+    ));
+    assert_eq!(err.path(), "containers");
+    assert_eq!(&yaml[err.span()], "{web: nginx}");
+}
+
+#[test]
+fn test_map_and_enum_paths() {
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, Facet, PartialEq)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Probe {
+        Http { path: String, port: u16 },
+        Exec(Vec<String>),
+    }
+
+    let yaml = "web:\n  Http:\n    path: /health\n    port: -1\n";
+    let err = facet_yaml::from_str::<BTreeMap<String, Probe>>(yaml).unwrap_err();
+    assert_eq!(err.path(), "web.Http.port");
+
+    let yaml = "web:\n  Tcp: 80\n";
+    let err = facet_yaml::from_str::<BTreeMap<String, Probe>>(yaml).unwrap_err();
+    assert!(matches!(err.kind(), YamlErrorKind::UnknownVariant(name) if name == "Tcp"));
+    assert_eq!(err.path(), "web");
+    assert_eq!(&yaml[err.span()], "Tcp");
+}
+
+#[test]
+fn test_wrong_length() {
+    let err = facet_yaml::from_str::<[u8; 3]>("[1, 2]").unwrap_err();
+    assert!(matches!(
+        err.kind(),
+        YamlErrorKind::WrongLength {
+            expected: 3,
+            got: 2
+        }
+    ));
+}
+
+#[test]
+fn test_syntax_error() {
+    let yaml = "containers:\n  - name: web\n  image: [nginx\n";
+    let err = facet_yaml::from_str::<Spec>(yaml).unwrap_err();
+    assert!(matches!(err.kind(), YamlErrorKind::Syntax(_)));
+    assert_eq!(err.path(), "");
+    assert!(err.line() > 1, "{err}");
+}
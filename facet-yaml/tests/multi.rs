@@ -0,0 +1,120 @@
+//! Tests for multi-document YAML streams.
+
+use facet::Facet;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Manifest {
+    kind: String,
+    name: String,
+    replicas: Option<u32>,
+}
+
+const BUNDLE: &str = r#"
+# leading comments are fine
+kind: Deployment
+name: web
+replicas: 2
+---
+kind: Service
+name: web
+...
+---
+kind: ConfigMap
+name: settings
+"#;
+
+#[test]
+fn test_from_str_multi() {
+    let manifests: Vec<Manifest> = facet_yaml::from_str_multi(BUNDLE).unwrap();
+    let fields: Vec<_> = manifests
+        .iter()
+        .map(|m| (m.kind.as_str(), m.name.as_str(), m.replicas))
+        .collect();
+    assert_eq!(
+        fields,
+        [
+            ("Deployment", "web", Some(2)),
+            ("Service", "web", None),
+            ("ConfigMap", "settings", None),
+        ]
+    );
+
+    assert!(
+        facet_yaml::from_str_multi::<Manifest>("")
+            .unwrap()
+            .is_empty()
+    );
+    // an empty document is null
+    assert_eq!(
+        facet_yaml::from_str_multi::<Option<u32>>("---\n---\n1\n").unwrap(),
+        [None, Some(1)]
+    );
+}
+
+#[test]
+fn test_single_document_only() {
+    let err = facet_yaml::from_str::<Manifest>(BUNDLE).unwrap_err();
+    assert!(err.to_string().contains("exactly one"), "{err}");
+    assert!(facet_yaml::from_str::<Manifest>("").is_err());
+    let manifest = facet_yaml::from_str::<Manifest>("---\nkind: A\nname: a\n...\n").unwrap();
+    assert_eq!(manifest.name, "a");
+}
+
+#[test]
+fn test_from_str_documents() {
+    let mut documents = facet_yaml::from_str_documents::<Manifest>(BUNDLE);
+    assert_eq!(documents.document(), 0);
+    assert_eq!(documents.next().unwrap().unwrap().kind, "Deployment");
+    assert_eq!(documents.document(), 1);
+    assert_eq!(documents.count(), 2);
+
+    // a document that doesn't fit the type doesn't stop the stream
+    let yaml = "kind: A\nname: a\n---\nkind: B\n---\nkind: C\nname: c\n";
+    let results: Vec<_> = facet_yaml::from_str_documents::<Manifest>(yaml).collect();
+    assert_eq!(results.len(), 3);
+    let err = results[1].as_ref().unwrap_err();
+    assert_eq!(err.document(), Some(2));
+    assert_eq!(err.line(), 4);
+    assert!(
+        err.to_string().starts_with("YAML error in document 2:"),
+        "{err}"
+    );
+    assert_eq!(results[2].as_ref().unwrap().name, "c");
+
+    // but a syntax error does
+    let yaml = "kind: A\nname: a\n---\nkind: [B\n---\nkind: C\nname: c\n";
+    let results: Vec<_> = facet_yaml::from_str_documents::<Manifest>(yaml).collect();
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+    assert!(facet_yaml::from_str_multi::<Manifest>(yaml).is_err());
+}
+
+#[test]
+fn test_duplicate_keys() {
+    let yaml = "kind: A\nname: a\n---\nkind: B\nkind: B\nname: b\n";
+    let err = facet_yaml::from_str_multi::<Manifest>(yaml).unwrap_err();
+    assert!(err.to_string().contains("duplicated key"), "{err}");
+}
+
+#[test]
+fn test_multi_writer() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Scale {
+        replicas: Option<u32>,
+    }
+
+    let manifests = [Scale { replicas: Some(2) }, Scale { replicas: None }];
+    let yaml = facet_yaml::to_string_multi(&manifests).unwrap();
+    assert_eq!(yaml, "---\nreplicas: 2\n---\nreplicas: null\n");
+    assert_eq!(
+        facet_yaml::from_str_multi::<Scale>(&yaml).unwrap(),
+        manifests
+    );
+
+    let mut out = Vec::new();
+    facet_yaml::to_writer_multi(manifests.iter(), &mut out).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), yaml);
+
+    assert_eq!(facet_yaml::to_string_multi::<Scale, _>(&[]).unwrap(), "");
+}
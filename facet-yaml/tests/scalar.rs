@@ -0,0 +1,151 @@
+//! Tests for scalar values.
+
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    num::NonZero,
+};
+
+use facet::Facet;
+
+#[test]
+fn test_integers() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        a: u8,
+        b: i16,
+        c: u32,
+        d: i64,
+        e: u64,
+        f: u128,
+        g: i128,
+        h: usize,
+        i: isize,
+    }
+
+    let yaml = r#"
+        a: 255
+        b: -300
+        c: 0x10
+        d: -9223372036854775808
+        e: 18446744073709551615
+        f: 340282366920938463463374607431768211455
+        g: -5
+        h: 0o17
+        i: 7
+    "#;
+    assert_eq!(
+        facet_yaml::from_str::<Root>(yaml).expect("Failed to parse YAML"),
+        Root {
+            a: 255,
+            b: -300,
+            c: 16,
+            d: i64::MIN,
+            e: u64::MAX,
+            f: u128::MAX,
+            g: -5,
+            h: 15,
+            i: 7,
+        },
+    );
+
+    assert!(facet_yaml::from_str::<u8>("256").is_err());
+    assert!(facet_yaml::from_str::<u32>("-1").is_err());
+    assert!(facet_yaml::from_str::<u32>("one").is_err());
+
+    // reals are read into integers only when they have no fraction
+    assert_eq!(facet_yaml::from_str::<u16>("1e3").unwrap(), 1000);
+    assert!(matches!(
+        facet_yaml::from_str::<u16>("1.5").unwrap_err().kind(),
+        facet_yaml::YamlErrorKind::InvalidValue(_)
+    ));
+}
+
+#[test]
+fn test_non_zero() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        a: NonZero<u16>,
+        b: NonZero<i64>,
+    }
+
+    assert_eq!(
+        facet_yaml::from_str::<Root>("a: 1\nb: -2").expect("Failed to parse YAML"),
+        Root {
+            a: NonZero::new(1).unwrap(),
+            b: NonZero::new(-2).unwrap(),
+        },
+    );
+    assert!(facet_yaml::from_str::<Root>("a: 0\nb: 1").is_err());
+}
+
+#[test]
+fn test_floats() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        a: f64,
+        b: f32,
+        c: f64,
+        d: f64,
+    }
+
+    let root: Root = facet_yaml::from_str("a: 1.5\nb: -2\nc: 1e3\nd: .inf").unwrap();
+    assert_eq!(
+        root,
+        Root {
+            a: 1.5,
+            b: -2.0,
+            c: 1000.0,
+            d: f64::INFINITY,
+        },
+    );
+    assert!(facet_yaml::from_str::<f64>(".nan").unwrap().is_nan());
+    assert!(facet_yaml::from_str::<f64>("'1.5'").is_err());
+}
+
+#[test]
+fn test_bool_and_string() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        flag: bool,
+        name: String,
+        version: String,
+    }
+
+    assert_eq!(
+        facet_yaml::from_str::<Root>("flag: true\nname: 'yes'\nversion: 1.10").unwrap(),
+        Root {
+            flag: true,
+            name: "yes".to_string(),
+            version: "1.10".to_string(),
+        },
+    );
+    assert!(facet_yaml::from_str::<bool>("1").is_err());
+}
+
+#[test]
+fn test_addresses() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        socket: SocketAddr,
+        ip: IpAddr,
+        v4: Ipv4Addr,
+        v6: Ipv6Addr,
+    }
+
+    let yaml = r#"
+        socket: 127.0.0.1:8000
+        ip: "::1"
+        v4: 10.0.0.1
+        v6: "fe80::1"
+    "#;
+    assert_eq!(
+        facet_yaml::from_str::<Root>(yaml).expect("Failed to parse YAML"),
+        Root {
+            socket: "127.0.0.1:8000".parse().unwrap(),
+            ip: "::1".parse().unwrap(),
+            v4: Ipv4Addr::new(10, 0, 0, 1),
+            v6: "fe80::1".parse().unwrap(),
+        },
+    );
+    assert!(facet_yaml::from_str::<Ipv4Addr>("not an ip").is_err());
+}
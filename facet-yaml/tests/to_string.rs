@@ -0,0 +1,315 @@
+//! Tests for serializing to YAML.
+
+use std::collections::BTreeMap;
+
+use facet::Facet;
+use facet_yaml::YamlWriterOptions;
+
+#[test]
+fn test_block_style() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Container {
+        name: String,
+        args: Vec<String>,
+        ports: Vec<u16>,
+        memory: Option<f64>,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Deployment {
+        name: String,
+        labels: BTreeMap<String, String>,
+        containers: Vec<Container>,
+    }
+
+    let deployment = Deployment {
+        name: "web".to_string(),
+        labels: BTreeMap::from([
+            ("app".to_string(), "web".to_string()),
+            ("tier".to_string(), "frontend".to_string()),
+        ]),
+        containers: vec![Container {
+            name: "nginx".to_string(),
+            args: vec![],
+            ports: vec![80, 443],
+            memory: None,
+        }],
+    };
+
+    let yaml = facet_yaml::to_string(&deployment).expect("Failed to serialize YAML");
+    assert_eq!(
+        yaml,
+        r#"name: web
+labels:
+  app: web
+  tier: frontend
+containers:
+  - name: nginx
+    args: []
+    ports:
+      - 80
+      - 443
+    memory: null
+"#
+    );
+    assert_eq!(
+        facet_yaml::from_str::<Deployment>(&yaml).expect("Failed to parse YAML"),
+        deployment
+    );
+}
+
+#[test]
+fn test_doc_comments() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Container {
+        /// Container name
+        name: String,
+    }
+
+    /// A minimal deployment manifest
+    #[derive(Debug, Facet, PartialEq)]
+    struct Deployment {
+        /// How many pods to run
+        replicas: u32,
+        containers: Vec<Container>,
+    }
+
+    let deployment = Deployment {
+        replicas: 3,
+        containers: vec![Container {
+            name: "nginx".to_string(),
+        }],
+    };
+
+    let options = YamlWriterOptions::new().with_doc_comments(true);
+    let yaml = facet_yaml::to_string_with_options(&deployment, &options).unwrap();
+    // the comment can't go after the dash
+    assert_eq!(
+        yaml,
+        r#"# A minimal deployment manifest
+# How many pods to run
+replicas: 3
+containers:
+  -
+    # Container name
+    name: nginx
+"#
+    );
+    assert_eq!(
+        facet_yaml::from_str::<Deployment>(&yaml).expect("Failed to parse YAML"),
+        deployment
+    );
+
+    let mut out = Vec::new();
+    facet_yaml::to_writer_with_options(&deployment, &mut out, &options).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), yaml);
+}
+
+#[test]
+fn test_quoting() {
+    let strings = [
+        "",
+        "true",
+        "No",
+        "null",
+        "~",
+        "12",
+        "1.5",
+        "0x1f",
+        ".inf",
+        "- item",
+        "-",
+        "key: value",
+        "a #comment",
+        "trailing:",
+        " padded ",
+        "#hash",
+        "*alias",
+        "&anchor",
+        "!tag",
+        "[list]",
+        "{map}",
+        "'single'",
+        "\"double\"",
+        "@at",
+        "`tick",
+        "%percent",
+        "|",
+        ">",
+        "line\nbreak",
+        "tab\there",
+        "bell\u{7}",
+        "---",
+        "...",
+        "?",
+        ",",
+    ];
+    for s in strings {
+        let yaml = facet_yaml::to_string(&s.to_string()).unwrap();
+        assert!(
+            yaml.starts_with('"'),
+            "{s:?} should be quoted, got {yaml:?}"
+        );
+        assert_eq!(
+            facet_yaml::from_str::<String>(&yaml).unwrap(),
+            s,
+            "{yaml:?}"
+        );
+    }
+
+    for s in [
+        "plain",
+        "a:b",
+        "apps/v1",
+        "-dash",
+        "with space",
+        "café",
+        "a#b",
+        "back\\slash",
+        "C#",
+    ] {
+        let yaml = facet_yaml::to_string(&s.to_string()).unwrap();
+        assert_eq!(yaml, format!("{s}\n"));
+        assert_eq!(facet_yaml::from_str::<String>(&yaml).unwrap(), s);
+    }
+}
+
+#[test]
+fn test_scalars_round_trip() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Scalars {
+        flag: bool,
+        small: i8,
+        big: u64,
+        huge: i128,
+        whole: f64,
+        half: f32,
+        low: f64,
+        c: char,
+        ip: std::net::IpAddr,
+    }
+
+    let value = Scalars {
+        flag: false,
+        small: -8,
+        big: u64::MAX,
+        huge: i128::MIN,
+        whole: 2.0,
+        half: 0.5,
+        low: f64::NEG_INFINITY,
+        c: '#',
+        ip: "::1".parse().unwrap(),
+    };
+    let yaml = facet_yaml::to_string(&value).unwrap();
+    assert!(yaml.contains("whole: 2.0\n"), "{yaml}");
+    assert!(yaml.contains("low: -.inf\n"), "{yaml}");
+    assert!(yaml.contains("ip: \"::1\"\n"), "{yaml}");
+    assert_eq!(facet_yaml::from_str::<Scalars>(&yaml).unwrap(), value);
+}
+
+#[test]
+fn test_nested_collections() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        grid: Vec<Vec<u8>>,
+        pairs: Vec<(String, u8)>,
+        empty: BTreeMap<String, u8>,
+        fixed: [Option<u8>; 2],
+    }
+
+    let value = Root {
+        grid: vec![vec![1, 2], vec![], vec![3]],
+        pairs: vec![("a".to_string(), 1)],
+        empty: BTreeMap::new(),
+        fixed: [Some(1), None],
+    };
+    let yaml = facet_yaml::to_string(&value).unwrap();
+    assert_eq!(
+        yaml,
+        "grid:\n  - - 1\n    - 2\n  - []\n  - - 3\npairs:\n  - - a\n    - 1\nempty: {}\nfixed:\n  - 1\n  - null\n"
+    );
+    assert_eq!(facet_yaml::from_str::<Root>(&yaml).unwrap(), value);
+}
+
+#[test]
+fn test_enums_round_trip() {
+    #[derive(Debug, Facet, PartialEq)]
+    #[repr(u8)]
+    enum External {
+        Unit,
+        Newtype(String),
+        Tuple(u8, u8),
+        Struct { x: i32 },
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    #[repr(u8)]
+    #[facet(tag = "type")]
+    enum Internal {
+        Unit,
+        Struct { x: i32 },
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    #[repr(u8)]
+    #[facet(tag = "t", content = "c")]
+    enum Adjacent {
+        Unit,
+        Tuple(u8, u8),
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    #[repr(u8)]
+    #[facet(untagged)]
+    enum Untagged {
+        Number(u32),
+        Text(String),
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        external: Vec<External>,
+        internal: Vec<Internal>,
+        adjacent: Vec<Adjacent>,
+        untagged: Vec<Untagged>,
+    }
+
+    let value = Root {
+        external: vec![
+            External::Unit,
+            External::Newtype("new".to_string()),
+            External::Tuple(1, 2),
+            External::Struct { x: -1 },
+        ],
+        internal: vec![Internal::Unit, Internal::Struct { x: 5 }],
+        adjacent: vec![Adjacent::Unit, Adjacent::Tuple(3, 4)],
+        untagged: vec![Untagged::Number(1), Untagged::Text("one".to_string())],
+    };
+    let yaml = facet_yaml::to_string(&value).unwrap();
+    assert_eq!(
+        yaml,
+        r#"external:
+  - Unit
+  - Newtype: new
+  - Tuple:
+      - 1
+      - 2
+  - Struct:
+      x: -1
+internal:
+  - type: Unit
+  - type: Struct
+    x: 5
+adjacent:
+  - t: Unit
+  - t: Tuple
+    c:
+      - 3
+      - 4
+untagged:
+  - 1
+  - one
+"#
+    );
+    assert_eq!(facet_yaml::from_str::<Root>(&yaml).unwrap(), value);
+}
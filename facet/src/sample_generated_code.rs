@@ -27,7 +27,7 @@ pub struct KitchenSinkStruct {
 static KITCHEN_SINK_STRUCT_SHAPE: &'static crate::Shape =
     <KitchenSinkStruct as crate::Facet>::SHAPE;
 #[automatically_derived]
-unsafe impl crate::Facet for KitchenSinkStruct {
+unsafe impl<'__facet> crate::Facet<'__facet> for KitchenSinkStruct {
     const SHAPE: &'static crate::Shape = &const {
         let fields: &'static [crate::Field] = &const {
             [
@@ -427,7 +427,7 @@ pub struct Point {
 #[used]
 static POINT_SHAPE: &'static crate::Shape = <Point as crate::Facet>::SHAPE;
 #[automatically_derived]
-unsafe impl crate::Facet for Point {
+unsafe impl<'__facet> crate::Facet<'__facet> for Point {
     const SHAPE: &'static crate::Shape = &const {
         let fields: &'static [crate::Field] = &const {
             [
@@ -823,7 +823,7 @@ pub enum KitchenSinkEnum {
 #[used]
 static KITCHEN_SINK_ENUM_SHAPE: &'static crate::Shape = <KitchenSinkEnum as crate::Facet>::SHAPE;
 #[automatically_derived]
-unsafe impl crate::Facet for KitchenSinkEnum {
+unsafe impl<'__facet> crate::Facet<'__facet> for KitchenSinkEnum {
     const SHAPE: &'static crate::Shape = &const {
         #[repr(C)]
         struct __ShadowKitchenSinkEnum_TupleVariantSimple {
@@ -1339,7 +1339,7 @@ pub enum SubEnum {
 #[used]
 static SUB_ENUM_SHAPE: &'static crate::Shape = <SubEnum as crate::Facet>::SHAPE;
 #[automatically_derived]
-unsafe impl crate::Facet for SubEnum {
+unsafe impl<'__facet> crate::Facet<'__facet> for SubEnum {
     const SHAPE: &'static crate::Shape = &const {
         #[repr(C)]
         struct __ShadowSubEnum_OptionB {
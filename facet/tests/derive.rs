@@ -288,7 +288,7 @@ fn tuple_struct_field_doc_comment() {
 #[test]
 fn record_struct_generic() {
     #[derive(Clone, Hash, PartialEq, Eq, ::facet::Facet)]
-    struct Blah<'a, T: Facet, const C: usize = 3>
+    struct Blah<'a, T: Facet<'a>, const C: usize = 3>
     where
         T: core::hash::Hash,
     {
@@ -300,7 +300,7 @@ fn record_struct_generic() {
 fn tuple_struct_generic() {
     #[derive(Clone, Hash, PartialEq, Eq, ::facet::Facet)]
     #[repr(transparent)]
-    struct Blah<'a, T: Facet, const C: usize = 3>(T, core::marker::PhantomData<&'a ()>)
+    struct Blah<'a, T: Facet<'a>, const C: usize = 3>(T, core::marker::PhantomData<&'a ()>)
     where
         T: core::hash::Hash;
 }
@@ -318,7 +318,7 @@ fn enum_generic() {
     #[allow(dead_code)]
     #[derive(Clone, Hash, PartialEq, Eq, ::facet::Facet)]
     #[repr(u8)]
-    enum E<'a, T: Facet, const C: usize = 3>
+    enum E<'a, T: Facet<'a>, const C: usize = 3>
     where
         T: core::hash::Hash,
     {
@@ -336,7 +336,7 @@ fn enum_generic() {
 //     #[allow(dead_code)]
 //     #[derive(Clone, Hash, PartialEq, Eq, ::facet::Facet)]
 //     #[repr(u8)]
-//     enum E<'a, T: Facet, const C: usize = 3>
+//     enum E<'a, T: Facet<'a>, const C: usize = 3>
 //     where
 //         T: core::hash::Hash,
 //     {